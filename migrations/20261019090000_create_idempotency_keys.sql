@@ -0,0 +1,20 @@
+-- Responses to POST requests that carried an Idempotency-Key header.
+-- A row without a status_code is still being processed. Keys belong to
+-- whoever sent them: `user:{id}`, or `anonymous`.
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    principal TEXT NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_method VARCHAR(10) NOT NULL,
+    request_path TEXT NOT NULL,
+    request_hash CHAR(64) NOT NULL,
+    status_code SMALLINT,
+    content_type TEXT,
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMPTZ,
+    PRIMARY KEY (principal, idempotency_key, request_method, request_path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys (created_at);
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_key
+    ON idempotency_keys (idempotency_key, request_method, request_path);
@@ -0,0 +1,3 @@
+-- Staff records need a modification timestamp to derive ETags from.
+ALTER TABLE staff_users
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
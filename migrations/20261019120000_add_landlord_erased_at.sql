@@ -0,0 +1,2 @@
+-- Set when a landlord's personal data has been anonymised on request.
+ALTER TABLE landlord_details ADD COLUMN IF NOT EXISTS erased_at TIMESTAMPTZ;
@@ -0,0 +1,28 @@
+-- Encrypted values are much longer than the plaintext they replace.
+ALTER TABLE landlords_bank_details
+    ALTER COLUMN account_number TYPE TEXT,
+    ALTER COLUMN sort_code TYPE TEXT,
+    ALTER COLUMN iban TYPE TEXT;
+ALTER TABLE landlords_lettings_management
+    ALTER COLUMN ni_number TYPE TEXT,
+    ALTER COLUMN unique_taxpayer_reference TYPE TEXT;
+
+-- Explicit grants on top of a user's access level.
+CREATE TABLE IF NOT EXISTS staff_permissions (
+    user_id UUID NOT NULL REFERENCES staff_users (user_id) ON DELETE CASCADE,
+    permission VARCHAR(64) NOT NULL,
+    granted_by UUID,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, permission)
+);
+
+CREATE TABLE IF NOT EXISTS audit_trail (
+    entry_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    description TEXT NOT NULL,
+    action_type VARCHAR(64) NOT NULL,
+    ip_address TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_trail_user_id ON audit_trail (user_id, timestamp);
@@ -0,0 +1,25 @@
+-- Insert-ordered number of each outbox event. Transactions can commit out of
+-- this order, so it is not a resume position on its own.
+ALTER TABLE outbox_events ADD COLUMN IF NOT EXISTS sequence BIGSERIAL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_outbox_events_sequence ON outbox_events (sequence);
+
+-- Transaction that wrote the event. Once it is older than every running
+-- transaction (`pg_snapshot_xmin`), no event can appear before it any more,
+-- so the live stream reads events in (xact_id, sequence) order and only up to
+-- that point. The pair is the SSE event id clients resume from.
+ALTER TABLE outbox_events
+    ADD COLUMN IF NOT EXISTS xact_id BIGINT NOT NULL DEFAULT pg_current_xact_id()::text::bigint;
+CREATE INDEX IF NOT EXISTS idx_outbox_events_position ON outbox_events (xact_id, sequence);
+
+-- Wakes the live update listener when an outbox event commits.
+CREATE OR REPLACE FUNCTION notify_outbox_event() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('outbox_events', NEW.sequence::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS outbox_events_notify ON outbox_events;
+CREATE TRIGGER outbox_events_notify
+    AFTER INSERT ON outbox_events
+    FOR EACH ROW EXECUTE FUNCTION notify_outbox_event();
@@ -0,0 +1,27 @@
+-- Addresses for email notifications. Staff without one are never emailed.
+ALTER TABLE staff_users ADD COLUMN IF NOT EXISTS email TEXT;
+ALTER TABLE viewing_details ADD COLUMN IF NOT EXISTS client_email TEXT;
+ALTER TABLE valuation_details ADD COLUMN IF NOT EXISTS client_email TEXT;
+
+CREATE TABLE IF NOT EXISTS email_queue (
+    id UUID PRIMARY KEY,
+    template VARCHAR(64) NOT NULL,
+    context JSONB NOT NULL DEFAULT '{}'::jsonb,
+    -- Staff recipients are resolved when the email is sent, so address and
+    -- email_notifi_en changes made in the meantime are honoured.
+    recipient_staff_id UUID REFERENCES staff_users(user_id) ON DELETE CASCADE,
+    recipient_email TEXT,
+    -- Stops the same notification from being queued twice.
+    dedupe_key TEXT UNIQUE,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (recipient_staff_id IS NOT NULL OR recipient_email IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_queue_pending
+    ON email_queue (next_attempt_at)
+    WHERE status = 'pending';
@@ -0,0 +1,71 @@
+CREATE TABLE IF NOT EXISTS jobs (
+    id UUID PRIMARY KEY,
+    kind VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    -- pending, running, succeeded, failed or cancelled.
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    -- At most one pending or running job may hold a given key.
+    unique_key TEXT,
+    schedule_name VARCHAR(64),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Instance running the job and when its claim runs out. A job whose
+    -- claim ran out is picked up again, e.g. after a crash.
+    locked_by TEXT,
+    locked_until TIMESTAMPTZ,
+    last_error TEXT,
+    result JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_unique_key
+    ON jobs (unique_key)
+    WHERE unique_key IS NOT NULL AND status IN ('pending', 'running');
+CREATE INDEX IF NOT EXISTS idx_jobs_due
+    ON jobs (run_at)
+    WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_jobs_running
+    ON jobs (locked_until)
+    WHERE status = 'running';
+CREATE INDEX IF NOT EXISTS idx_jobs_kind_created
+    ON jobs (kind, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS job_schedules (
+    name VARCHAR(64) PRIMARY KEY,
+    kind VARCHAR(64) NOT NULL,
+    -- A cron expression, or `every <seconds>s`.
+    schedule TEXT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    last_run_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Landlord registrations in progress; abandoned ones are removed by the
+-- registration_expiry job once `expires_at` has passed.
+DO $$ BEGIN
+    CREATE TYPE registration_step AS ENUM (
+        'basicinfo', 'contactdetails', 'address', 'bankdetails',
+        'lettingspreferences', 'landlorddocs', 'completed'
+    );
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS registration_progress (
+    registration_id UUID PRIMARY KEY,
+    landlord_id UUID,
+    current_step registration_step NOT NULL DEFAULT 'basicinfo',
+    registration_data JSONB NOT NULL DEFAULT '{}'::jsonb,
+    expires_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_registration_progress_expiry
+    ON registration_progress (expires_at)
+    WHERE completed_at IS NULL;
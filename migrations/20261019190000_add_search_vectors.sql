@@ -0,0 +1,42 @@
+-- Full-text search. Names and addresses use the `simple` configuration so
+-- they are matched as written; event text uses `english` for stemming.
+ALTER TABLE property_address ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(display_address, '')), 'A')
+        || setweight(to_tsvector('simple', coalesce(postcode, '')), 'A')
+        || setweight(to_tsvector('simple', coalesce(searchable_area, '') || ' ' || coalesce(town_city, '')), 'B')
+    ) STORED;
+CREATE INDEX IF NOT EXISTS idx_property_address_search
+    ON property_address USING GIN (search_vector);
+-- Postcode prefix lookups ignore spacing and case ("sw1a" finds "SW1A 1AA").
+CREATE INDEX IF NOT EXISTS idx_property_address_postcode_prefix
+    ON property_address (upper(replace(postcode, ' ', '')) text_pattern_ops);
+
+ALTER TABLE landlord_details ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(full_name, '') || ' ' || coalesce(company_name, '')), 'A')
+        || setweight(to_tsvector('simple', coalesce(email, '')), 'B')
+    ) STORED;
+CREATE INDEX IF NOT EXISTS idx_landlord_details_search
+    ON landlord_details USING GIN (search_vector);
+-- Phone prefix lookups compare digits only.
+CREATE INDEX IF NOT EXISTS idx_landlord_details_phone_prefix
+    ON landlord_details (regexp_replace(phone_nr, '\D', '', 'g') text_pattern_ops);
+
+ALTER TABLE staff_users ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(name, '') || ' ' || coalesce(username, '')), 'A')
+        || setweight(to_tsvector('simple', coalesce(email, '')), 'B')
+    ) STORED;
+CREATE INDEX IF NOT EXISTS idx_staff_users_search
+    ON staff_users USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_staff_users_phone_prefix
+    ON staff_users (regexp_replace(coalesce(mob_phone, ''), '\D', '', 'g') text_pattern_ops);
+
+ALTER TABLE events ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A')
+        || setweight(to_tsvector('english', coalesce(description, '')), 'B')
+    ) STORED;
+CREATE INDEX IF NOT EXISTS idx_events_search
+    ON events USING GIN (search_vector);
@@ -0,0 +1,106 @@
+-- An organisation is the agency, or a partner agency running the system
+-- under its own brand. Staff, landlords, properties and diary events each
+-- belong to one of its branches.
+CREATE TABLE IF NOT EXISTS organisations (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS branches (
+    id UUID PRIMARY KEY,
+    organisation_id UUID NOT NULL REFERENCES organisations (id),
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (organisation_id, name)
+);
+
+-- Everything that existed before branches belongs to one head office.
+INSERT INTO organisations (id, name)
+VALUES ('00000000-0000-0000-0000-000000000001', 'Default organisation')
+ON CONFLICT DO NOTHING;
+INSERT INTO branches (id, organisation_id, name)
+VALUES ('00000000-0000-0000-0000-000000000002', '00000000-0000-0000-0000-000000000001', 'Head Office')
+ON CONFLICT DO NOTHING;
+
+-- Branches a staff member works for besides their own.
+CREATE TABLE IF NOT EXISTS staff_branches (
+    staff_id UUID NOT NULL REFERENCES staff_users (user_id) ON DELETE CASCADE,
+    branch_id UUID NOT NULL REFERENCES branches (id) ON DELETE CASCADE,
+    granted_by UUID,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (staff_id, branch_id)
+);
+
+-- The server sets both settings on every connection it hands out.
+-- `app.branch_ids` is `*` for every branch, or an array literal of the
+-- branches the caller may see; `app.home_branch_id` is where rows the caller
+-- creates go. Connections that never set them (migrations, psql) are not
+-- restricted.
+CREATE OR REPLACE FUNCTION branch_visible(branch UUID) RETURNS BOOLEAN
+LANGUAGE sql STABLE AS $$
+    SELECT CASE
+        WHEN COALESCE(current_setting('app.branch_ids', TRUE), '') IN ('', '*') THEN TRUE
+        ELSE COALESCE(branch = ANY (current_setting('app.branch_ids', TRUE)::uuid[]), FALSE)
+    END
+$$;
+
+CREATE OR REPLACE FUNCTION current_home_branch() RETURNS UUID
+LANGUAGE sql STABLE AS $$
+    SELECT COALESCE(
+        NULLIF(current_setting('app.home_branch_id', TRUE), '')::uuid,
+        '00000000-0000-0000-0000-000000000002'::uuid
+    )
+$$;
+
+ALTER TABLE staff_users ADD COLUMN IF NOT EXISTS branch_id UUID REFERENCES branches (id);
+ALTER TABLE landlord_details ADD COLUMN IF NOT EXISTS branch_id UUID REFERENCES branches (id);
+ALTER TABLE property_core ADD COLUMN IF NOT EXISTS branch_id UUID REFERENCES branches (id);
+ALTER TABLE events ADD COLUMN IF NOT EXISTS branch_id UUID REFERENCES branches (id);
+
+UPDATE staff_users SET branch_id = '00000000-0000-0000-0000-000000000002' WHERE branch_id IS NULL;
+UPDATE landlord_details SET branch_id = '00000000-0000-0000-0000-000000000002' WHERE branch_id IS NULL;
+UPDATE property_core SET branch_id = '00000000-0000-0000-0000-000000000002' WHERE branch_id IS NULL;
+UPDATE events SET branch_id = '00000000-0000-0000-0000-000000000002' WHERE branch_id IS NULL;
+
+ALTER TABLE staff_users ALTER COLUMN branch_id SET DEFAULT current_home_branch(), ALTER COLUMN branch_id SET NOT NULL;
+ALTER TABLE landlord_details ALTER COLUMN branch_id SET DEFAULT current_home_branch(), ALTER COLUMN branch_id SET NOT NULL;
+ALTER TABLE property_core ALTER COLUMN branch_id SET DEFAULT current_home_branch(), ALTER COLUMN branch_id SET NOT NULL;
+ALTER TABLE events ALTER COLUMN branch_id SET DEFAULT current_home_branch(), ALTER COLUMN branch_id SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_staff_users_branch_id ON staff_users (branch_id);
+CREATE INDEX IF NOT EXISTS idx_landlord_details_branch_id ON landlord_details (branch_id);
+CREATE INDEX IF NOT EXISTS idx_property_core_branch_id ON property_core (branch_id);
+CREATE INDEX IF NOT EXISTS idx_events_branch_id ON events (branch_id);
+
+-- FORCE so the policies also apply when the server connects as the owner.
+ALTER TABLE staff_users ENABLE ROW LEVEL SECURITY;
+ALTER TABLE staff_users FORCE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS branch_isolation ON staff_users;
+CREATE POLICY branch_isolation ON staff_users
+    USING (branch_visible(branch_id)) WITH CHECK (branch_visible(branch_id));
+
+ALTER TABLE landlord_details ENABLE ROW LEVEL SECURITY;
+ALTER TABLE landlord_details FORCE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS branch_isolation ON landlord_details;
+CREATE POLICY branch_isolation ON landlord_details
+    USING (branch_visible(branch_id)) WITH CHECK (branch_visible(branch_id));
+
+ALTER TABLE property_core ENABLE ROW LEVEL SECURITY;
+ALTER TABLE property_core FORCE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS branch_isolation ON property_core;
+CREATE POLICY branch_isolation ON property_core
+    USING (branch_visible(branch_id)) WITH CHECK (branch_visible(branch_id));
+
+ALTER TABLE events ENABLE ROW LEVEL SECURITY;
+ALTER TABLE events FORCE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS branch_isolation ON events;
+CREATE POLICY branch_isolation ON events
+    USING (branch_visible(branch_id)) WITH CHECK (branch_visible(branch_id));
+
+-- Existing admins run the agency that owns the system, so they keep seeing
+-- every organisation's branches.
+INSERT INTO staff_permissions (user_id, permission)
+SELECT user_id, 'manage_organisations' FROM staff_users
+WHERE acc_level::text = 'admin' AND deleted_at IS NULL
+ON CONFLICT DO NOTHING;
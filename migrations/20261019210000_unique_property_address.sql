@@ -0,0 +1,9 @@
+-- A property has at most one address. Where there are several, keep the one
+-- updated most recently.
+DELETE FROM property_address a
+USING property_address b
+WHERE a.property_id = b.property_id
+  AND (COALESCE(a.updated_at, '-infinity'), a.address_id) < (COALESCE(b.updated_at, '-infinity'), b.address_id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_property_address_property_id
+    ON property_address (property_id);
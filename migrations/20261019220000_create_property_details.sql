@@ -0,0 +1,119 @@
+-- Specifications, rental info, letting services, keys and certificates of a
+-- property. Specifications and rental info are one row per property.
+DO $$ BEGIN
+    CREATE TYPE furnished_status AS ENUM ('unfurnished', 'partfurnished', 'fullyfurnished');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE letting_service_type AS ENUM (
+        'tenantfindonly', 'fullmanagement', 'rentcollection', 'rentguarantee', 'maintenance',
+        'propertyletting', 'propertymanagement', 'evictionservice', 'tenantscreening', 'marketappraisal'
+    );
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE certificate_type AS ENUM (
+        'electricalsafetycertificate', 'gassafetycertificate', 'energyperformancecertificate',
+        'firesafetycertificate', 'watersafetycertificate', 'buildingregulationcompliancecertificate',
+        'pattestingcertificate', 'fensacertificate', 'asbestossurveycertificate',
+        'structuralsafetycertificate', 'landlordinsurancecertificate', 'healthandsafetyriskassessment',
+        'boilerservicecertificate', 'chimneysafetycertificate', 'foodhygienecertificate',
+        'wastecarriercertificate', 'smokeandco2detectorcompliancecertificate'
+    );
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE responsibility_type AS ENUM (
+        'landlord', 'propertymanager', 'manager', 'developer', 'broker', 'salesagent', 'legal', 'investor'
+    );
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS property_specifications (
+    spec_id UUID PRIMARY KEY,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    bedrooms INTEGER NOT NULL DEFAULT 0,
+    receptions INTEGER NOT NULL DEFAULT 0,
+    bathrooms INTEGER NOT NULL DEFAULT 0,
+    is_hmo BOOLEAN NOT NULL DEFAULT FALSE,
+    furnished_status furnished_status NOT NULL,
+    floor_area_size DOUBLE PRECISION,
+    floor_area_unit TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS property_rental_info (
+    rental_info_id UUID PRIMARY KEY,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    rent_amount DOUBLE PRECISION,
+    rent_poa BOOLEAN NOT NULL DEFAULT FALSE,
+    valuation_rent_min DOUBLE PRECISION,
+    valuation_rent_max DOUBLE PRECISION,
+    minimum_rent DOUBLE PRECISION,
+    deposit DOUBLE PRECISION,
+    holding_deposit DOUBLE PRECISION,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS property_services (
+    service_id UUID PRIMARY KEY,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    service_type letting_service_type NOT NULL,
+    letting_fee_percentage DOUBLE PRECISION,
+    letting_fee_amount DOUBLE PRECISION,
+    management_fee_percentage DOUBLE PRECISION,
+    management_fee_amount DOUBLE PRECISION,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+-- Edits need a version to check `If-Match` against.
+ALTER TABLE property_services ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+CREATE TABLE IF NOT EXISTS property_keys_security (
+    key_id UUID PRIMARY KEY,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    key_code TEXT NOT NULL,
+    security_code TEXT,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS property_certificates (
+    certificate_id UUID PRIMARY KEY,
+    property_id UUID REFERENCES property_core (property_id),
+    certificate_type certificate_type,
+    expiry_date DATE
+);
+ALTER TABLE property_certificates
+    ADD COLUMN IF NOT EXISTS responsibility_name responsibility_type,
+    ADD COLUMN IF NOT EXISTS start_date DATE,
+    ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    -- Expiry date the last certificate.expiring event was raised for, so each
+    -- expiry is only announced once.
+    ADD COLUMN IF NOT EXISTS expiry_notified_for DATE;
+
+-- One set of specifications and rental info per property; keep the most
+-- recently updated where there are several.
+DELETE FROM property_specifications a
+USING property_specifications b
+WHERE a.property_id = b.property_id
+  AND (a.updated_at, a.spec_id) < (b.updated_at, b.spec_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_property_specifications_property_id
+    ON property_specifications (property_id);
+
+DELETE FROM property_rental_info a
+USING property_rental_info b
+WHERE a.property_id = b.property_id
+  AND (a.updated_at, a.rental_info_id) < (b.updated_at, b.rental_info_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_property_rental_info_property_id
+    ON property_rental_info (property_id);
+
+CREATE INDEX IF NOT EXISTS idx_property_services_property_id ON property_services (property_id);
+CREATE INDEX IF NOT EXISTS idx_property_keys_security_property_id ON property_keys_security (property_id);
+CREATE INDEX IF NOT EXISTS idx_property_certificates_property_id ON property_certificates (property_id);
@@ -0,0 +1,7 @@
+-- Supports the filters and sorts of GET /api/v1/properties/search.
+CREATE INDEX IF NOT EXISTS property_core_status_idx ON property_core (status) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS property_core_landlord_id_idx ON property_core (landlord_id) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS property_core_staff_assigned_idx ON property_core (staff_assigned) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS property_core_date_available_idx ON property_core (date_available) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS property_rental_info_rent_amount_idx ON property_rental_info (rent_amount);
+CREATE INDEX IF NOT EXISTS property_address_town_city_idx ON property_address (lower(town_city));
@@ -0,0 +1,27 @@
+-- The end of the current tenancy, set when a property is let and cleared when
+-- it leaves that status.
+ALTER TABLE property_core ADD COLUMN IF NOT EXISTS tenancy_end_date DATE;
+
+CREATE TABLE IF NOT EXISTS property_status_history (
+    history_id UUID PRIMARY KEY,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    -- NULL for the status a property was created with.
+    previous_status property_status,
+    status property_status NOT NULL,
+    reason TEXT,
+    tenancy_end_date DATE,
+    -- NULL when the change was not made by a logged-in staff member.
+    changed_by UUID,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_property_status_history_property_id
+    ON property_status_history (property_id, changed_at);
+
+-- Start the history of existing properties with the status they have now.
+INSERT INTO property_status_history (history_id, property_id, previous_status, status, changed_at)
+SELECT gen_random_uuid(), p.property_id, NULL, p.status, p.created_at
+FROM property_core p
+WHERE NOT EXISTS (
+    SELECT 1 FROM property_status_history h WHERE h.property_id = p.property_id
+);
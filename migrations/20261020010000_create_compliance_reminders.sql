@@ -0,0 +1,16 @@
+-- One row per compliance reminder raised as a diary note, so each problem
+-- with a property's certificates is raised once.
+CREATE TABLE IF NOT EXISTS compliance_reminders (
+    reminder_id UUID PRIMARY KEY,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    certificate_type certificate_type NOT NULL,
+    -- missing, expired or expiring
+    status TEXT NOT NULL,
+    -- NULL for a missing certificate.
+    expiry_date DATE,
+    event_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_compliance_reminders_unique
+    ON compliance_reminders (property_id, certificate_type, status, COALESCE(expiry_date, 'infinity'::date));
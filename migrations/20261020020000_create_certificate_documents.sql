@@ -0,0 +1,28 @@
+-- The files behind a property's certificates. Renewing a certificate uploads
+-- a new version, and earlier versions are kept as its history.
+CREATE TABLE IF NOT EXISTS certificate_documents (
+    document_id UUID PRIMARY KEY,
+    certificate_id UUID NOT NULL REFERENCES property_certificates (certificate_id) ON DELETE CASCADE,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    -- 1 for the first upload, counting up with each renewal.
+    version INTEGER NOT NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    -- Hex SHA-256 of the file, to spot the same file uploaded twice.
+    sha256 TEXT NOT NULL,
+    -- Where the file is kept in document storage.
+    storage_key TEXT NOT NULL,
+    -- The certificate's dates this version was uploaded with.
+    start_date DATE NOT NULL,
+    expiry_date DATE,
+    uploaded_by UUID NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Set once a staff member has checked the document.
+    verified_by UUID,
+    verified_at TIMESTAMPTZ,
+    UNIQUE (certificate_id, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_certificate_documents_property_id
+    ON certificate_documents (property_id);
@@ -0,0 +1,36 @@
+-- One row per uploaded property photo, tracking it through processing.
+DO $$ BEGIN
+    CREATE TYPE photo_status AS ENUM ('pending', 'ready', 'failed');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS property_photos (
+    photo_id UUID PRIMARY KEY,
+    property_id UUID NOT NULL REFERENCES property_core (property_id),
+    -- The photos uploaded in the same request share an upload id.
+    upload_id UUID NOT NULL,
+    status photo_status NOT NULL DEFAULT 'pending',
+    file_name TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    -- The upload waiting to be processed, on the disk of the instance that
+    -- received it. Cleared once processing finishes.
+    temp_path TEXT,
+    -- The processed image in document storage, once ready.
+    storage_key TEXT,
+    content_type TEXT,
+    width INTEGER,
+    height INTEGER,
+    -- Why processing failed.
+    error TEXT,
+    uploaded_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    processed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_property_photos_property_id
+    ON property_photos (property_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_property_photos_upload_id
+    ON property_photos (upload_id);
+CREATE INDEX IF NOT EXISTS idx_property_photos_pending
+    ON property_photos (created_at)
+    WHERE status = 'pending';
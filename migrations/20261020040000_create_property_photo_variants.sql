@@ -0,0 +1,33 @@
+-- The renditions of each photo, in several widths and formats.
+DO $$ BEGIN
+    CREATE TYPE photo_format AS ENUM ('avif', 'webp', 'jpeg');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS property_photo_variants (
+    photo_id UUID NOT NULL REFERENCES property_photos (photo_id) ON DELETE CASCADE,
+    format photo_format NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    -- Where the rendition is in document storage.
+    storage_key TEXT NOT NULL,
+    PRIMARY KEY (photo_id, format, width)
+);
+
+-- Photos processed before renditions keep their one AVIF as their only
+-- variant.
+DO $$ BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'property_photos' AND column_name = 'storage_key'
+    ) THEN
+        INSERT INTO property_photo_variants (photo_id, format, width, height, storage_key)
+        SELECT photo_id, 'avif', width, height, storage_key
+        FROM property_photos
+        WHERE status = 'ready' AND storage_key IS NOT NULL
+        ON CONFLICT DO NOTHING;
+    END IF;
+END $$;
+
+ALTER TABLE property_photos DROP COLUMN IF EXISTS storage_key;
+ALTER TABLE property_photos DROP COLUMN IF EXISTS content_type;
@@ -0,0 +1,31 @@
+-- Webhook subscriptions belong to the organisation of the staff member who
+-- created them, and only receive events about that organisation's records.
+ALTER TABLE webhook_subscriptions ADD COLUMN IF NOT EXISTS organisation_id UUID REFERENCES organisations (id);
+
+UPDATE webhook_subscriptions s
+SET organisation_id = COALESCE(
+    (SELECT b.organisation_id FROM staff_users u JOIN branches b ON b.id = u.branch_id
+     WHERE u.user_id = s.created_by),
+    '00000000-0000-0000-0000-000000000001'
+)
+WHERE organisation_id IS NULL;
+
+ALTER TABLE webhook_subscriptions ALTER COLUMN organisation_id SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_subscriptions_organisation_id
+    ON webhook_subscriptions (organisation_id);
+
+-- Staff only see and manage subscriptions of organisations they can see a
+-- branch of.
+ALTER TABLE webhook_subscriptions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_subscriptions FORCE ROW LEVEL SECURITY;
+DROP POLICY IF EXISTS organisation_isolation ON webhook_subscriptions;
+CREATE POLICY organisation_isolation ON webhook_subscriptions
+    USING (EXISTS (
+        SELECT 1 FROM branches b
+        WHERE b.organisation_id = webhook_subscriptions.organisation_id AND branch_visible(b.id)
+    ))
+    WITH CHECK (EXISTS (
+        SELECT 1 FROM branches b
+        WHERE b.organisation_id = webhook_subscriptions.organisation_id AND branch_visible(b.id)
+    ));
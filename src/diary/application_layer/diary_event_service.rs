@@ -1,12 +1,21 @@
 use crate::diary::domain_layer::diary_event_types::{
     CreateEventRequest, DateQueryParams, Event, EventDetails,
 };
-use crate::diary::infrastructure_layer::diary_event_repo::EventRepository;
+use crate::diary::infrastructure_layer::diary_event_repo::{CustomErrors, EventRepository};
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
 use crate::AppState;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use chrono::NaiveDate;
+use serde_json::json;
 use uuid::Uuid;
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    tag = "diary events",
+    responses((status = 200, description = "All diary events, without their details", body = [Event]))
+)]
 pub async fn get_all_events(state: web::Data<AppState>) -> impl Responder {
     let repo = EventRepository::new();
     match repo.get_all_events(state.into_inner()).await {
@@ -15,20 +24,38 @@ pub async fn get_all_events(state: web::Data<AppState>) -> impl Responder {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/events/{event_id}",
+    tag = "diary events",
+    params(("event_id" = Uuid, Path, description = "Event id")),
+    responses(
+        (status = 200, description = "The event", body = Event, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+    )
+)]
 pub async fn get_event_by_id(
     state: web::Data<AppState>,
     event_id: web::Path<Uuid>,
+    req: HttpRequest,
 ) -> impl Responder {
     let repo: EventRepository = EventRepository::new();
     match repo
         .get_event_by_id(state.into_inner(), event_id.into_inner())
         .await
     {
-        Ok(event) => HttpResponse::Ok().json(event),
+        Ok(event) => conditional_request::conditional_get(&req, event.updated_at, &event),
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/events/users/{user_id}",
+    tag = "diary events",
+    params(("user_id" = Uuid, Path, description = "Staff user id")),
+    responses((status = 200, description = "The user's events as `[event, details]` pairs", body = [(Event, EventDetails)]))
+)]
 pub async fn get_event_by_user_id(
     state: web::Data<AppState>,
     user_id: web::Path<Uuid>,
@@ -44,6 +71,13 @@ pub async fn get_event_by_user_id(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/events/diary/{user_id}/events",
+    tag = "diary events",
+    params(("user_id" = Uuid, Path, description = "Staff user id"), DateQueryParams),
+    responses((status = 200, description = "The user's events in the date range as `[event, details]` pairs", body = [(Event, EventDetails)]))
+)]
 pub async fn get_event_by_user_id_with_dates(
     state: web::Data<AppState>,
     user_id: web::Path<Uuid>,
@@ -64,6 +98,17 @@ pub async fn get_event_by_user_id_with_dates(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/events",
+    tag = "diary events",
+    params(IdempotencyHeader),
+    responses(
+        (status = 201, description = "The created event", body = Event),
+        (status = 409, description = "A request with this `Idempotency-Key` is still in progress", body = ErrorResponse),
+        (status = 422, description = "The `Idempotency-Key` was used with a different body", body = ErrorResponse),
+    )
+)]
 pub async fn create_event(
     state: web::Data<AppState>,
     new_event_request: web::Json<CreateEventRequest>,
@@ -79,27 +124,59 @@ pub async fn create_event(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/events/{event_id}",
+    tag = "diary events",
+    params(("event_id" = Uuid, Path, description = "Event id"), IfMatchHeader),
+    responses(
+        (status = 200, description = "The updated event", body = Event, headers(("ETag" = String))),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 412, description = "The event changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
 pub async fn update_event(
     state: web::Data<AppState>,
     event_id: web::Path<Uuid>,
-    updated_event: web::Json<Event>,
-    updated_details: web::Json<EventDetails>,
+    update_request: web::Json<CreateEventRequest>,
+    req: HttpRequest,
 ) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
     let repo = EventRepository::new();
+    let request = update_request.into_inner();
     match repo
         .update_event(
             state.into_inner(),
             event_id.into_inner(),
-            updated_event.into_inner(),
-            updated_details.into_inner(),
+            request.event,
+            request.details,
+            expected_updated_at,
         )
         .await
     {
-        Ok(event) => HttpResponse::Ok().json(event),
+        Ok(event) => conditional_request::updated(event.updated_at, &event),
+        Err(CustomErrors::PreconditionFailed) => conditional_request::precondition_failed(),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Event not found"}))
+        }
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/events/{event_id}",
+    tag = "diary events",
+    params(("event_id" = Uuid, Path, description = "Event id")),
+    responses(
+        (status = 204, description = "Event soft-deleted"),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+    )
+)]
 pub async fn delete_event(state: web::Data<AppState>, event_id: web::Path<Uuid>) -> impl Responder {
     let repo = EventRepository::new();
     match repo
@@ -107,6 +184,36 @@ pub async fn delete_event(state: web::Data<AppState>, event_id: web::Path<Uuid>)
         .await
     {
         Ok(_) => HttpResponse::NoContent().finish(),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Event not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/events/{event_id}/restore",
+    tag = "diary events",
+    params(("event_id" = Uuid, Path, description = "Event id")),
+    responses(
+        (status = 200, description = "The restored event", body = Event),
+        (status = 404, description = "Deleted event not found", body = ErrorResponse),
+    )
+)]
+pub async fn restore_event(
+    state: web::Data<AppState>,
+    event_id: web::Path<Uuid>,
+) -> impl Responder {
+    let repo = EventRepository::new();
+    match repo
+        .restore_event(state.into_inner(), event_id.into_inner())
+        .await
+    {
+        Ok(event) => HttpResponse::Ok().json(event),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Deleted event not found"}))
+        }
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
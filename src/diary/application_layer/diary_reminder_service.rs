@@ -0,0 +1,83 @@
+use chrono::{Duration, Local};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+
+use crate::diary::infrastructure_layer::diary_event_repo::EventRepository;
+use crate::shared::{
+    domain_layer::email::{EmailRecipient, EmailTemplate, QueuedEmail},
+    infrastructure_layer::{email_queue_repository::EmailQueueRepository, job_runner::JobResult},
+};
+
+/// Longest reminder lead time looked for.
+const LOOKAHEAD_DAYS: i64 = 7;
+
+/// Job that queues reminder emails for upcoming diary events once their
+/// reminder is due. Diary times are office-local, so it uses the server's clock.
+pub async fn reminder_scan_job(pool: Pool<Postgres>) -> JobResult {
+    match queue_due_reminders(&pool).await {
+        Ok(queued) => {
+            if queued > 0 {
+                log::info!("Queued {} event reminders", queued);
+            }
+            Ok(Some(json!({ "queued": queued })))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Each reminder is keyed on the event and its start, so rescans and other
+/// instances do not queue it twice, while a rescheduled event is reminded again.
+async fn queue_due_reminders(pool: &Pool<Postgres>) -> Result<usize, sqlx::Error> {
+    let now = Local::now().naive_local();
+    let candidates = EventRepository::reminder_candidates(
+        pool,
+        now.date(),
+        now.date() + Duration::days(LOOKAHEAD_DAYS),
+    )
+    .await?;
+
+    let reminders: Vec<QueuedEmail> = candidates
+        .into_iter()
+        .filter(|candidate| {
+            let starts_at = candidate.starts_at();
+            candidate
+                .lead_time()
+                .is_some_and(|lead| starts_at - lead <= now && now < starts_at)
+        })
+        .map(|candidate| {
+            QueuedEmail::new(
+                EmailTemplate::EventReminder,
+                EmailRecipient::Staff(candidate.created_by),
+                json!({
+                    "event_type": format!("{:?}", candidate.event_type).to_lowercase(),
+                    "title": candidate.title.clone().unwrap_or_else(|| format!("{:?}", candidate.event_type)),
+                    "description": candidate.description,
+                    "date": candidate.date.format("%d/%m/%Y").to_string(),
+                    "start_time": candidate.start_time.format("%H:%M").to_string(),
+                    "end_time": candidate.end_time.format("%H:%M").to_string(),
+                    "property_id": candidate.property_id,
+                    "client_name": candidate.client_name,
+                    "contact_number": candidate.contact_number,
+                }),
+            )
+            .dedupe_key(format!(
+                "reminder:{}:{}",
+                candidate.event_id,
+                candidate.starts_at().format("%Y-%m-%dT%H:%M")
+            ))
+        })
+        .collect();
+    if reminders.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut queued = 0;
+    for reminder in &reminders {
+        if EmailQueueRepository::enqueue(&mut tx, reminder).await? {
+            queued += 1;
+        }
+    }
+    tx.commit().await?;
+    Ok(queued)
+}
@@ -1,9 +1,20 @@
 use crate::diary::domain_layer::diary_settings::DiarySettings;
-use crate::diary::infrastructure_layer::diary_settings_repo::DiarySettingsRepository;
+use crate::diary::infrastructure_layer::diary_settings_repo::{
+    CustomErrors, DiarySettingsRepository,
+};
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
 use crate::AppState;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
 use uuid::Uuid;
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/diary-settings",
+    tag = "diary settings",
+    responses((status = 200, description = "Diary settings of every staff user", body = [DiarySettings]))
+)]
 pub async fn get_all_diary_settings(state: web::Data<AppState>) -> impl Responder {
     let repo = DiarySettingsRepository::new();
     match repo.get_all_diary_settings(state.into_inner()).await {
@@ -12,20 +23,42 @@ pub async fn get_all_diary_settings(state: web::Data<AppState>) -> impl Responde
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/diary-settings/{id}",
+    tag = "diary settings",
+    params(("id" = Uuid, Path, description = "Staff user id; unlike PUT and DELETE, not the settings id")),
+    responses(
+        (status = 200, description = "The user's diary settings", body = DiarySettings, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+    )
+)]
 pub async fn get_diary_settings_by_id(
     state: web::Data<AppState>,
     staff_id: web::Path<Uuid>,
+    req: HttpRequest,
 ) -> impl Responder {
     let repo = DiarySettingsRepository::new();
     match repo
         .get_diary_settings_by_id(state.into_inner(), staff_id.into_inner())
         .await
     {
-        Ok(settings) => HttpResponse::Ok().json(settings),
+        Ok(settings) => conditional_request::conditional_get(&req, settings.updated_at, &settings),
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/diary-settings",
+    tag = "diary settings",
+    params(IdempotencyHeader),
+    responses(
+        (status = 201, description = "The created diary settings", body = DiarySettings),
+        (status = 409, description = "A request with this `Idempotency-Key` is still in progress", body = ErrorResponse),
+        (status = 422, description = "The `Idempotency-Key` was used with a different body", body = ErrorResponse),
+    )
+)]
 pub async fn create_diary_settings(
     state: web::Data<AppState>,
     new_settings: web::Json<DiarySettings>,
@@ -40,25 +73,54 @@ pub async fn create_diary_settings(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/diary-settings/{id}",
+    tag = "diary settings",
+    params(("id" = Uuid, Path, description = "Diary settings id"), IfMatchHeader),
+    responses(
+        (status = 200, description = "The updated diary settings", body = DiarySettings, headers(("ETag" = String))),
+        (status = 404, description = "Diary settings not found", body = ErrorResponse),
+        (status = 412, description = "The settings changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
 pub async fn update_diary_settings(
     state: web::Data<AppState>,
     diary_id: web::Path<Uuid>,
     updated_settings: web::Json<DiarySettings>,
+    req: HttpRequest,
 ) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
     let repo = DiarySettingsRepository::new();
     match repo
         .update_diary_settings(
             state.into_inner(),
             diary_id.into_inner(),
             updated_settings.into_inner(),
+            expected_updated_at,
         )
         .await
     {
-        Ok(settings) => HttpResponse::Ok().json(settings),
+        Ok(settings) => conditional_request::updated(settings.updated_at, &settings),
+        Err(CustomErrors::PreconditionFailed) => conditional_request::precondition_failed(),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Diary settings not found"}))
+        }
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/diary-settings/{id}",
+    tag = "diary settings",
+    params(("id" = Uuid, Path, description = "Diary settings id")),
+    responses((status = 204, description = "Diary settings deleted"))
+)]
 pub async fn delete_diary_settings(
     state: web::Data<AppState>,
     diary_id: web::Path<Uuid>,
@@ -1,2 +1,3 @@
 pub mod diary_event_service;
 pub mod diary_settings_service;
+pub mod diary_reminder_service;
@@ -1,15 +1,16 @@
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use time::OffsetDateTime;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct CreateEventRequest {
     pub event: Event,
     pub details: EventDetails,
 }
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct Event {
     pub id: Option<Uuid>,
     pub external_id: String,
@@ -22,9 +23,13 @@ pub struct Event {
     pub created_by: Uuid,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// Branch of the staff member who created the event.
+    #[sqlx(default)]
+    #[schema(read_only)]
+    pub branch_id: Option<Uuid>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "event_type", rename_all = "lowercase")]
 pub enum EventType {
     Viewing,
@@ -41,7 +46,7 @@ pub enum EventType {
     Training,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, ToSchema)]
 #[serde(tag = "event_type", content = "data")]
 pub enum EventDetails {
     Viewing(ViewingDetails),
@@ -58,7 +63,7 @@ pub enum EventDetails {
     Note(NoteDetails),
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct StaffHolidayDetails {
     pub staff_member: Uuid,
     pub holiday_type: Option<String>,
@@ -69,7 +74,7 @@ pub struct StaffHolidayDetails {
     pub remaining_days: Option<f64>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct TrainingDetails {
     pub training_title: String,
     pub location: Option<String>,
@@ -83,7 +88,7 @@ pub struct TrainingDetails {
     pub attendance_confirmed: Option<bool>,
     pub certificates_issued: Option<bool>,
 }
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct PublicHolidayDetails {
     pub holiday_name: String,
     pub region: Option<String>,
@@ -94,7 +99,7 @@ pub struct PublicHolidayDetails {
     pub custom_working_hours: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct NoteDetails {
     pub note_type: String,
     pub assigned_staff: Option<Vec<Uuid>>,
@@ -108,16 +113,18 @@ pub struct NoteDetails {
     pub completed_by: Option<Uuid>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct ViewingDetails {
     pub property_id: String,
     pub client_name: String,
     pub contact_number: String,
     pub viewing_type: String,
     pub notification_length: Option<String>,
+    /// Booking confirmations are sent here when given.
+    pub client_email: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct AppointmentDetails {
     pub location: Option<String>,
     pub property_id: Option<String>,
@@ -127,50 +134,117 @@ pub struct AppointmentDetails {
     pub recurrence_pattern: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct InspectionDetails {
     pub property_id: String,
     pub contractor: String,
     pub notification: Option<bool>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct LeaveDetails {
     pub staff_member: String,
     pub is_half_day: Option<bool>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct MeetingDetails {
     pub location: Option<String>,
     pub is_recurring: Option<bool>,
     pub recurrence_pattern: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct ValuationDetails {
     pub property_id: String,
     pub client_name: String,
     pub contact_number: String,
     pub notification: Option<bool>,
+    /// Booking confirmations are sent here when given.
+    pub client_email: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct CallbackDetails {
     pub contact_name: String,
     pub phone_number: String,
     pub is_urgent: Option<bool>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, FromRow)]
+#[derive(Clone, Serialize, Deserialize, Debug, FromRow, ToSchema)]
 pub struct MaintenanceDetails {
     pub property_id: String,
     pub contractor: String,
     pub notification: Option<bool>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DateQueryParams {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
 }
+
+/// Staff an event concerns, used to scope live updates and pop-ups.
+#[derive(Clone, Debug)]
+pub struct EventAudience {
+    pub branch_id: Uuid,
+    pub is_private: bool,
+    pub involved: Vec<Uuid>,
+    /// Involved staff who have pop-up notifications switched on.
+    pub popup_recipients: Vec<Uuid>,
+}
+
+/// Reminder used for inspections, valuations, appointments and maintenance
+/// visits that have `notification` switched on.
+pub const DEFAULT_REMINDER_MINUTES: i64 = 60;
+
+/// An upcoming event that asked for a reminder.
+#[derive(Clone, Debug, FromRow)]
+pub struct ReminderCandidate {
+    pub event_id: Uuid,
+    pub event_type: EventType,
+    pub date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub created_by: Uuid,
+    pub property_id: Option<String>,
+    pub client_name: Option<String>,
+    pub contact_number: Option<String>,
+    pub notification_length: Option<String>,
+    pub notification: bool,
+}
+
+impl ReminderCandidate {
+    pub fn starts_at(&self) -> NaiveDateTime {
+        self.date.and_time(self.start_time)
+    }
+
+    /// How long before the start the reminder is due. Viewings use their
+    /// `notification_length`, everything else the default.
+    pub fn lead_time(&self) -> Option<Duration> {
+        match &self.notification_length {
+            Some(length) => parse_notification_length(length),
+            None if self.notification => Some(Duration::minutes(DEFAULT_REMINDER_MINUTES)),
+            None => None,
+        }
+    }
+}
+
+/// Parses lengths such as `30m`, `2h` or `1d`. A bare number is minutes.
+pub fn parse_notification_length(value: &str) -> Option<Duration> {
+    let value = value.trim().to_lowercase();
+    let (amount, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value.as_str(), "m"),
+    };
+    let amount: i64 = amount.parse().ok().filter(|amount| *amount > 0)?;
+    match unit.trim() {
+        "m" | "min" | "mins" | "minutes" => Some(Duration::minutes(amount)),
+        "h" | "hr" | "hrs" | "hours" => Some(Duration::hours(amount)),
+        "d" | "day" | "days" => Some(Duration::days(amount)),
+        _ => None,
+    }
+}
@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct DiarySettings {
     pub diary_id: Option<Uuid>,
     pub staff_id: Uuid,
@@ -1,8 +1,21 @@
 use crate::{
     diary::domain_layer::diary_event_types::{
-        AppointmentDetails, CallbackDetails, Event, EventDetails, EventType, InspectionDetails,
-        LeaveDetails, MaintenanceDetails, MeetingDetails, NoteDetails, PublicHolidayDetails,
-        StaffHolidayDetails, TrainingDetails, ValuationDetails, ViewingDetails,
+        AppointmentDetails, CallbackDetails, Event, EventAudience, EventDetails, EventType,
+        InspectionDetails, LeaveDetails, MaintenanceDetails, MeetingDetails, NoteDetails,
+        PublicHolidayDetails, ReminderCandidate, StaffHolidayDetails, TrainingDetails,
+        ValuationDetails, ViewingDetails,
+    },
+    shared::{
+        domain_layer::{
+            email::{EmailRecipient, EmailTemplate, QueuedEmail},
+            outbox_event::{
+                DiaryEventCreated, DiaryEventDeleted, DiaryEventUpdated, OutboxEvent,
+                DIARY_EVENTS_TOPIC,
+            },
+        },
+        infrastructure_layer::{
+            email_queue_repository::EmailQueueRepository, outbox_repository::OutboxRepository,
+        },
     },
     AppState,
 };
@@ -10,9 +23,10 @@ use actix_web::{dev::Path, error::ResponseError};
 use chrono::{DateTime, NaiveDate, Utc};
 use derive_more::Display;
 use serde::Serialize;
+use serde_json::json;
 use serde_json::Value;
 use sqlx::types::JsonValue;
-use sqlx::{FromRow, Row};
+use sqlx::{FromRow, Pool, Postgres, Row, Transaction};
 use std::sync::Arc;
 use uuid::Uuid; // Add Row trait import
 
@@ -26,10 +40,28 @@ pub enum CustomErrors {
     NotFound,
     #[display(fmt = "Bad request: {}", _0)]
     BadRequest(String),
+    #[display(fmt = "Event was modified by someone else")]
+    PreconditionFailed,
 }
 
 impl ResponseError for CustomErrors {}
 
+/// Every table holding type-specific event details, keyed by `event_id`.
+pub const DETAIL_TABLES: [&str; 12] = [
+    "viewing_details",
+    "appointment_details",
+    "inspection_details",
+    "leave_details",
+    "meeting_details",
+    "valuation_details",
+    "callback_details",
+    "maintenance_details",
+    "staff_holiday_details",
+    "training_details",
+    "public_holiday_details",
+    "note_details",
+];
+
 pub struct EventRepository {}
 
 impl EventRepository {
@@ -48,11 +80,26 @@ impl EventRepository {
             .begin()
             .await
             .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        let event = Self::insert_event(&mut tx, new_event, details, None).await?;
+        tx.commit()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(event)
+    }
 
+    /// Creates an event inside an open transaction, with its details, the
+    /// emails it sends and its `DiaryEventCreated` event. It goes in
+    /// `branch_id`, or the caller's home branch when that is `None`.
+    pub async fn insert_event(
+        tx: &mut Transaction<'_, Postgres>,
+        new_event: Event,
+        details: EventDetails,
+        branch_id: Option<Uuid>,
+    ) -> Result<Event, CustomErrors> {
         // Insert main event
         let event = sqlx::query_as::<_, Event>(
-    "INSERT INTO events (external_id, event_type, date, start_time, end_time, created_by, title, description)
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    "INSERT INTO events (external_id, event_type, date, start_time, end_time, created_by, title, description, branch_id)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, current_home_branch()))
     RETURNING *"
     )
     .bind(&new_event.external_id)
@@ -63,24 +110,62 @@ impl EventRepository {
     .bind(&new_event.created_by)
     .bind(&new_event.title)
     .bind(&new_event.description)
-    .fetch_one(&mut *tx)
+    .bind(branch_id)
+    .fetch_one(&mut **tx)
     .await
     .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
 
         // Insert event details based on type
+        let emails = Self::notification_emails(tx, &event, &details, &[])
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Self::insert_details(tx, event.id.unwrap_or_default(), details).await?;
+        EmailQueueRepository::enqueue_all(tx, &emails)
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let event_id = event.id.unwrap_or_default();
+        let created = DiaryEventCreated {
+            event_id: event_id.to_string(),
+            event_type: format!("{:?}", event.event_type).to_lowercase(),
+            date: event.date.to_string(),
+            created_by: event.created_by.to_string(),
+        };
+        OutboxRepository::enqueue(
+            tx,
+            &OutboxEvent::new(
+                DIARY_EVENTS_TOPIC,
+                "diary_event",
+                event_id,
+                "DiaryEventCreated",
+                &created,
+            ),
+        )
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(event)
+    }
+
+    /// Inserts the type-specific details row for an event inside an open transaction.
+    pub async fn insert_details(
+        tx: &mut Transaction<'_, Postgres>,
+        event_id: Uuid,
+        details: EventDetails,
+    ) -> Result<(), CustomErrors> {
         match details {
             EventDetails::Viewing(details) => {
                 sqlx::query(
-    "INSERT INTO viewing_details (event_id, property_id, client_name, contact_number, viewing_type, notification_length)
-    VALUES ($1, $2, $3, $4, $5, $6)"
+    "INSERT INTO viewing_details (event_id, property_id, client_name, contact_number, viewing_type, notification_length, client_email)
+    VALUES ($1, $2, $3, $4, $5, $6, $7)"
     )
-    .bind(event.id)
+    .bind(event_id)
     .bind(&details.property_id)
     .bind(&details.client_name)
     .bind(&details.contact_number)
     .bind(&details.viewing_type)
     .bind(&details.notification_length)
-    .execute(&mut *tx)
+    .bind(&details.client_email)
+    .execute(&mut **tx)
     .await
     .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -89,14 +174,14 @@ impl EventRepository {
     "INSERT INTO appointment_details (event_id, location, property_id, is_private, notification, is_recurring, recurrence_pattern)
     VALUES ($1, $2, $3, $4, $5, $6, $7)"
     )
-    .bind(event.id)
+    .bind(event_id)
     .bind(&details.location)
     .bind(&details.property_id)
     .bind(&details.is_private)
     .bind(&details.notification)
     .bind(&details.is_recurring)
     .bind(&details.recurrence_pattern)
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await
     .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -105,11 +190,11 @@ impl EventRepository {
     "INSERT INTO inspection_details (event_id, property_id, contractor, notification)
     VALUES ($1, $2, $3, $4)"
     )
-    .bind(event.id)
+    .bind(event_id)
     .bind(&details.property_id)
     .bind(&details.contractor)
     .bind(&details.notification)
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await
     .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -118,10 +203,10 @@ impl EventRepository {
                     "INSERT INTO leave_details (event_id, staff_member, is_half_day)
     VALUES ($1, $2, $3)",
                 )
-                .bind(event.id)
+                .bind(event_id)
                 .bind(&details.staff_member)
                 .bind(&details.is_half_day)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await
                 .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -130,25 +215,26 @@ impl EventRepository {
     "INSERT INTO meeting_details (event_id, location, is_recurring, recurrence_pattern)
     VALUES ($1, $2, $3, $4)"
     )
-    .bind(event.id)
+    .bind(event_id)
     .bind(&details.location)
     .bind(&details.is_recurring)
     .bind(&details.recurrence_pattern)
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await
     .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
             EventDetails::Valuation(details) => {
                 sqlx::query(
-    "INSERT INTO valuation_details (event_id, property_id, client_name, contact_number, notification)
-    VALUES ($1, $2, $3, $4, $5)"
+    "INSERT INTO valuation_details (event_id, property_id, client_name, contact_number, notification, client_email)
+    VALUES ($1, $2, $3, $4, $5, $6)"
     )
-    .bind(event.id)
+    .bind(event_id)
     .bind(&details.property_id)
     .bind(&details.client_name)
     .bind(&details.contact_number)
     .bind(&details.notification)
-    .execute(&mut *tx)
+    .bind(&details.client_email)
+    .execute(&mut **tx)
     .await
     .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -157,11 +243,11 @@ impl EventRepository {
                     "INSERT INTO callback_details (event_id, contact_name, phone_number, is_urgent)
     VALUES ($1, $2, $3, $4)",
                 )
-                .bind(event.id)
+                .bind(event_id)
                 .bind(&details.contact_name)
                 .bind(&details.phone_number)
                 .bind(&details.is_urgent)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await
                 .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -170,11 +256,11 @@ impl EventRepository {
     "INSERT INTO maintenance_details (event_id, property_id, contractor, notification)
     VALUES ($1, $2, $3, $4)"
     )
-    .bind(event.id)
+    .bind(event_id)
     .bind(&details.property_id)
     .bind(&details.contractor)
     .bind(&details.notification)
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await
     .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -185,7 +271,7 @@ impl EventRepository {
     approval_status, approved_by, approval_date, remaining_days
     ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
                 )
-                .bind(event.id)
+                .bind(event_id)
                 .bind(&details.staff_member)
                 .bind(&details.holiday_type)
                 .bind(&details.is_half_day)
@@ -193,7 +279,7 @@ impl EventRepository {
                 .bind(&details.approved_by)
                 .bind(&details.approval_date)
                 .bind(&details.remaining_days)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await
                 .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -207,7 +293,7 @@ impl EventRepository {
     attendance_confirmed, certificates_issued
     ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
                 )
-                .bind(event.id)
+                .bind(event_id)
                 .bind(&details.training_title)
                 .bind(&details.location)
                 .bind(&details.lead_staff)
@@ -219,7 +305,7 @@ impl EventRepository {
                 .bind(&details.prerequisites)
                 .bind(&details.attendance_confirmed)
                 .bind(&details.certificates_issued)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await
                 .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -231,7 +317,7 @@ impl EventRepository {
     custom_working_hours
     ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
                 )
-                .bind(event.id)
+                .bind(event_id)
                 .bind(&details.holiday_name)
                 .bind(&details.region)
                 .bind(&details.affects_all_staff)
@@ -239,7 +325,7 @@ impl EventRepository {
                 .bind(&details.is_bank_holiday)
                 .bind(&details.office_status)
                 .bind(&details.custom_working_hours)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await
                 .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
@@ -251,7 +337,7 @@ impl EventRepository {
     status, completion_date, completed_by
     ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
                 )
-                .bind(event.id)
+                .bind(event_id)
                 .bind(&details.note_type)
                 .bind(&details.assigned_staff)
                 .bind(&details.is_private)
@@ -262,24 +348,22 @@ impl EventRepository {
                 .bind(&details.status)
                 .bind(&details.completion_date)
                 .bind(&details.completed_by)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await
                 .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
             }
         }
-
-        tx.commit()
-            .await
-            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
-        Ok(event)
+        Ok(())
     }
 
     pub async fn get_all_events(&self, state: Arc<AppState>) -> Result<Vec<Event>, CustomErrors> {
         let pool = &state.db;
-        sqlx::query_as::<_, Event>("SELECT * FROM events ORDER BY date, start_time")
-            .fetch_all(pool)
-            .await
-            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))
+        sqlx::query_as::<_, Event>(
+            "SELECT * FROM events WHERE deleted_at IS NULL ORDER BY date, start_time",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))
     }
 
     pub async fn get_event_by_id(
@@ -288,7 +372,7 @@ impl EventRepository {
         event_id: Uuid,
     ) -> Result<Event, CustomErrors> {
         let pool = &state.db;
-        sqlx::query_as::<_, Event>("SELECT * FROM events WHERE id = $1")
+        sqlx::query_as::<_, Event>("SELECT * FROM events WHERE id = $1 AND deleted_at IS NULL")
             .bind(event_id)
             .fetch_optional(pool)
             .await
@@ -312,7 +396,8 @@ impl EventRepository {
                     'client_name', vd.client_name,
                     'contact_number', vd.contact_number,
                     'viewing_type', vd.viewing_type,
-                    'notification_length', vd.notification_length
+                    'notification_length', vd.notification_length,
+                    'client_email', vd.client_email
                 )
                 FROM viewing_details vd
                 WHERE vd.event_id = e.id
@@ -360,7 +445,8 @@ impl EventRepository {
                     'property_id', vd.property_id,
                     'client_name', vd.client_name,
                     'contact_number', vd.contact_number,
-                    'notification', vd.notification
+                    'notification', vd.notification,
+                    'client_email', vd.client_email
                 )
                 FROM valuation_details vd
                 WHERE vd.event_id = e.id
@@ -444,7 +530,7 @@ impl EventRepository {
             )
         END AS details
     FROM events e
-    WHERE e.created_by = $1
+    WHERE e.created_by = $1 AND e.deleted_at IS NULL
     ORDER BY e.date, e.start_time
 "#;
         // Rest of the function remains the same...
@@ -468,6 +554,7 @@ impl EventRepository {
                     created_by: row.get("created_by"),
                     created_at: Some(row.get::<DateTime<Utc>, _>("created_at")),
                     updated_at: Some(row.get::<DateTime<Utc>, _>("updated_at")),
+                    branch_id: row.get("branch_id"),
                 };
                 let details_json: JsonValue = row.get("details");
                 let details = match event.event_type {
@@ -532,7 +619,8 @@ impl EventRepository {
                     'client_name', vd.client_name,
                     'contact_number', vd.contact_number,
                     'viewing_type', vd.viewing_type,
-                    'notification_length', vd.notification_length
+                    'notification_length', vd.notification_length,
+                    'client_email', vd.client_email
                 )
                 FROM viewing_details vd
                 WHERE vd.event_id = e.id
@@ -580,7 +668,8 @@ impl EventRepository {
                     'property_id', vd.property_id,
                     'client_name', vd.client_name,
                     'contact_number', vd.contact_number,
-                    'notification', vd.notification
+                    'notification', vd.notification,
+                    'client_email', vd.client_email
                 )
                 FROM valuation_details vd
                 WHERE vd.event_id = e.id
@@ -664,7 +753,7 @@ impl EventRepository {
             )
         END AS details
     FROM events e
-    WHERE e.created_by = $1
+    WHERE e.created_by = $1 AND e.deleted_at IS NULL
         AND ($2::date IS NULL OR e.date >= $2)
         AND ($3::date IS NULL OR e.date <= $3)
         ORDER BY e.date, e.start_time
@@ -695,6 +784,7 @@ impl EventRepository {
                     created_by: row.get("created_by"),
                     created_at: Some(row.get::<DateTime<Utc>, _>("created_at")),
                     updated_at: Some(row.get::<DateTime<Utc>, _>("updated_at")),
+                    branch_id: row.get("branch_id"),
                 };
                 let details_json: JsonValue = row.get("details");
                 let details = match event.event_type {
@@ -741,12 +831,15 @@ impl EventRepository {
         Ok(result)
     }
 
+    /// Replaces an event and its details. When `expected_updated_at` is given the
+    /// update only applies if the event has not changed since the caller read it.
     pub async fn update_event(
         &self,
         state: Arc<AppState>,
         event_id: Uuid,
         updated_event: Event,
         updated_details: EventDetails,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Event, CustomErrors> {
         let pool = &state.db;
         let mut tx = pool
@@ -765,7 +858,7 @@ impl EventRepository {
     title = $6,
     description = $7,
     updated_at = CURRENT_TIMESTAMP
-    WHERE id = $8
+    WHERE id = $8 AND deleted_at IS NULL AND ($9::timestamptz IS NULL OR updated_at = $9)
     RETURNING *",
         )
         .bind(&updated_event.external_id)
@@ -776,89 +869,73 @@ impl EventRepository {
         .bind(&updated_event.title)
         .bind(&updated_event.description)
         .bind(event_id)
+        .bind(expected_updated_at)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let event = match event {
+            Some(event) => event,
+            None => {
+                let exists =
+                    sqlx::query("SELECT 1 FROM events WHERE id = $1 AND deleted_at IS NULL")
+                        .bind(event_id)
+                        .fetch_optional(&mut *tx)
+                        .await
+                        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+                return match exists {
+                    Some(_) => Err(CustomErrors::PreconditionFailed),
+                    None => Err(CustomErrors::NotFound),
+                };
+            }
+        };
+
+        // Staff already assigned to a note are not told again.
+        let previously_assigned = sqlx::query_scalar::<_, Option<Vec<Uuid>>>(
+            "SELECT assigned_staff FROM note_details WHERE event_id = $1",
+        )
+        .bind(event_id)
         .fetch_optional(&mut *tx)
         .await
         .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?
-        .ok_or(CustomErrors::NotFound)?;
+        .flatten()
+        .unwrap_or_default();
+        let emails =
+            Self::notification_emails(&mut tx, &event, &updated_details, &previously_assigned)
+                .await
+                .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
 
-        // Delete existing details
-        sqlx::query("DELETE FROM viewing_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM appointment_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM inspection_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM leave_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM meeting_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM valuation_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM callback_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM maintenance_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
+        // Replace existing details, whatever type they were
+        for table in DETAIL_TABLES {
+            sqlx::query(&format!("DELETE FROM {} WHERE event_id = $1", table))
+                .bind(event_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        }
+        Self::insert_details(&mut tx, event_id, updated_details).await?;
+        EmailQueueRepository::enqueue_all(&mut tx, &emails)
             .await
-            .ok();
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
 
-        // Insert new details based on type
-        match updated_details {
-            EventDetails::Viewing(details) => {
-                sqlx::query(
-    "INSERT INTO viewing_details (event_id, property_id, client_name, contact_number, viewing_type, notification_length)
-    VALUES ($1, $2, $3, $4, $5, $6)"
-    )
-    .bind(event_id)
-    .bind(&details.property_id)
-    .bind(&details.client_name)
-    .bind(&details.contact_number)
-    .bind(&details.viewing_type)
-    .bind(&details.notification_length)
-    .execute(&mut *tx)
-    .await
-    .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
-            }
-            EventDetails::Appointment(details) => {
-                sqlx::query(
-    "INSERT INTO appointment_details (event_id, location, property_id, is_private, notification, is_recurring, recurrence_pattern)
-    VALUES ($1, $2, $3, $4, $5, $6, $7)"
-    )
-    .bind(event_id)
-    .bind(&details.location)
-    .bind(&details.property_id)
-    .bind(&details.is_private)
-    .bind(&details.notification)
-    .bind(&details.is_recurring)
-    .bind(&details.recurrence_pattern)
-    .execute(&mut *tx)
-    .await
-    .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
-            }
-            // ... Similar patterns for other event types ...
-            _ => return Err(CustomErrors::BadRequest("Invalid event type".to_string())),
-        }
+        let updated = DiaryEventUpdated {
+            event_id: event_id.to_string(),
+            event_type: format!("{:?}", event.event_type).to_lowercase(),
+            date: event.date.to_string(),
+            created_by: event.created_by.to_string(),
+        };
+        OutboxRepository::enqueue(
+            &mut tx,
+            &OutboxEvent::new(
+                DIARY_EVENTS_TOPIC,
+                "diary_event",
+                event_id,
+                "DiaryEventUpdated",
+                &updated,
+            ),
+        )
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
 
         tx.commit()
             .await
@@ -866,76 +943,224 @@ impl EventRepository {
         Ok(event)
     }
 
+    /// Soft-deletes an event. Its details are kept until the retention purge.
     pub async fn delete_event(
         &self,
         state: Arc<AppState>,
         event_id: Uuid,
     ) -> Result<(), CustomErrors> {
-        let pool = &state.db;
-        let mut tx = pool
+        let mut tx = state
+            .db
             .begin()
             .await
             .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
-
-        // Delete all related details first
-        sqlx::query("DELETE FROM viewing_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM appointment_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM inspection_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM leave_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM meeting_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM valuation_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM callback_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-        sqlx::query("DELETE FROM maintenance_details WHERE event_id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .ok();
-
-        // Delete the main event
-        let result = sqlx::query("DELETE FROM events WHERE id = $1")
-            .bind(event_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        let result = sqlx::query(
+            "UPDATE events SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+    WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(event_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
 
         if result.rows_affected() == 0 {
             return Err(CustomErrors::NotFound);
         }
 
+        let deleted = DiaryEventDeleted {
+            event_id: event_id.to_string(),
+        };
+        OutboxRepository::enqueue(
+            &mut tx,
+            &OutboxEvent::new(
+                DIARY_EVENTS_TOPIC,
+                "diary_event",
+                event_id,
+                "DiaryEventDeleted",
+                &deleted,
+            ),
+        )
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
         tx.commit()
             .await
             .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
         Ok(())
     }
 
+    /// Who may see an event on the live channel. Private appointments and
+    /// notes only go to the staff involved; `None` when the event is gone.
+    pub async fn live_audience(
+        pool: &Pool<Postgres>,
+        event_id: Uuid,
+    ) -> Result<Option<EventAudience>, sqlx::Error> {
+        let row = sqlx::query_as::<_, (Uuid, bool, Vec<Uuid>)>(
+            "SELECT
+        e.branch_id,
+        COALESCE(ad.is_private, FALSE) OR COALESCE(nd.is_private, FALSE),
+        ARRAY_REMOVE(
+            ARRAY[e.created_by, hd.staff_member, td.lead_staff]
+                || COALESCE(nd.assigned_staff, '{}'::uuid[])
+                || COALESCE(td.attendees, '{}'::uuid[]),
+            NULL
+        )
+    FROM events e
+    LEFT JOIN appointment_details ad ON ad.event_id = e.id
+    LEFT JOIN note_details nd ON nd.event_id = e.id
+    LEFT JOIN staff_holiday_details hd ON hd.event_id = e.id
+    LEFT JOIN training_details td ON td.event_id = e.id
+    WHERE e.id = $1",
+        )
+        .bind(event_id)
+        .fetch_optional(pool)
+        .await?;
+        let (branch_id, is_private, involved) = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        let popup_recipients = sqlx::query_scalar::<_, Uuid>(
+            "SELECT staff_id FROM diary_settings WHERE popup_notifi_en AND staff_id = ANY($1)",
+        )
+        .bind(&involved)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Some(EventAudience {
+            branch_id,
+            is_private,
+            involved,
+            popup_recipients,
+        }))
+    }
+
+    /// Booking confirmations for clients of viewings and valuations, and
+    /// assignment emails for staff newly assigned to a note. Keyed on the
+    /// booked slot, so rescheduling confirms again but other edits do not.
+    async fn notification_emails(
+        tx: &mut Transaction<'_, Postgres>,
+        event: &Event,
+        details: &EventDetails,
+        previously_assigned: &[Uuid],
+    ) -> Result<Vec<QueuedEmail>, sqlx::Error> {
+        let event_id = event.id.unwrap_or_default();
+        let event_type = format!("{:?}", event.event_type).to_lowercase();
+        let mut emails = Vec::new();
+
+        let booking = match details {
+            EventDetails::Viewing(details) => details
+                .client_email
+                .as_ref()
+                .map(|email| (email, &details.client_name, &details.property_id)),
+            EventDetails::Valuation(details) => details
+                .client_email
+                .as_ref()
+                .map(|email| (email, &details.client_name, &details.property_id)),
+            _ => None,
+        };
+        if let Some((client_email, client_name, property_id)) = booking {
+            let property_address = sqlx::query_scalar::<_, Option<String>>(
+                "SELECT display_address FROM property_address WHERE property_id::text = $1",
+            )
+            .bind(property_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .flatten();
+            let reference = event_id.simple().to_string()[..8].to_uppercase();
+            emails.push(
+                QueuedEmail::new(
+                    EmailTemplate::BookingConfirmation,
+                    EmailRecipient::Address(client_email.clone()),
+                    json!({
+                        "client_name": client_name,
+                        "event_type": event_type,
+                        "date": event.date.format("%d/%m/%Y").to_string(),
+                        "start_time": event.start_time.format("%H:%M").to_string(),
+                        "end_time": event.end_time.format("%H:%M").to_string(),
+                        "property_address": property_address,
+                        "reference": reference,
+                    }),
+                )
+                .dedupe_key(format!(
+                    "booking:{}:{}T{}:{}",
+                    event_id, event.date, event.start_time, client_email
+                )),
+            );
+        }
+
+        if let EventDetails::Note(details) = details {
+            let subject = format!(
+                "the note \"{}\" on {}",
+                event.title.as_deref().unwrap_or(&details.note_type),
+                event.date.format("%d/%m/%Y")
+            );
+            for staff_id in details.assigned_staff.iter().flatten() {
+                if previously_assigned.contains(staff_id) {
+                    continue;
+                }
+                emails.push(QueuedEmail::new(
+                    EmailTemplate::AssignmentChanged,
+                    EmailRecipient::Staff(*staff_id),
+                    json!({
+                        "subject": subject,
+                        "details": event.description,
+                    }),
+                ));
+            }
+        }
+        Ok(emails)
+    }
+
+    /// Events between `from` and `to` that asked for a reminder.
+    pub async fn reminder_candidates(
+        pool: &Pool<Postgres>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ReminderCandidate>, sqlx::Error> {
+        sqlx::query_as::<_, ReminderCandidate>(
+            "SELECT e.id AS event_id, e.event_type, e.date, e.start_time, e.end_time,
+        e.title, e.description, e.created_by,
+        COALESCE(vw.property_id::text, ad.property_id::text, ins.property_id::text,
+            va.property_id::text, mt.property_id::text) AS property_id,
+        COALESCE(vw.client_name, va.client_name) AS client_name,
+        COALESCE(vw.contact_number, va.contact_number) AS contact_number,
+        vw.notification_length,
+        COALESCE(ad.notification, ins.notification, va.notification, mt.notification, FALSE) AS notification
+    FROM events e
+    LEFT JOIN viewing_details vw ON vw.event_id = e.id
+    LEFT JOIN appointment_details ad ON ad.event_id = e.id
+    LEFT JOIN inspection_details ins ON ins.event_id = e.id
+    LEFT JOIN valuation_details va ON va.event_id = e.id
+    LEFT JOIN maintenance_details mt ON mt.event_id = e.id
+    WHERE e.deleted_at IS NULL AND e.date BETWEEN $1 AND $2
+        AND (vw.notification_length IS NOT NULL
+            OR COALESCE(ad.notification, ins.notification, va.notification, mt.notification, FALSE))
+    ORDER BY e.date, e.start_time",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn restore_event(
+        &self,
+        state: Arc<AppState>,
+        event_id: Uuid,
+    ) -> Result<Event, CustomErrors> {
+        sqlx::query_as::<_, Event>(
+            "UPDATE events SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+    WHERE id = $1 AND deleted_at IS NOT NULL
+    RETURNING *",
+        )
+        .bind(event_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?
+        .ok_or(CustomErrors::NotFound)
+    }
+
     pub async fn get_events_by_date_range(
         &self,
         state: Arc<AppState>,
@@ -945,7 +1170,7 @@ impl EventRepository {
         let pool = &state.db;
         sqlx::query_as::<_, Event>(
             "SELECT * FROM events
-    WHERE date >= $1 AND date <= $2
+    WHERE date >= $1 AND date <= $2 AND deleted_at IS NULL
     ORDER BY date, start_time",
         )
         .bind(start_date)
@@ -963,7 +1188,7 @@ impl EventRepository {
         let pool = &state.db;
         sqlx::query_as::<_, Event>(
             "SELECT * FROM events
-    WHERE event_type = $1::event_type
+    WHERE event_type = $1::event_type AND deleted_at IS NULL
     ORDER BY date, start_time",
         )
         .bind(event_type)
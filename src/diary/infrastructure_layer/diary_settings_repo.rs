@@ -1,6 +1,6 @@
 use crate::{diary::domain_layer::diary_settings::DiarySettings, AppState};
 use actix_web::error::ResponseError;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use derive_more::Display;
 use serde::Serialize;
 use std::sync::Arc;
@@ -12,6 +12,10 @@ pub enum CustomErrors {
     DatabaseError(String),
     #[display(fmt = "Not authorized")]
     NotAuthorized,
+    #[display(fmt = "Diary settings not found")]
+    NotFound,
+    #[display(fmt = "Diary settings were modified by someone else")]
+    PreconditionFailed,
 }
 
 impl ResponseError for CustomErrors {}
@@ -71,26 +75,42 @@ impl DiarySettingsRepository {
         }
     }
 
+    /// Updates the settings only if they still have the `updated_at` the caller
+    /// last saw. `expected_updated_at = None` skips the check (`If-Match: *`).
     pub async fn update_diary_settings(
         &self,
         state: Arc<AppState>,
         diary_id: Uuid,
         updated_settings: DiarySettings,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<DiarySettings, CustomErrors> {
         let record = sqlx::query_as::<_, DiarySettings>(
-            "UPDATE diary_settings SET staff_id = $1, diary_colour = $2, popup_notifi_en = $3, email_notifi_en = $4, updated_at = $5 WHERE diary_id = $6 RETURNING *"
+            "UPDATE diary_settings SET staff_id = $1, diary_colour = $2, popup_notifi_en = $3, email_notifi_en = $4, updated_at = CURRENT_TIMESTAMP
+             WHERE diary_id = $5 AND ($6::timestamptz IS NULL OR updated_at = $6)
+             RETURNING *"
         )
         .bind(updated_settings.staff_id)
         .bind(updated_settings.diary_colour)
         .bind(updated_settings.popup_notifi_en)
         .bind(updated_settings.email_notifi_en)
-        .bind(updated_settings.updated_at)
         .bind(diary_id)
-        .fetch_one(&state.db)
+        .bind(expected_updated_at)
+        .fetch_optional(&state.db)
         .await;
 
         match record {
-            Ok(settings) => Ok(settings),
+            Ok(Some(settings)) => Ok(settings),
+            Ok(None) => {
+                let exists = sqlx::query("SELECT 1 FROM diary_settings WHERE diary_id = $1")
+                    .bind(diary_id)
+                    .fetch_optional(&state.db)
+                    .await
+                    .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+                match exists {
+                    Some(_) => Err(CustomErrors::PreconditionFailed),
+                    None => Err(CustomErrors::NotFound),
+                }
+            }
             Err(e) => Err(CustomErrors::DatabaseError(e.to_string())),
         }
     }
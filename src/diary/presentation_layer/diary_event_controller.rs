@@ -1,13 +1,19 @@
-use actix_web::web;
+use actix_web::{guard, web};
 
 use crate::diary::application_layer::diary_event_service;
+use crate::shared::infrastructure_layer::idempotency_repo::Idempotency;
 
 // PRESENTATION LAYER (routes.rs)
 pub fn diary_event_configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/events")
             .route("", web::get().to(diary_event_service::get_all_events))
-            .route("", web::post().to(diary_event_service::create_event))
+            .service(
+                web::resource("")
+                    .guard(guard::Post())
+                    .wrap(Idempotency)
+                    .route(web::post().to(diary_event_service::create_event)),
+            )
             .route(
                 "/{event_id}",
                 web::get().to(diary_event_service::get_event_by_id),
@@ -20,6 +26,10 @@ pub fn diary_event_configure_routes(cfg: &mut web::ServiceConfig) {
                 "/{event_id}",
                 web::delete().to(diary_event_service::delete_event),
             )
+            .route(
+                "/{event_id}/restore",
+                web::post().to(diary_event_service::restore_event),
+            )
             .route(
                 "/users/{user_id}",
                 web::get().to(diary_event_service::get_event_by_user_id),
@@ -1,6 +1,7 @@
-use actix_web::web;
+use actix_web::{guard, web};
 
 use crate::diary::application_layer::diary_settings_service;
+use crate::shared::infrastructure_layer::idempotency_repo::Idempotency;
 
 pub fn diary_settings_configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -13,9 +14,11 @@ pub fn diary_settings_configure_routes(cfg: &mut web::ServiceConfig) {
                 "/{staff_id}",
                 web::get().to(diary_settings_service::get_diary_settings_by_id),
             ) // Get a specific diary setting by id
-            .route(
-                "",
-                web::post().to(diary_settings_service::create_diary_settings),
+            .service(
+                web::resource("")
+                    .guard(guard::Post())
+                    .wrap(Idempotency)
+                    .route(web::post().to(diary_settings_service::create_diary_settings)),
             ) // Create new diary settings
             .route(
                 "/{diary_id}",
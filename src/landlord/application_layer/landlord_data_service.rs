@@ -0,0 +1,236 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    landlord::{
+        domain_layer::{
+            landlord_additional_contact::LandlordAdditionalContact,
+            landlord_data_export::{LandlordDataExport, LandlordErasure, RetentionHold},
+        },
+        infrastructure_layer::landlord_data_repository::{CustomErrors, LandlordDataRepository},
+    },
+    shared::domain_layer::{
+        api_schema::{ErrorResponse, RetentionHoldResponse},
+        sensitive_data::REVEAL_SENSITIVE_DATA,
+    },
+    user::application_layer::permission_service,
+    AppState,
+};
+
+/// Everything held about the landlord, bank details and tax references in
+/// full, so only for staff holding the reveal permission. Audited like a reveal.
+#[utoipa::path(
+    get,
+    path = "/api/v1/landlords/{landlord_id}/export",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Everything held about the landlord, as a JSON download", body = LandlordDataExport),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `reveal_sensitive_data`", body = ErrorResponse),
+        (status = 404, description = "Landlord not found", body = ErrorResponse),
+        (status = 500, description = "The export could not be audited", body = ErrorResponse),
+    )
+)]
+pub async fn export_landlord_data(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let landlord_id = landlord_id.into_inner();
+    if let Err(response) = require_export_audit(&req, &state, landlord_id).await {
+        return response;
+    }
+    let repo = LandlordDataRepository::new();
+    match repo.export(state.into_inner(), landlord_id).await {
+        Ok(export) => HttpResponse::Ok()
+            .insert_header(attachment(&format!("landlord-{}.json", landlord_id)))
+            .json(export),
+        Err(e) => error_response(e),
+    }
+}
+
+/// The JSON export plus every uploaded document, as one ZIP download.
+#[utoipa::path(
+    get,
+    path = "/api/v1/landlords/{landlord_id}/export/archive",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "`landlord.json` plus uploaded documents", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `reveal_sensitive_data`", body = ErrorResponse),
+        (status = 404, description = "Landlord not found", body = ErrorResponse),
+        (status = 500, description = "The export could not be audited", body = ErrorResponse),
+    )
+)]
+pub async fn export_landlord_archive(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let landlord_id = landlord_id.into_inner();
+    if let Err(response) = require_export_audit(&req, &state, landlord_id).await {
+        return response;
+    }
+    let upload_dir = state.upload_dir.clone();
+    let repo = LandlordDataRepository::new();
+    let export = match repo.export(state.into_inner(), landlord_id).await {
+        Ok(export) => export,
+        Err(e) => return error_response(e),
+    };
+
+    match web::block(move || build_archive(&export, &upload_dir)).await {
+        Ok(Ok(archive)) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header(attachment(&format!("landlord-{}.zip", landlord_id)))
+            .body(archive),
+        Ok(Err(e)) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+/// Anonymises the landlord's personal data. Refused with 409 while
+/// `do_not_delete_before` is in the future.
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords/{landlord_id}/erasure",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    responses(
+        (status = 200, description = "What was erased", body = LandlordErasure),
+        (status = 404, description = "Landlord not found", body = ErrorResponse),
+        (status = 409, description = "Retention hold in force", body = RetentionHoldResponse),
+    )
+)]
+pub async fn erase_landlord_data(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+) -> impl Responder {
+    let upload_dir = state.upload_dir.clone();
+    let repo = LandlordDataRepository::new();
+    match repo
+        .erase(state.into_inner(), landlord_id.into_inner())
+        .await
+    {
+        Ok((erasure, documents)) => {
+            for document in documents {
+                if let Some(path) = document_path(&upload_dir, &document) {
+                    if let Err(e) = tokio::fs::remove_file(&path).await {
+                        log::warn!("Could not remove erased document {:?}: {}", path, e);
+                    }
+                }
+            }
+            HttpResponse::Ok().json(erasure)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords/{landlord_id}/contacts/{contact_id}/erasure",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id"), ("contact_id" = Uuid, Path, description = "Additional contact id")),
+    responses(
+        (status = 200, description = "The anonymised contact", body = LandlordAdditionalContact),
+        (status = 404, description = "Contact not found", body = ErrorResponse),
+        (status = 409, description = "Retention hold in force", body = RetentionHoldResponse),
+    )
+)]
+pub async fn erase_contact_data(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (landlord_id, contact_id) = path.into_inner();
+    let repo = LandlordDataRepository::new();
+    match repo
+        .erase_contact(state.into_inner(), landlord_id, contact_id)
+        .await
+    {
+        Ok(contact) => HttpResponse::Ok().json(contact),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Checks the caller may see the landlord's financial identifiers and writes
+/// the reveal to the audit trail before anything is read.
+async fn require_export_audit(
+    req: &HttpRequest,
+    state: &web::Data<AppState>,
+    landlord_id: Uuid,
+) -> Result<(), HttpResponse> {
+    let user_id = permission_service::require_permission(req, state, REVEAL_SENSITIVE_DATA).await?;
+    permission_service::require_audit(
+        req,
+        state,
+        user_id,
+        "sensitive_data_revealed",
+        format!("Exported all data held about landlord {}", landlord_id),
+    )
+    .await
+}
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::RetentionHold(date) => HttpResponse::Conflict().json(json!({
+            "error": "Retention hold",
+            "message": e.to_string(),
+            "hold": RetentionHold { do_not_delete_before: date },
+        })),
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        _ => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+fn attachment(filename: &str) -> (header::HeaderName, String) {
+    (
+        header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", filename),
+    )
+}
+
+/// Document references are resolved inside the upload directory only, so a
+/// stored path cannot point the export at arbitrary files.
+fn document_path(upload_dir: &str, reference: &str) -> Option<PathBuf> {
+    Path::new(reference)
+        .file_name()
+        .map(|name| Path::new(upload_dir).join(name))
+}
+
+fn build_archive(
+    export: &LandlordDataExport,
+    upload_dir: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    zip.start_file("landlord.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(export)?)?;
+
+    for document in export
+        .documents
+        .iter()
+        .filter_map(|d| d.id_document.as_deref())
+    {
+        let Some(path) = document_path(upload_dir, document) else {
+            continue;
+        };
+        match std::fs::read(&path) {
+            Ok(contents) => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                zip.start_file(format!("documents/{}", name), options)?;
+                zip.write_all(&contents)?;
+            }
+            Err(e) => log::warn!("Document {:?} missing from export: {}", path, e),
+        }
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
@@ -0,0 +1,155 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    landlord::{
+        domain_layer::{
+            landlords_bank_details::LandlordsBankDetails,
+            landlords_lettings_management::LandlordsLettingsManagement,
+        },
+        infrastructure_layer::landlord_financial_repository::LandlordFinancialRepository,
+    },
+    shared::domain_layer::{api_schema::ErrorResponse, sensitive_data::REVEAL_SENSITIVE_DATA},
+    user::application_layer::permission_service,
+    AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/landlords/{landlord_id}/bank-details",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    responses((status = 200, description = "Bank details with account number, sort code and IBAN masked", body = [LandlordsBankDetails]))
+)]
+pub async fn get_bank_details(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+) -> impl Responder {
+    let repo = LandlordFinancialRepository::new();
+    match repo
+        .get_bank_details(state.into_inner(), landlord_id.into_inner())
+        .await
+    {
+        Ok(rows) => {
+            HttpResponse::Ok().json(rows.into_iter().map(|row| row.masked()).collect::<Vec<_>>())
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+/// Full bank details, for staff holding the reveal permission. Every call is
+/// audited before anything is read, and nothing is revealed if that fails.
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords/{landlord_id}/bank-details/reveal",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Unmasked bank details", body = [LandlordsBankDetails]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `reveal_sensitive_data`", body = ErrorResponse),
+        (status = 500, description = "The reveal could not be audited", body = ErrorResponse),
+    )
+)]
+pub async fn reveal_bank_details(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id =
+        match permission_service::require_permission(&req, &state, REVEAL_SENSITIVE_DATA).await {
+            Ok(user_id) => user_id,
+            Err(response) => return response,
+        };
+    let landlord_id = landlord_id.into_inner();
+    if let Err(response) = permission_service::require_audit(
+        &req,
+        &state,
+        user_id,
+        "sensitive_data_revealed",
+        format!("Revealed bank details of landlord {}", landlord_id),
+    )
+    .await
+    {
+        return response;
+    }
+    let repo = LandlordFinancialRepository::new();
+    match repo
+        .get_bank_details(state.clone().into_inner(), landlord_id)
+        .await
+    {
+        Ok(rows) => HttpResponse::Ok().json(rows),
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/landlords/{landlord_id}/lettings-management",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    responses((status = 200, description = "Lettings preferences with NI number and UTR masked", body = [LandlordsLettingsManagement]))
+)]
+pub async fn get_lettings_management(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+) -> impl Responder {
+    let repo = LandlordFinancialRepository::new();
+    match repo
+        .get_lettings_management(state.into_inner(), landlord_id.into_inner())
+        .await
+    {
+        Ok(rows) => {
+            HttpResponse::Ok().json(rows.into_iter().map(|row| row.masked()).collect::<Vec<_>>())
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+/// Full NI number and UTR, for staff holding the reveal permission. Every call is
+/// audited before anything is read, and nothing is revealed if that fails.
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords/{landlord_id}/lettings-management/reveal",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Unmasked lettings preferences", body = [LandlordsLettingsManagement]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `reveal_sensitive_data`", body = ErrorResponse),
+        (status = 500, description = "The reveal could not be audited", body = ErrorResponse),
+    )
+)]
+pub async fn reveal_lettings_management(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id =
+        match permission_service::require_permission(&req, &state, REVEAL_SENSITIVE_DATA).await {
+            Ok(user_id) => user_id,
+            Err(response) => return response,
+        };
+    let landlord_id = landlord_id.into_inner();
+    if let Err(response) = permission_service::require_audit(
+        &req,
+        &state,
+        user_id,
+        "sensitive_data_revealed",
+        format!("Revealed tax references of landlord {}", landlord_id),
+    )
+    .await
+    {
+        return response;
+    }
+    let repo = LandlordFinancialRepository::new();
+    match repo
+        .get_lettings_management(state.clone().into_inner(), landlord_id)
+        .await
+    {
+        Ok(rows) => HttpResponse::Ok().json(rows),
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
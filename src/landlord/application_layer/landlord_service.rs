@@ -1,7 +1,17 @@
 use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
 
-use crate::{landlord::{domain_layer::landlord_details::{LandlordDetails, LandlordQueryParams}, infrastructure_layer::landlord_repository::LandlordRepository}, AppState};
+use crate::{landlord::{domain_layer::landlord_details::{LandlordDetails, LandlordQueryParams}, infrastructure_layer::landlord_repository::{CustomErrors, LandlordRepository}}, AppState};
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, StatusMessageResponse};
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/landlords",
+    tag = "landlords",
+    params(LandlordQueryParams),
+    responses((status = 200, description = "Landlords that have not been deleted", body = [LandlordDetails]))
+)]
 pub async fn get_all_landlords(
     state: web::Data<AppState>,
     query: web::Query<LandlordQueryParams>,
@@ -13,6 +23,17 @@ pub async fn get_all_landlords(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords",
+    tag = "landlords",
+    params(IdempotencyHeader),
+    responses(
+        (status = 200, description = "Landlord saved", body = StatusMessageResponse),
+        (status = 409, description = "A request with this `Idempotency-Key` is still in progress", body = ErrorResponse),
+        (status = 422, description = "The `Idempotency-Key` was used with a different body", body = ErrorResponse),
+    )
+)]
 pub async fn register_landlord(
     state: web::Data<AppState>,
     landlord: web::Json<LandlordDetails>,
@@ -24,4 +45,75 @@ pub async fn register_landlord(
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/landlords/{landlord_id}",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    responses(
+        (status = 204, description = "Landlord soft-deleted"),
+        (status = 404, description = "Landlord not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_landlord(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+) -> impl Responder {
+    let repo = LandlordRepository::new();
+    match repo.delete(state.into_inner(), landlord_id.into_inner()).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Landlord not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords/{landlord_id}/registration/complete",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    responses(
+        (status = 200, description = "Registration completed, or already complete", body = StatusMessageResponse),
+        (status = 404, description = "Landlord general details not found", body = ErrorResponse),
+    )
+)]
+pub async fn complete_registration(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+) -> impl Responder {
+    let repo = LandlordRepository::new();
+    match repo.complete_registration(state.into_inner(), landlord_id.into_inner()).await {
+        Ok(true) => HttpResponse::Ok().json(json!({"status": "success", "message": "Registration completed"})),
+        Ok(false) => HttpResponse::Ok().json(json!({"status": "success", "message": "Registration was already complete"})),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Landlord general details not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords/{landlord_id}/restore",
+    tag = "landlords",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    responses(
+        (status = 200, description = "The restored landlord", body = LandlordDetails),
+        (status = 404, description = "Deleted landlord not found", body = ErrorResponse),
+    )
+)]
+pub async fn restore_landlord(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+) -> impl Responder {
+    let repo = LandlordRepository::new();
+    match repo.restore(state.into_inner(), landlord_id.into_inner()).await {
+        Ok(landlord) => HttpResponse::Ok().json(landlord),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Deleted landlord not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
@@ -1 +1,4 @@
+pub mod landlord_data_service;
+pub mod landlord_financial_service;
 pub mod landlord_service;
+pub mod registration_expiry_service;
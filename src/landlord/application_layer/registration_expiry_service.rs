@@ -0,0 +1,20 @@
+use chrono::Utc;
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+
+use crate::landlord::infrastructure_layer::registration_progress_repository::RegistrationProgressRepository;
+use crate::shared::infrastructure_layer::job_runner::JobResult;
+
+/// Job that drops landlord registrations abandoned past their `expires_at`.
+pub async fn expire_registrations(pool: Pool<Postgres>) -> JobResult {
+    let repo = RegistrationProgressRepository::new();
+    match repo.delete_expired(&pool, Utc::now()).await {
+        Ok(expired) => {
+            if expired > 0 {
+                log::info!("Removed {} expired landlord registrations", expired);
+            }
+            Ok(Some(json!({ "expired": expired })))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -3,8 +3,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::types::Uuid;
 
 use super::landlord_details::LandlordTitle;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct LandlordAdditionalContact {
     pub contact_id: Uuid,
     pub landlord_id: Uuid,
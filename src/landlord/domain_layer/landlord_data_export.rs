@@ -0,0 +1,46 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use super::{
+    landlord_additional_contact::LandlordAdditionalContact, landlord_details::LandlordDetails,
+    landlord_documents::LandlordDocs, landlord_general::LandlordGeneral,
+    landlords_address::LandlordsAddress, landlords_bank_details::LandlordsBankDetails,
+    landlords_lettings_management::LandlordsLettingsManagement,
+};
+use crate::{
+    diary::domain_layer::diary_event_types::Event,
+    properties::domain_layer::{property_address::PropertyAddress, property_core::PropertyCore},
+};
+use utoipa::ToSchema;
+
+/// Everything held about a landlord, as returned for a subject access request.
+/// Soft-deleted records are included because they are still held.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct LandlordDataExport {
+    pub exported_at: DateTime<Utc>,
+    pub details: LandlordDetails,
+    pub general: Option<LandlordGeneral>,
+    pub addresses: Vec<LandlordsAddress>,
+    pub bank_details: Vec<LandlordsBankDetails>,
+    pub lettings_management: Vec<LandlordsLettingsManagement>,
+    pub additional_contacts: Vec<LandlordAdditionalContact>,
+    pub documents: Vec<LandlordDocs>,
+    pub properties: Vec<PropertyCore>,
+    pub property_addresses: Vec<PropertyAddress>,
+    pub diary_events: Vec<Event>,
+}
+
+/// What an erasure request changed.
+#[derive(Serialize, Debug, Default, ToSchema)]
+pub struct LandlordErasure {
+    pub erased_at: Option<DateTime<Utc>>,
+    pub addresses: u64,
+    pub additional_contacts: u64,
+    pub documents: u64,
+}
+
+/// Returned instead of erasing while a retention hold is in force.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct RetentionHold {
+    pub do_not_delete_before: NaiveDate,
+}
@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "landlord_status", rename_all = "lowercase")]
 #[serde(rename_all = "camelCase")]
 pub enum LandlordStatus {
@@ -11,7 +12,7 @@ pub enum LandlordStatus {
     Inactive,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "landlord_type_enum", rename_all = "lowercase")]
 #[serde(rename_all = "camelCase")] 
 pub enum LandlordTypeEnum {
@@ -19,7 +20,7 @@ pub enum LandlordTypeEnum {
     Company,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "landlord_title_enum", rename_all = "lowercase")]
 #[serde(rename_all = "camelCase")]
 pub enum LandlordTitle {
@@ -34,7 +35,7 @@ pub enum LandlordTitle {
 }
 
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct LandlordDetails {
     pub landlord_id: Option<Uuid>,
     pub landlord_type: LandlordTypeEnum,
@@ -46,11 +47,17 @@ pub struct LandlordDetails {
     pub status: LandlordStatus,
     pub staff_assigned: Option<Uuid>,
     pub created_at: Option<DateTime<Utc>>,
-    pub updated_at: Option<DateTime<Utc>>
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Branch the landlord belongs to. New landlords go to the creator's branch; use
+    /// `POST /api/v1/landlords/{id}/transfer` to move one.
+    #[sqlx(default)]
+    #[schema(read_only)]
+    pub branch_id: Option<Uuid>,
 }
 
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LandlordQueryParams {
     pub status: Option<String>, 
     pub sort_by: Option<String>,
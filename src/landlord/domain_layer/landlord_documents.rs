@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct LandlordDocs {
     pub landlord_id: Uuid,
     pub id_document: Option<String>,
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "landlord_property_categories", rename_all = "lowercase")]
 
 pub enum LandlordPropertyCategory {
@@ -10,7 +11,7 @@ pub enum LandlordPropertyCategory {
     Commercial,
 }
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct LandlordGeneral {
     pub landlord_id: Uuid,
     pub notes: Option<String>,
@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+use crate::shared::domain_layer::sensitive_data::{mask, mask_opt};
+use utoipa::ToSchema;
+
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct LandlordsBankDetails {
     pub id: Uuid,
     pub landlord_id: Uuid,
@@ -14,6 +17,18 @@ pub struct LandlordsBankDetails {
     pub is_primary: bool,
 }
 
+impl LandlordsBankDetails {
+    /// Account number, sort code and IBAN reduced to their last few characters.
+    pub fn masked(self) -> Self {
+        LandlordsBankDetails {
+            account_number: mask(&self.account_number, 4),
+            sort_code: mask(&self.sort_code, 2),
+            iban: mask_opt(self.iban.as_deref(), 4),
+            ..self
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct BankDetailsRequest {
     pub account_name: String,
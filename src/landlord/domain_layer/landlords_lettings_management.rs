@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+use crate::shared::domain_layer::sensitive_data::mask_opt;
+use utoipa::ToSchema;
+
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "landlord_payment_frequency", rename_all = "lowercase")]
 pub enum LandlordPaymentFrequency {
     Weekly,
@@ -15,7 +18,7 @@ pub enum LandlordPaymentFrequency {
     FourWeekly,
 }
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct LandlordsLettingsManagement {
     pub id: Uuid,
     pub landlord_id: Uuid,
@@ -32,6 +35,17 @@ pub struct LandlordsLettingsManagement {
     pub accountant_email: Option<String>,
 }
 
+impl LandlordsLettingsManagement {
+    /// NI number and UTR reduced to their last few characters.
+    pub fn masked(self) -> Self {
+        LandlordsLettingsManagement {
+            ni_number: mask_opt(self.ni_number.as_deref(), 4),
+            unique_taxpayer_reference: mask_opt(self.unique_taxpayer_reference.as_deref(), 4),
+            ..self
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LettingsPreferencesRequest {
     pub payment_frequency: LandlordPaymentFrequency,
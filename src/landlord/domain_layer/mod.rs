@@ -6,3 +6,4 @@ pub mod landlord_registration_progress;
 pub mod landlords_address;
 pub mod landlords_bank_details;
 pub mod landlords_lettings_management;
+pub mod landlord_data_export;
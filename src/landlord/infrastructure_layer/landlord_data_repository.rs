@@ -0,0 +1,282 @@
+use actix_web::error::ResponseError;
+use chrono::{NaiveDate, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Postgres, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    diary::domain_layer::diary_event_types::Event,
+    landlord::domain_layer::{
+        landlord_additional_contact::LandlordAdditionalContact,
+        landlord_data_export::{LandlordDataExport, LandlordErasure},
+        landlord_details::LandlordDetails,
+        landlord_documents::LandlordDocs,
+        landlord_general::LandlordGeneral,
+        landlords_address::LandlordsAddress,
+    },
+    landlord::infrastructure_layer::landlord_financial_repository::{
+        self, LandlordFinancialRepository,
+    },
+    properties::domain_layer::{property_address::PropertyAddress, property_core::PropertyCore},
+    AppState,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Landlord not found")]
+    NotFound,
+    #[display(fmt = "Records must be kept until {}", _0)]
+    RetentionHold(NaiveDate),
+    #[display(fmt = "{}", _0)]
+    FinancialRecords(String),
+}
+
+impl ResponseError for CustomErrors {}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+impl From<landlord_financial_repository::CustomErrors> for CustomErrors {
+    fn from(e: landlord_financial_repository::CustomErrors) -> Self {
+        CustomErrors::FinancialRecords(e.to_string())
+    }
+}
+
+/// Diary events that mention one of the landlord's properties, or the landlord
+/// directly through a note. `$1` holds the landlord and property ids as text.
+const LINKED_EVENTS_QUERY: &str = r#"
+    SELECT * FROM events
+    WHERE id IN (
+        SELECT event_id FROM viewing_details WHERE property_id::text = ANY($1)
+        UNION SELECT event_id FROM appointment_details WHERE property_id::text = ANY($1)
+        UNION SELECT event_id FROM inspection_details WHERE property_id::text = ANY($1)
+        UNION SELECT event_id FROM valuation_details WHERE property_id::text = ANY($1)
+        UNION SELECT event_id FROM maintenance_details WHERE property_id::text = ANY($1)
+        UNION SELECT event_id FROM note_details WHERE related_entity_id::text = ANY($1)
+    )
+    ORDER BY date, start_time
+"#;
+
+pub struct LandlordDataRepository {}
+
+impl LandlordDataRepository {
+    pub fn new() -> Self {
+        LandlordDataRepository {}
+    }
+
+    pub async fn export(
+        &self,
+        state: Arc<AppState>,
+        landlord_id: Uuid,
+    ) -> Result<LandlordDataExport, CustomErrors> {
+        let pool = &state.db;
+        let details = sqlx::query_as::<_, LandlordDetails>(
+            "SELECT * FROM landlord_details WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(CustomErrors::NotFound)?;
+
+        let general = sqlx::query_as::<_, LandlordGeneral>(
+            "SELECT * FROM landlord_general WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .fetch_optional(pool)
+        .await?;
+        let addresses = sqlx::query_as::<_, LandlordsAddress>(
+            "SELECT * FROM landlords_address WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .fetch_all(pool)
+        .await?;
+        // Financial identifiers are exported in full; callers hold the reveal
+        // permission and have audited the export.
+        let financial = LandlordFinancialRepository::new();
+        let bank_details = financial
+            .get_bank_details(state.clone(), landlord_id)
+            .await?;
+        let lettings_management = financial
+            .get_lettings_management(state.clone(), landlord_id)
+            .await?;
+        let additional_contacts = sqlx::query_as::<_, LandlordAdditionalContact>(
+            "SELECT * FROM landlord_additional_contact WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .fetch_all(pool)
+        .await?;
+        let documents = sqlx::query_as::<_, LandlordDocs>(
+            "SELECT * FROM landlord_documents WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .fetch_all(pool)
+        .await?;
+        let properties = sqlx::query_as::<_, PropertyCore>(
+            "SELECT * FROM property_core WHERE landlord_id = $1 ORDER BY created_at",
+        )
+        .bind(landlord_id)
+        .fetch_all(pool)
+        .await?;
+
+        let property_ids: Vec<Uuid> = properties.iter().filter_map(|p| p.property_id).collect();
+        let property_addresses = sqlx::query_as::<_, PropertyAddress>(
+            "SELECT * FROM property_address WHERE property_id = ANY($1)",
+        )
+        .bind(&property_ids)
+        .fetch_all(pool)
+        .await?;
+
+        let mut linked_ids: Vec<String> = property_ids.iter().map(Uuid::to_string).collect();
+        linked_ids.push(landlord_id.to_string());
+        let diary_events = sqlx::query_as::<_, Event>(LINKED_EVENTS_QUERY)
+            .bind(&linked_ids)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(LandlordDataExport {
+            exported_at: Utc::now(),
+            details,
+            general,
+            addresses,
+            bank_details,
+            lettings_management,
+            additional_contacts,
+            documents,
+            properties,
+            property_addresses,
+            diary_events,
+        })
+    }
+
+    /// Anonymises a landlord's personal data. Bank details and tax records in
+    /// lettings management are financial records and are kept. Returns the
+    /// document references that were removed so their files can be deleted.
+    pub async fn erase(
+        &self,
+        state: Arc<AppState>,
+        landlord_id: Uuid,
+    ) -> Result<(LandlordErasure, Vec<String>), CustomErrors> {
+        let mut tx = state.db.begin().await?;
+        Self::check_retention_hold(&mut tx, landlord_id).await?;
+
+        let erased_at = Utc::now();
+        sqlx::query(
+            "UPDATE landlord_details
+             SET title = NULL, full_name = 'Erased', email = NULL, phone_nr = '',
+                 status = 'inactive', erased_at = $2, updated_at = $2
+             WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .bind(erased_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE landlord_general SET notes = NULL WHERE landlord_id = $1")
+            .bind(landlord_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let addresses = sqlx::query(
+            "UPDATE landlords_address
+             SET address_line_1 = 'Erased', address_line_2 = NULL, city = 'Erased',
+                 county = NULL, postcode = ''
+             WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query(
+            "UPDATE landlords_lettings_management SET accountant_email = NULL WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let additional_contacts = sqlx::query(
+            "UPDATE landlord_additional_contact
+             SET title = NULL, full_name = 'Erased', email = NULL, mobile_phone = NULL,
+                 alternative_phone = NULL, notes = NULL, updated_at = $2
+             WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .bind(erased_at)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let documents: Vec<Option<String>> = sqlx::query_scalar(
+            "DELETE FROM landlord_documents WHERE landlord_id = $1 RETURNING id_document",
+        )
+        .bind(landlord_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let erasure = LandlordErasure {
+            erased_at: Some(erased_at),
+            addresses,
+            additional_contacts,
+            documents: documents.len() as u64,
+        };
+        Ok((erasure, documents.into_iter().flatten().collect()))
+    }
+
+    /// Anonymises a single additional contact of a landlord.
+    pub async fn erase_contact(
+        &self,
+        state: Arc<AppState>,
+        landlord_id: Uuid,
+        contact_id: Uuid,
+    ) -> Result<LandlordAdditionalContact, CustomErrors> {
+        let mut tx = state.db.begin().await?;
+        Self::check_retention_hold(&mut tx, landlord_id).await?;
+
+        let contact = sqlx::query_as::<_, LandlordAdditionalContact>(
+            "UPDATE landlord_additional_contact
+             SET title = NULL, full_name = 'Erased', email = NULL, mobile_phone = NULL,
+                 alternative_phone = NULL, notes = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE landlord_id = $1 AND contact_id = $2
+             RETURNING *",
+        )
+        .bind(landlord_id)
+        .bind(contact_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(CustomErrors::NotFound)?;
+
+        tx.commit().await?;
+        Ok(contact)
+    }
+
+    async fn check_retention_hold(
+        tx: &mut Transaction<'_, Postgres>,
+        landlord_id: Uuid,
+    ) -> Result<(), CustomErrors> {
+        let hold: Option<Option<NaiveDate>> = sqlx::query_scalar(
+            "SELECT g.do_not_delete_before
+             FROM landlord_details d
+             LEFT JOIN landlord_general g ON g.landlord_id = d.landlord_id
+             WHERE d.landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+        match hold {
+            None => Err(CustomErrors::NotFound),
+            Some(Some(date)) if date > Utc::now().date_naive() => {
+                Err(CustomErrors::RetentionHold(date))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use actix_web::error::ResponseError;
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    landlord::domain_layer::{
+        landlords_bank_details::LandlordsBankDetails,
+        landlords_lettings_management::LandlordsLettingsManagement,
+    },
+    shared::infrastructure_layer::field_cipher::{CipherError, KeyRing},
+    AppState,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Encryption error: {}", _0)]
+    CipherError(String),
+}
+
+impl ResponseError for CustomErrors {}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+impl From<CipherError> for CustomErrors {
+    fn from(e: CipherError) -> Self {
+        CustomErrors::CipherError(e.to_string())
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ReencryptSummary {
+    pub bank_details: u64,
+    pub lettings_management: u64,
+}
+
+/// Bank details and tax references are stored encrypted; everything here
+/// returns them decrypted, and callers decide whether to mask them.
+pub struct LandlordFinancialRepository {}
+
+impl LandlordFinancialRepository {
+    pub fn new() -> Self {
+        LandlordFinancialRepository {}
+    }
+
+    pub async fn get_bank_details(
+        &self,
+        state: Arc<AppState>,
+        landlord_id: Uuid,
+    ) -> Result<Vec<LandlordsBankDetails>, CustomErrors> {
+        let rows = sqlx::query_as::<_, LandlordsBankDetails>(
+            "SELECT * FROM landlords_bank_details WHERE landlord_id = $1 ORDER BY is_primary DESC",
+        )
+        .bind(landlord_id)
+        .fetch_all(&state.db)
+        .await?;
+        rows.into_iter()
+            .map(|row| Self::decrypt_bank_details(&state.key_ring, row))
+            .collect()
+    }
+
+    pub async fn get_lettings_management(
+        &self,
+        state: Arc<AppState>,
+        landlord_id: Uuid,
+    ) -> Result<Vec<LandlordsLettingsManagement>, CustomErrors> {
+        let rows = sqlx::query_as::<_, LandlordsLettingsManagement>(
+            "SELECT * FROM landlords_lettings_management WHERE landlord_id = $1",
+        )
+        .bind(landlord_id)
+        .fetch_all(&state.db)
+        .await?;
+        rows.into_iter()
+            .map(|row| Self::decrypt_lettings_management(&state.key_ring, row))
+            .collect()
+    }
+
+    /// Rewrites every value not yet encrypted with the active key: plaintext
+    /// left from before encryption, or values under a retired key version.
+    pub async fn reencrypt_all(
+        &self,
+        pool: &Pool<Postgres>,
+        key_ring: &KeyRing,
+    ) -> Result<ReencryptSummary, CustomErrors> {
+        let mut summary = ReencryptSummary::default();
+
+        let bank_details =
+            sqlx::query_as::<_, LandlordsBankDetails>("SELECT * FROM landlords_bank_details")
+                .fetch_all(pool)
+                .await?;
+        for row in bank_details {
+            let stale = [
+                Some(&row.account_number),
+                Some(&row.sort_code),
+                row.iban.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|value| key_ring.needs_reencryption(value));
+            if !stale {
+                continue;
+            }
+            let row = Self::decrypt_bank_details(key_ring, row)?;
+            sqlx::query(
+                "UPDATE landlords_bank_details SET account_number = $2, sort_code = $3, iban = $4 WHERE id = $1",
+            )
+            .bind(row.id)
+            .bind(key_ring.encrypt(&row.account_number)?)
+            .bind(key_ring.encrypt(&row.sort_code)?)
+            .bind(key_ring.encrypt_opt(row.iban.as_deref())?)
+            .execute(pool)
+            .await?;
+            summary.bank_details += 1;
+        }
+
+        let lettings = sqlx::query_as::<_, LandlordsLettingsManagement>(
+            "SELECT * FROM landlords_lettings_management",
+        )
+        .fetch_all(pool)
+        .await?;
+        for row in lettings {
+            let stale = [
+                row.ni_number.as_ref(),
+                row.unique_taxpayer_reference.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|value| key_ring.needs_reencryption(value));
+            if !stale {
+                continue;
+            }
+            let row = Self::decrypt_lettings_management(key_ring, row)?;
+            sqlx::query(
+                "UPDATE landlords_lettings_management SET ni_number = $2, unique_taxpayer_reference = $3 WHERE id = $1",
+            )
+            .bind(row.id)
+            .bind(key_ring.encrypt_opt(row.ni_number.as_deref())?)
+            .bind(key_ring.encrypt_opt(row.unique_taxpayer_reference.as_deref())?)
+            .execute(pool)
+            .await?;
+            summary.lettings_management += 1;
+        }
+
+        Ok(summary)
+    }
+
+    fn decrypt_bank_details(
+        key_ring: &KeyRing,
+        row: LandlordsBankDetails,
+    ) -> Result<LandlordsBankDetails, CustomErrors> {
+        Ok(LandlordsBankDetails {
+            account_number: key_ring.decrypt(&row.account_number)?,
+            sort_code: key_ring.decrypt(&row.sort_code)?,
+            iban: key_ring.decrypt_opt(row.iban.as_deref())?,
+            ..row
+        })
+    }
+
+    fn decrypt_lettings_management(
+        key_ring: &KeyRing,
+        row: LandlordsLettingsManagement,
+    ) -> Result<LandlordsLettingsManagement, CustomErrors> {
+        Ok(LandlordsLettingsManagement {
+            ni_number: key_ring.decrypt_opt(row.ni_number.as_deref())?,
+            unique_taxpayer_reference: key_ring
+                .decrypt_opt(row.unique_taxpayer_reference.as_deref())?,
+            ..row
+        })
+    }
+}
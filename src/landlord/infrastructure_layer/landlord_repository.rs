@@ -9,6 +9,17 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::landlord::domain_layer::landlord_details::LandlordQueryParams;
+use crate::shared::{
+    domain_layer::{
+        email::{EmailRecipient, EmailTemplate, QueuedEmail},
+        outbox_event::{
+            LandlordCreated, LandlordRegistrationCompleted, OutboxEvent, LANDLORD_EVENTS_TOPIC,
+        },
+    },
+    infrastructure_layer::{
+        email_queue_repository::EmailQueueRepository, outbox_repository::OutboxRepository,
+    },
+};
 use crate::{
     landlord::domain_layer::
     landlord_details::LandlordDetails,
@@ -23,6 +34,8 @@ pub enum CustomErrors {
     NotAuthorized,
     #[display(fmt = "Validation error: {}", _0)]
     ValidationError(String),
+    #[display(fmt = "Landlord not found")]
+    NotFound,
 }
 
 impl ResponseError for CustomErrors {}
@@ -41,7 +54,7 @@ impl LandlordRepository {
     ) -> Result<Vec<LandlordDetails>, CustomErrors> {
         let base_query = "SELECT * FROM landlord_details";
         let mut query_string = base_query.to_string();
-        let mut conditions = Vec::new();
+        let mut conditions = vec!["deleted_at IS NULL".to_string()];
     
         if let Some(status) = &query.status {
             match status.to_lowercase().as_str() {
@@ -53,10 +66,8 @@ impl LandlordRepository {
             }
         }
     
-        if !conditions.is_empty() {
-            query_string.push_str(" WHERE ");
-            query_string.push_str(&conditions.join(" AND "));
-        }
+        query_string.push_str(" WHERE ");
+        query_string.push_str(&conditions.join(" AND "));
     
         if let Some(sort_by) = &query.sort_by {
             let order = query.order.as_deref().unwrap_or("ASC");
@@ -103,8 +114,13 @@ impl LandlordRepository {
     ) -> Result<JsonValue, CustomErrors> {
         let id = Uuid::new_v4();
         let now = Utc::now();
+        let mut tx = state
+            .db
+            .begin()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
         let record = sqlx::query("INSERT INTO landlord_details (landlord_id, landlord_type, title, company_name, full_name, email, phone_nr, status, staff_assigned, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)")
-            .bind(&id)
+            .bind(id)
             .bind(&landlord_details.landlord_type)
             .bind(&landlord_details.title)
             .bind(&landlord_details.company_name)
@@ -112,15 +128,135 @@ impl LandlordRepository {
             .bind(&landlord_details.email)
             .bind(&landlord_details.phone_nr)
             .bind(&landlord_details.status)
-            .bind(&landlord_details.staff_assigned)
-            .bind(&now)
-            .bind(&now)
-            .execute(&state.db)
+            .bind(landlord_details.staff_assigned)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *tx)
             .await;
+        if let Err(e) = record {
+            return Err(CustomErrors::DatabaseError(e.to_string()));
+        }
+
+        let created = LandlordCreated {
+            landlord_id: id.to_string(),
+        };
+        OutboxRepository::enqueue(
+            &mut tx,
+            &OutboxEvent::new(LANDLORD_EVENTS_TOPIC, "landlord", id, "LandlordCreated", &created),
+        )
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        if let Some(staff_id) = landlord_details.staff_assigned {
+            let name = landlord_details
+                .company_name
+                .as_ref()
+                .or(landlord_details.full_name.as_ref())
+                .cloned()
+                .unwrap_or_else(|| id.to_string());
+            let email = QueuedEmail::new(
+                EmailTemplate::AssignmentChanged,
+                EmailRecipient::Staff(staff_id),
+                json!({ "subject": format!("landlord {}", name) }),
+            );
+            EmailQueueRepository::enqueue(&mut tx, &email)
+                .await
+                .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(json!({"status": "success", "message": "Landlord details saved successfully"}))
+    }
+
+    /// Soft-deletes a landlord. The record is only purged once the retention
+    /// period has passed and never before `do_not_delete_before`.
+    pub async fn delete(&self, state: Arc<AppState>, landlord_id: Uuid) -> Result<(), CustomErrors> {
+        let result = sqlx::query(
+            "UPDATE landlord_details SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE landlord_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(landlord_id)
+        .execute(&state.db)
+        .await;
+        match result {
+            Ok(result) if result.rows_affected() == 0 => Err(CustomErrors::NotFound),
+            Ok(_) => Ok(()),
+            Err(e) => Err(CustomErrors::DatabaseError(e.to_string())),
+        }
+    }
+
+    /// Marks the registration as complete. Returns `false` when it already
+    /// was, in which case no event is raised again.
+    pub async fn complete_registration(
+        &self,
+        state: Arc<AppState>,
+        landlord_id: Uuid,
+    ) -> Result<bool, CustomErrors> {
+        let mut tx = state
+            .db
+            .begin()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let was_complete = sqlx::query_scalar::<_, bool>(
+            "SELECT g.registration_complete FROM landlord_general g
+             JOIN landlord_details d ON d.landlord_id = g.landlord_id
+             WHERE g.landlord_id = $1 AND d.deleted_at IS NULL
+             FOR UPDATE OF g",
+        )
+        .bind(landlord_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?
+        .ok_or(CustomErrors::NotFound)?;
+
+        if was_complete {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE landlord_general SET registration_complete = TRUE WHERE landlord_id = $1")
+            .bind(landlord_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let completed = LandlordRegistrationCompleted {
+            landlord_id: landlord_id.to_string(),
+        };
+        OutboxRepository::enqueue(
+            &mut tx,
+            &OutboxEvent::new(
+                LANDLORD_EVENTS_TOPIC,
+                "landlord",
+                landlord_id,
+                "LandlordRegistrationCompleted",
+                &completed,
+            ),
+        )
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(true)
+    }
+
+    pub async fn restore(
+        &self,
+        state: Arc<AppState>,
+        landlord_id: Uuid,
+    ) -> Result<LandlordDetails, CustomErrors> {
+        let record = sqlx::query_as::<_, LandlordDetails>(
+            "UPDATE landlord_details SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE landlord_id = $1 AND deleted_at IS NOT NULL RETURNING *",
+        )
+        .bind(landlord_id)
+        .fetch_optional(&state.db)
+        .await;
         match record {
-            Ok(_) => {
-                Ok(json!({"status": "success", "message": "Landlord details saved successfully"}))
-            }
+            Ok(Some(landlord)) => Ok(landlord),
+            Ok(None) => Err(CustomErrors::NotFound),
             Err(e) => Err(CustomErrors::DatabaseError(e.to_string())),
         }
     }
@@ -1 +1,4 @@
+pub mod landlord_data_repository;
+pub mod landlord_financial_repository;
 pub mod landlord_repository;
+pub mod registration_progress_repository;
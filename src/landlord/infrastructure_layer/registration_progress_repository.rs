@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+pub struct RegistrationProgressRepository {}
+
+impl RegistrationProgressRepository {
+    pub fn new() -> Self {
+        RegistrationProgressRepository {}
+    }
+
+    /// Removes registrations that were never completed and expired before
+    /// `now`, along with the data entered so far.
+    pub async fn delete_expired(
+        &self,
+        pool: &Pool<Postgres>,
+        now: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM registration_progress WHERE completed_at IS NULL AND expires_at < $1",
+        )
+        .bind(now)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
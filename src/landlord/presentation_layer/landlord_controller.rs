@@ -1,10 +1,70 @@
-use crate::landlord::application_layer::landlord_service;
-use actix_web::web;
+use crate::landlord::application_layer::{
+    landlord_data_service, landlord_financial_service, landlord_service,
+};
+use crate::properties::application_layer::properties_service;
+use crate::shared::{
+    application_layer::branch_service, infrastructure_layer::idempotency_repo::Idempotency,
+};
+use actix_web::{guard, web};
 
 pub fn landlord_configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/landlords")
             .route("", web::get().to(landlord_service::get_all_landlords)) 
-            .route("", web::post().to(landlord_service::register_landlord))
+            .service(
+                web::resource("")
+                    .guard(guard::Post())
+                    .wrap(Idempotency)
+                    .route(web::post().to(landlord_service::register_landlord)),
+            )
+            .route("/{landlord_id}", web::delete().to(landlord_service::delete_landlord))
+            .route(
+                "/{landlord_id}/registration/complete",
+                web::post().to(landlord_service::complete_registration),
+            )
+            .route(
+                "/{landlord_id}/restore",
+                web::post().to(landlord_service::restore_landlord),
+            )
+            .route(
+                "/{landlord_id}/properties",
+                web::get().to(properties_service::get_by_landlord),
+            )
+            .route(
+                "/{landlord_id}/transfer",
+                web::post().to(branch_service::transfer_landlord),
+            )
+            .route(
+                "/{landlord_id}/export",
+                web::get().to(landlord_data_service::export_landlord_data),
+            )
+            .route(
+                "/{landlord_id}/export/archive",
+                web::get().to(landlord_data_service::export_landlord_archive),
+            )
+            .route(
+                "/{landlord_id}/erasure",
+                web::post().to(landlord_data_service::erase_landlord_data),
+            )
+            .route(
+                "/{landlord_id}/contacts/{contact_id}/erasure",
+                web::post().to(landlord_data_service::erase_contact_data),
+            )
+            .route(
+                "/{landlord_id}/bank-details",
+                web::get().to(landlord_financial_service::get_bank_details),
+            )
+            .route(
+                "/{landlord_id}/bank-details/reveal",
+                web::post().to(landlord_financial_service::reveal_bank_details),
+            )
+            .route(
+                "/{landlord_id}/lettings-management",
+                web::get().to(landlord_financial_service::get_lettings_management),
+            )
+            .route(
+                "/{landlord_id}/lettings-management/reveal",
+                web::post().to(landlord_financial_service::reveal_lettings_management),
+            )
     );
 }
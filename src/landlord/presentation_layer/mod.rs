@@ -1 +1,2 @@
 pub mod landlord_controller;
+pub mod reencrypt_command;
@@ -0,0 +1,34 @@
+use clap::{ArgMatches, Command};
+use sqlx::{Pool, Postgres};
+
+use crate::landlord::infrastructure_layer::landlord_financial_repository::LandlordFinancialRepository;
+use crate::shared::infrastructure_layer::{
+    field_cipher::KeyRing, webhook_repository::WebhookRepository,
+};
+
+pub fn reencrypt_command() -> Command {
+    Command::new("reencrypt").about(
+        "Encrypt landlord bank details, tax references and webhook secrets with the newest key in FIELD_ENCRYPTION_KEYS",
+    )
+}
+
+pub async fn run(_matches: &ArgMatches, pool: &Pool<Postgres>) -> std::io::Result<()> {
+    let key_ring = KeyRing::from_env().map_err(|e| std::io::Error::other(e.to_string()))?;
+    let repo = LandlordFinancialRepository::new();
+    let summary = repo
+        .reencrypt_all(pool, &key_ring)
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    let webhook_secrets = WebhookRepository::new()
+        .reencrypt_secrets(pool, &key_ring)
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!(
+        "Re-encrypted {} bank detail, {} lettings and {} webhook records with key version {}",
+        summary.bank_details,
+        summary.lettings_management,
+        webhook_secrets,
+        key_ring.active_version()
+    );
+    Ok(())
+}
@@ -6,19 +6,45 @@
 )]
 use actix_cors::Cors;
 use actix_web::{http, web::Data, App, HttpServer};
+use clap::Command;
 use diary::presentation_layer::{
     diary_event_controller::diary_event_configure_routes,
     diary_settings_controller::diary_settings_configure_routes,
 };
 use dotenv::dotenv;
-use landlord::presentation_layer::landlord_controller::landlord_configure_routes;
+use landlord::presentation_layer::{
+    landlord_controller::landlord_configure_routes, reencrypt_command,
+};
 use listenfd::ListenFd;
+use properties::application_layer::photo_pipeline::{self, PhotoPipeline, PhotoPipelineConfig};
 use properties::presentation_layer::{
-    properties_controller::configure_routes, property_address_controller::configure_address_routes,
-    property_images_controller::configure_photos_routes,
+    properties_controller::configure_routes, property_images_controller::configure_photos_routes,
+};
+use seed::presentation_layer::seed_command;
+use shared::infrastructure_layer::field_cipher::KeyRing;
+use shared::{
+    application_layer::{
+        domain_event_consumers,
+        live_update_service::{self, LiveUpdateHub},
+        notification_service, outbox_relay, scheduled_jobs, webhook_dispatcher,
+    },
+    domain_layer::{consumer_health::ConsumerHealth, retention_policy::RetentionPolicy},
+    infrastructure_layer::{
+        branch_scope::{self, BranchScoping},
+        document_storage::{self, DocumentStorage},
+        email_transport, event_publisher,
+    },
+    presentation_layer::{
+        branch_controller::branch_configure_routes, health_controller::health_configure_routes,
+        job_controller::job_configure_routes,
+        live_update_controller::live_update_configure_routes, openapi_command,
+        openapi_controller::openapi_configure_routes, retention_command,
+        search_controller::search_configure_routes, webhook_controller::webhook_configure_routes,
+    },
 };
-use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
+use sqlx::{Pool, Postgres};
 use std::fs;
+use std::sync::Arc;
 use user::presentation_layer::user_controller::user_configure_routes;
 mod properties {
     pub mod application_layer;
@@ -45,12 +71,29 @@ mod landlord {
     pub mod infrastructure_layer;
     pub mod presentation_layer;
 }
+mod seed {
+    pub mod application_layer;
+    pub mod domain_layer;
+    pub mod infrastructure_layer;
+    pub mod presentation_layer;
+}
+mod shared {
+    pub mod application_layer;
+    pub mod domain_layer;
+    pub mod infrastructure_layer;
+    pub mod presentation_layer;
+}
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Pool<Postgres>,
     pub jwt_secret: String,
     pub upload_dir: String,
+    pub key_ring: Arc<KeyRing>,
+    pub consumer_health: Arc<ConsumerHealth>,
+    pub live_updates: Arc<LiveUpdateHub>,
+    pub document_storage: Arc<dyn DocumentStorage>,
+    pub photo_pipeline: Arc<PhotoPipeline>,
 }
 
 fn initialize_upload_directory() -> std::io::Result<String> {
@@ -71,19 +114,67 @@ fn initialize_upload_directory() -> std::io::Result<String> {
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
+    let cli = Command::new("server")
+        .subcommand(seed_command::seed_command())
+        .subcommand(retention_command::purge_command())
+        .subcommand(reencrypt_command::reencrypt_command())
+        .subcommand(openapi_command::openapi_command())
+        .get_matches();
+    if let Some(("openapi", matches)) = cli.subcommand() {
+        return openapi_command::run(matches);
+    }
     let database_url = std::env::var("DATABASE_URL_RO").expect("DATABASE_URL_RO must be set");
+
+    if let Some((name, matches)) = cli.subcommand() {
+        let pool = branch_scope::pool_options()
+            .max_connections(5)
+            .connect(&database_url)
+            .await
+            .expect("Failed to create pool");
+        return match name {
+            "purge" => retention_command::run(matches, &pool).await,
+            "reencrypt" => reencrypt_command::run(matches, &pool).await,
+            _ => seed_command::run(matches, &pool).await,
+        };
+    }
+
     let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
     let server_port = std::env::var("SERVER_PORT").expect("SERVER_PORT must be set");
     let server_host = std::env::var("SERVER_HOST").expect("SERVER_HOST must be set");
 
     let server_ip = format!("{}:{}", server_host, server_port);
     let upload_dir = initialize_upload_directory().expect("Failed to initialize upload directory");
+    let key_ring = Arc::new(KeyRing::from_env().expect("Failed to load field encryption keys"));
 
-    let pool = PgPoolOptions::new()
+    let pool = branch_scope::pool_options()
         .max_connections(1000)
         .connect(&database_url)
         .await
         .expect("Failed to create pool");
+    let publisher = event_publisher::publisher_from_env().expect("Failed to create event publisher");
+    outbox_relay::spawn_outbox_relay(pool.clone(), publisher);
+    webhook_dispatcher::spawn_webhook_dispatcher(pool.clone(), key_ring.clone());
+    let email_transport =
+        email_transport::transport_from_env().expect("Failed to create email transport");
+    notification_service::spawn_email_sender(pool.clone(), email_transport);
+    let document_storage =
+        document_storage::storage_from_env().expect("Failed to create document storage");
+    let photo_pipeline = photo_pipeline::spawn_photo_pipeline(
+        pool.clone(),
+        document_storage.clone(),
+        PhotoPipelineConfig::from_env(),
+    )?;
+    let live_updates = Arc::new(LiveUpdateHub::new());
+    live_update_service::spawn_live_update_listener(pool.clone(), live_updates.clone());
+    let consumer_health = Arc::new(ConsumerHealth::new());
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let job_runner = scheduled_jobs::spawn_job_runner(
+        pool.clone(),
+        document_storage.clone(),
+        RetentionPolicy::from_env(),
+        shutdown_rx.clone(),
+    );
+    let consumers = domain_event_consumers::spawn_consumers(consumer_health.clone(), shutdown_rx);
     let mut listenfd = ListenFd::from_env();
     let mut server = HttpServer::new(move || {
         App::new()
@@ -91,25 +182,45 @@ async fn main() -> std::io::Result<()> {
                 db: pool.clone(),
                 jwt_secret: jwt_secret.clone(),
                 upload_dir: upload_dir.clone(),
+                key_ring: key_ring.clone(),
+                consumer_health: consumer_health.clone(),
+                live_updates: live_updates.clone(),
+                document_storage: document_storage.clone(),
+                photo_pipeline: photo_pipeline.clone(),
             }))
+            .wrap(BranchScoping)
             .wrap(
                 Cors::default() // Add CORS middleware here
                     .allowed_origin("http://localhost:3000") // Adjust the origin as necessary
-                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"]) // Specify allowed methods
+                    .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"]) // Specify allowed methods
                     .allowed_headers(vec![
                         http::header::AUTHORIZATION,
                         http::header::ACCEPT,
                         http::header::CONTENT_TYPE,
+                        http::header::IF_MATCH,
+                        http::header::IF_NONE_MATCH,
+                        http::header::HeaderName::from_static("idempotency-key"),
+                        http::header::HeaderName::from_static("last-event-id"),
                     ]) // Specify allowed headers
+                    .expose_headers(vec![
+                        http::header::ETAG,
+                        http::header::HeaderName::from_static("idempotent-replayed"),
+                    ])
                     .max_age(3600),
             ) // Optional: Cache the preflight response
+            .configure(health_configure_routes)
             .configure(user_configure_routes)
             .configure(configure_photos_routes)
             .configure(configure_routes)
-            .configure(configure_address_routes)
             .configure(diary_settings_configure_routes)
             .configure(diary_event_configure_routes)
             .configure(landlord_configure_routes)
+            .configure(webhook_configure_routes)
+            .configure(live_update_configure_routes)
+            .configure(job_configure_routes)
+            .configure(search_configure_routes)
+            .configure(branch_configure_routes)
+            .configure(openapi_configure_routes)
 
         // .wrap(infrastructure_layer::auth_repo::Auth)
     });
@@ -117,11 +228,23 @@ async fn main() -> std::io::Result<()> {
         Some(listener) => server.listen(listener)?,
         None => server.bind(server_ip)?,
     };
-    // let topics = "images";
-    // let brokers = &server_host.as_str();
-    // let group_id = "0";
-
-    // consume_and_print(brokers, group_id, topics).await;
     server.run().await.unwrap();
+
+    // The server has stopped; let the consumer and job runner finish what they hold.
+    shutdown_tx.send(true).ok();
+    if actix_web::rt::time::timeout(std::time::Duration::from_secs(30), job_runner)
+        .await
+        .is_err()
+    {
+        log::warn!("Job runner did not stop within 30 seconds");
+    }
+    if let Some(consumers) = consumers {
+        if actix_web::rt::time::timeout(std::time::Duration::from_secs(30), consumers)
+            .await
+            .is_err()
+        {
+            log::warn!("Kafka consumer did not stop within 30 seconds");
+        }
+    }
     Ok(())
 }
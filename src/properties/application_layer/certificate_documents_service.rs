@@ -0,0 +1,436 @@
+use crate::properties::{
+    application_layer::properties_service::require_property,
+    domain_layer::certificate_document::{
+        extension, sniff_content_type, CertificateDocument, CertificateRenewal,
+        DocumentDownloadParams, MAX_DOCUMENT_BYTES,
+    },
+    infrastructure_layer::certificate_document_repository::{
+        CertificateDocumentRepository, CustomErrors, NewCertificateDocument,
+    },
+};
+use crate::shared::domain_layer::api_schema::{
+    CertificateDocumentUpload, ErrorResponse, IdempotencyHeader,
+};
+use crate::shared::infrastructure_layer::document_storage::StorageError;
+use crate::user::infrastructure_layer::jwt_repo;
+use crate::AppState;
+use actix_multipart::Multipart;
+use actix_web::http::header::{self, ContentDisposition};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{NaiveDate, Utc};
+use futures_util::TryStreamExt;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const DEFAULT_LINK_TTL_SECONDS: i64 = 900;
+
+/// How long download links work for, from `DOCUMENT_LINK_TTL_SECONDS`
+/// (default 15 minutes).
+fn link_ttl_seconds() -> i64 {
+    std::env::var("DOCUMENT_LINK_TTL_SECONDS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|ttl| *ttl > 0)
+        .unwrap_or(DEFAULT_LINK_TTL_SECONDS)
+}
+
+// Keyed on the JWT secret, with a prefix so a link signature can never pass
+// for anything else signed with it.
+fn link_mac(secret: &str, document_id: Uuid, expires: i64) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("certificate-document.{}.{}", document_id, expires).as_bytes());
+    mac
+}
+
+fn with_download_url(secret: &str, mut document: CertificateDocument) -> CertificateDocument {
+    let expires = Utc::now().timestamp() + link_ttl_seconds();
+    let signature = hex::encode(
+        link_mac(secret, document.document_id, expires)
+            .finalize()
+            .into_bytes(),
+    );
+    document.download_url = Some(format!(
+        "/api/v1/properties/{}/certificates/{}/documents/{}/download?expires={}&signature={}",
+        document.property_id, document.certificate_id, document.document_id, expires, signature
+    ));
+    document
+}
+
+struct Upload {
+    file_name: String,
+    bytes: Vec<u8>,
+    renewal: CertificateRenewal,
+}
+
+fn bad_request(message: impl Into<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(json!({"error": message.into()}))
+}
+
+async fn read_upload(payload: &mut Multipart) -> Result<Upload, HttpResponse> {
+    let mut file = None;
+    let mut renewal = CertificateRenewal::default();
+    while let Some(mut field) = payload
+        .try_next()
+        .await
+        .map_err(|e| bad_request(e.to_string()))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        let file_name = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .map(str::to_string);
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field
+            .try_next()
+            .await
+            .map_err(|e| bad_request(e.to_string()))?
+        {
+            if bytes.len() + chunk.len() > MAX_DOCUMENT_BYTES {
+                return Err(HttpResponse::PayloadTooLarge().json(json!({
+                    "error": format!("Documents can be at most {} MB", MAX_DOCUMENT_BYTES / (1024 * 1024))
+                })));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        match name.as_str() {
+            "file" => {
+                file = Some((
+                    file_name.unwrap_or_else(|| "certificate".to_string()),
+                    bytes,
+                ))
+            }
+            "start_date" | "expiry_date" => {
+                let value = String::from_utf8_lossy(&bytes);
+                let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                    .map_err(|_| bad_request(format!("{} must be a date like 2026-10-19", name)))?;
+                if name == "start_date" {
+                    renewal.start_date = Some(date);
+                } else {
+                    renewal.expiry_date = Some(date);
+                }
+            }
+            _ => {}
+        }
+    }
+    match file {
+        Some((file_name, bytes)) if !bytes.is_empty() => Ok(Upload {
+            file_name,
+            bytes,
+            renewal,
+        }),
+        _ => Err(bad_request("A non-empty `file` field is required")),
+    }
+}
+
+/// Every version of a certificate's document, newest first, each with a
+/// download link.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}/documents",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+    ),
+    responses(
+        (status = 200, description = "Versions of the certificate's document, newest first", body = [CertificateDocument]),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_documents(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (property_id, certificate_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = CertificateDocumentRepository::new();
+    match repo.get_all(&state.db, property_id, certificate_id).await {
+        Ok(documents) => HttpResponse::Ok().json(
+            documents
+                .into_iter()
+                .map(|document| with_download_url(&state.jwt_secret, document))
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}/documents/{document_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+        ("document_id" = Uuid, Path, description = "Document id"),
+    ),
+    responses(
+        (status = 200, description = "The document version", body = CertificateDocument),
+        (status = 404, description = "Property or document not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_document(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+) -> impl Responder {
+    let (property_id, certificate_id, document_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = CertificateDocumentRepository::new();
+    match repo
+        .get(&state.db, property_id, certificate_id, document_id)
+        .await
+    {
+        Ok(Some(document)) => {
+            HttpResponse::Ok().json(with_download_url(&state.jwt_secret, document))
+        }
+        Ok(None) => error_response(CustomErrors::NotFound),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Uploads the file behind a certificate as its next version. Sending
+/// `start_date` or `expiry_date` renews the certificate: it moves to the new
+/// dates and earlier versions stay as its history.
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}/documents",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+    ),
+    request_body(content = CertificateDocumentUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "The new document version", body = CertificateDocument),
+        (status = 400, description = "No file, a file that is not a PDF or image, or invalid dates", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "Property or certificate not found", body = ErrorResponse),
+        (status = 413, description = "File larger than 10 MB", body = ErrorResponse),
+    )
+)]
+pub async fn upload_document(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    mut payload: Multipart,
+    req: HttpRequest,
+) -> impl Responder {
+    let uploaded_by = match jwt_repo::authenticated_user_id(&req, &state).await {
+        Some(user_id) => user_id,
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Not logged in"})),
+    };
+    let (property_id, certificate_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let upload = match read_upload(&mut payload).await {
+        Ok(upload) => upload,
+        Err(response) => return response,
+    };
+    if let Err(message) = upload.renewal.validate() {
+        return bad_request(message);
+    }
+    let content_type = match sniff_content_type(&upload.bytes) {
+        Some(content_type) => content_type,
+        None => return bad_request("Documents must be PDF, JPEG, PNG or WebP files"),
+    };
+
+    let document_id = Uuid::new_v4();
+    let storage_key = format!(
+        "certificates/{}/{}/{}.{}",
+        property_id,
+        certificate_id,
+        document_id,
+        extension(content_type)
+    );
+    let document = NewCertificateDocument {
+        document_id,
+        file_name: upload.file_name,
+        content_type: content_type.to_string(),
+        size_bytes: upload.bytes.len() as i64,
+        sha256: hex::encode(Sha256::digest(&upload.bytes)),
+        storage_key: storage_key.clone(),
+        uploaded_by,
+    };
+    if let Err(e) = state
+        .document_storage
+        .put(&storage_key, content_type, upload.bytes)
+        .await
+    {
+        return storage_error(e);
+    }
+
+    let repo = CertificateDocumentRepository::new();
+    match repo
+        .create(
+            &state.db,
+            property_id,
+            certificate_id,
+            document,
+            &upload.renewal,
+        )
+        .await
+    {
+        Ok(document) => {
+            HttpResponse::Created().json(with_download_url(&state.jwt_secret, document))
+        }
+        Err(e) => {
+            // Nothing refers to the file any more.
+            if let Err(storage_e) = state.document_storage.delete(&storage_key).await {
+                log::warn!("Could not remove document {}: {}", storage_key, storage_e);
+            }
+            error_response(e)
+        }
+    }
+}
+
+/// Records that the logged-in staff member checked the document.
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}/documents/{document_id}/verify",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+        ("document_id" = Uuid, Path, description = "Document id"),
+        IdempotencyHeader,
+    ),
+    responses(
+        (status = 200, description = "The verified document", body = CertificateDocument),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "Property or document not found", body = ErrorResponse),
+        (status = 409, description = "Document already verified", body = ErrorResponse),
+    )
+)]
+pub async fn verify_document(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let verified_by = match jwt_repo::authenticated_user_id(&req, &state).await {
+        Some(user_id) => user_id,
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Not logged in"})),
+    };
+    let (property_id, certificate_id, document_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = CertificateDocumentRepository::new();
+    match repo
+        .verify(
+            &state.db,
+            property_id,
+            certificate_id,
+            document_id,
+            verified_by,
+        )
+        .await
+    {
+        Ok(document) => HttpResponse::Ok().json(with_download_url(&state.jwt_secret, document)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// The file itself. The signed link is the authorisation, so it works
+/// without logging in until it expires. When storage can serve the file
+/// itself, this redirects there.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}/documents/{document_id}/download",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+        ("document_id" = Uuid, Path, description = "Document id"),
+        DocumentDownloadParams,
+    ),
+    responses(
+        (status = 200, description = "The file", content_type = "application/octet-stream"),
+        (status = 302, description = "Redirect to the file in storage"),
+        (status = 403, description = "Link expired or signature invalid", body = ErrorResponse),
+        (status = 404, description = "Document not found", body = ErrorResponse),
+    )
+)]
+pub async fn download_document(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid, Uuid)>,
+    query: web::Query<DocumentDownloadParams>,
+) -> impl Responder {
+    let (property_id, certificate_id, document_id) = path.into_inner();
+    let remaining = query.expires - Utc::now().timestamp();
+    let signature_valid = hex::decode(&query.signature).is_ok_and(|signature| {
+        link_mac(&state.jwt_secret, document_id, query.expires)
+            .verify_slice(&signature)
+            .is_ok()
+    });
+    if !signature_valid || remaining <= 0 {
+        return HttpResponse::Forbidden()
+            .json(json!({"error": "Download link expired or invalid"}));
+    }
+
+    let repo = CertificateDocumentRepository::new();
+    let document = match repo.get_by_id(&state.db, document_id).await {
+        Ok(Some(document))
+            if document.property_id == property_id && document.certificate_id == certificate_id =>
+        {
+            document
+        }
+        Ok(_) => return error_response(CustomErrors::NotFound),
+        Err(e) => return error_response(e),
+    };
+
+    let expires_in = u32::try_from(remaining).unwrap_or(u32::MAX);
+    match state
+        .document_storage
+        .presigned_url(&document.storage_key, expires_in)
+        .await
+    {
+        Ok(Some(url)) => {
+            return HttpResponse::Found()
+                .insert_header((header::LOCATION, url))
+                .finish()
+        }
+        Ok(None) => {}
+        Err(e) => return storage_error(e),
+    }
+    match state.document_storage.get(&document.storage_key).await {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type(document.content_type)
+            .insert_header(ContentDisposition::attachment(document.file_name))
+            .insert_header((header::CACHE_CONTROL, "private, no-store"))
+            .body(bytes),
+        Err(e) => storage_error(e),
+    }
+}
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound | CustomErrors::CertificateNotFound => {
+            HttpResponse::NotFound().json(json!({"error": e.to_string()}))
+        }
+        CustomErrors::Invalid(message) => bad_request(message),
+        CustomErrors::AlreadyVerified => {
+            HttpResponse::Conflict().json(json!({"error": e.to_string()}))
+        }
+        CustomErrors::DatabaseError(_) => {
+            HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))
+        }
+    }
+}
+
+fn storage_error(e: StorageError) -> HttpResponse {
+    match e {
+        StorageError::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        StorageError::Io(_) | StorageError::Config(_) => {
+            log::error!("{}", e);
+            HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))
+        }
+    }
+}
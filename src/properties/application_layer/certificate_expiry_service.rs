@@ -0,0 +1,38 @@
+use chrono::Utc;
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+
+use crate::properties::domain_layer::property_compliance::warning_days;
+use crate::properties::infrastructure_layer::{
+    certificate_repository::CertificateRepository,
+    property_compliance_repository::PropertyComplianceRepository,
+};
+use crate::shared::infrastructure_layer::job_runner::JobResult;
+
+/// Job that raises one `CertificateExpiring` event per expiry date for
+/// certificates expiring within `CERTIFICATE_EXPIRY_WARNING_DAYS` (default 30),
+/// and diary reminders for the staff assigned to properties missing a
+/// required certificate or holding one that has expired or is expiring.
+pub async fn check_expiring_certificates(pool: Pool<Postgres>) -> JobResult {
+    let within_days = warning_days();
+    let today = Utc::now().date_naive();
+
+    let repo = CertificateRepository::new();
+    let raised = repo
+        .announce_expiring(&pool, today, within_days)
+        .await
+        .map_err(|e| e.to_string())?;
+    if raised > 0 {
+        log::info!("Raised {} certificate expiry events", raised);
+    }
+
+    let compliance_repo = PropertyComplianceRepository::new();
+    let reminders = compliance_repo
+        .raise_reminders(&pool, today, within_days)
+        .await
+        .map_err(|e| e.to_string())?;
+    if reminders > 0 {
+        log::info!("Raised {} compliance reminders", reminders);
+    }
+    Ok(Some(json!({ "raised": raised, "reminders": reminders })))
+}
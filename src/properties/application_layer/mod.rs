@@ -1,3 +1,12 @@
+pub mod certificate_documents_service;
+pub mod certificate_expiry_service;
+pub mod photo_pipeline;
 pub mod properties_service;
 pub mod property_address_service;
-pub mod property_photos_service;
\ No newline at end of file
+pub mod property_certificates_service;
+pub mod property_compliance_service;
+pub mod property_keys_service;
+pub mod property_photos_service;
+pub mod property_rental_info_service;
+pub mod property_services_service;
+pub mod property_specifications_service;
\ No newline at end of file
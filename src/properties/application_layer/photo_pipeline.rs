@@ -0,0 +1,411 @@
+use chrono::Utc;
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageReader,
+};
+use sqlx::{Pool, Postgres};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Semaphore};
+use uuid::Uuid;
+
+use crate::properties::{
+    domain_layer::property_photo::{PhotoFormat, PhotoVariant, PropertyPhoto},
+    infrastructure_layer::property_photos_repository::PropertyPhotoRepository,
+};
+use crate::shared::infrastructure_layer::document_storage::DocumentStorage;
+
+const DEFAULT_WORKERS: usize = 2;
+const DEFAULT_QUEUE_CAPACITY: usize = 100;
+const DEFAULT_PENDING_TIMEOUT_MINUTES: i64 = 60;
+const SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+const DEFAULT_WIDTHS: [u32; 3] = [320, 768, 1920];
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 70;
+const JPEG_QUALITY: u8 = 80;
+const WEBP_QUALITY: f32 = 75.0;
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// A comma-separated list from the environment, skipping entries `parse`
+/// rejects, or `default` if that leaves nothing.
+fn env_list<T>(name: &str, default: &[T], parse: impl Fn(&str) -> Option<T>) -> Vec<T>
+where
+    T: Clone,
+{
+    let values: Vec<T> = std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .filter(|value| !value.trim().is_empty())
+        .filter_map(|value| {
+            let parsed = parse(value.trim());
+            if parsed.is_none() {
+                log::warn!("Ignoring {} entry {:?}", name, value.trim());
+            }
+            parsed
+        })
+        .collect();
+    if values.is_empty() {
+        default.to_vec()
+    } else {
+        values
+    }
+}
+
+pub struct PhotoPipelineConfig {
+    /// Photos processed at once.
+    pub workers: usize,
+    /// Photos waiting for a worker before uploads have to wait too.
+    pub queue_capacity: usize,
+    /// Where uploads wait to be processed.
+    pub upload_dir: PathBuf,
+    /// Minutes after which a pending photo no instance holds the upload for
+    /// is given up on.
+    pub pending_timeout_minutes: i64,
+    /// Widths photos are rendered at. Photos are never scaled up.
+    pub widths: Vec<u32>,
+    /// Formats each width is rendered in.
+    pub formats: Vec<PhotoFormat>,
+}
+
+impl PhotoPipelineConfig {
+    pub fn from_env() -> Self {
+        PhotoPipelineConfig {
+            workers: env_or("PHOTO_WORKERS", DEFAULT_WORKERS).max(1),
+            queue_capacity: env_or("PHOTO_QUEUE_CAPACITY", DEFAULT_QUEUE_CAPACITY).max(1),
+            upload_dir: std::env::var("PHOTO_UPLOAD_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| std::env::temp_dir().join("property-photos")),
+            pending_timeout_minutes: env_or(
+                "PHOTO_PENDING_TIMEOUT_MINUTES",
+                DEFAULT_PENDING_TIMEOUT_MINUTES,
+            ),
+            widths: env_list("PHOTO_RENDITION_WIDTHS", &DEFAULT_WIDTHS, |value| {
+                value.parse().ok().filter(|width| *width > 0)
+            }),
+            formats: env_list(
+                "PHOTO_RENDITION_FORMATS",
+                &PhotoFormat::ALL,
+                PhotoFormat::parse,
+            ),
+        }
+    }
+}
+
+/// Hands uploaded photos to the workers that process them.
+pub struct PhotoPipeline {
+    queue: mpsc::Sender<Uuid>,
+    upload_dir: PathBuf,
+}
+
+impl PhotoPipeline {
+    /// Where uploads are written while they wait to be processed.
+    pub fn upload_dir(&self) -> &Path {
+        &self.upload_dir
+    }
+
+    /// Queues a recorded photo, waiting while the queue is full. A photo that
+    /// cannot be queued stays pending and is picked up again on restart.
+    pub async fn enqueue(&self, photo_id: Uuid) {
+        if self.queue.send(photo_id).await.is_err() {
+            log::error!(
+                "Photo pipeline has stopped; photo {} stays pending",
+                photo_id
+            );
+        }
+    }
+}
+
+/// Starts the photo workers. Photos left pending by an earlier run are queued
+/// again if their upload is still on this instance's disk.
+pub fn spawn_photo_pipeline(
+    pool: Pool<Postgres>,
+    storage: Arc<dyn DocumentStorage>,
+    config: PhotoPipelineConfig,
+) -> std::io::Result<Arc<PhotoPipeline>> {
+    std::fs::create_dir_all(&config.upload_dir)?;
+    let (sender, mut receiver) = mpsc::channel(config.queue_capacity);
+    let pipeline = Arc::new(PhotoPipeline {
+        queue: sender,
+        upload_dir: config.upload_dir,
+    });
+
+    let workers = Arc::new(Semaphore::new(config.workers));
+    let renditions = Arc::new(Renditions {
+        widths: config.widths,
+        formats: config.formats,
+    });
+    let (worker_pool, worker_storage) = (pool.clone(), storage);
+    actix_web::rt::spawn(async move {
+        while let Some(photo_id) = receiver.recv().await {
+            let permit = match workers.clone().acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => break,
+            };
+            let (pool, storage) = (worker_pool.clone(), worker_storage.clone());
+            let renditions = renditions.clone();
+            actix_web::rt::spawn(async move {
+                process(&pool, storage.as_ref(), &renditions, photo_id).await;
+                drop(permit);
+            });
+        }
+    });
+
+    let recovering = pipeline.clone();
+    let timeout = chrono::Duration::minutes(config.pending_timeout_minutes);
+    actix_web::rt::spawn(async move {
+        let mut first_run = true;
+        loop {
+            sweep(&pool, &recovering, timeout, first_run).await;
+            first_run = false;
+            actix_web::rt::time::sleep(SWEEP_INTERVAL).await;
+        }
+    });
+    Ok(pipeline)
+}
+
+/// Queues pending photos whose upload is here, on the first run only since
+/// they are queued from then on, and fails those nobody holds the upload for
+/// any more.
+async fn sweep(
+    pool: &Pool<Postgres>,
+    pipeline: &PhotoPipeline,
+    timeout: chrono::Duration,
+    first_run: bool,
+) {
+    let repo = PropertyPhotoRepository::new();
+    let pending = match repo.get_all_pending(pool).await {
+        Ok(pending) => pending,
+        Err(e) => {
+            log::error!("Checking pending photos failed: {}", e);
+            return;
+        }
+    };
+    let mut abandoned = Vec::new();
+    for photo in pending {
+        let held_here = photo
+            .temp_path
+            .as_deref()
+            .is_some_and(|temp_path| Path::new(temp_path).is_file());
+        if !held_here {
+            abandoned.push(photo.photo_id);
+        } else if first_run {
+            pipeline.enqueue(photo.photo_id).await;
+        }
+    }
+    if abandoned.is_empty() {
+        return;
+    }
+    match repo
+        .fail_stale(
+            pool,
+            &abandoned,
+            Utc::now() - timeout,
+            "The upload was lost before it could be processed",
+        )
+        .await
+    {
+        Ok(0) => {}
+        Ok(failed) => log::warn!("Gave up on {} photos whose uploads were lost", failed),
+        Err(e) => log::error!("Failing abandoned photos failed: {}", e),
+    }
+}
+
+async fn process(
+    pool: &Pool<Postgres>,
+    storage: &dyn DocumentStorage,
+    renditions: &Arc<Renditions>,
+    photo_id: Uuid,
+) {
+    let repo = PropertyPhotoRepository::new();
+    let photo = match repo.get_pending(pool, photo_id).await {
+        Ok(Some(photo)) => photo,
+        Ok(None) => return,
+        Err(e) => {
+            // Left pending, so it is picked up again on restart.
+            log::error!("Loading photo {} failed: {}", photo_id, e);
+            return;
+        }
+    };
+    let temp_path = match photo.temp_path.clone() {
+        Some(temp_path) => PathBuf::from(temp_path),
+        None => return,
+    };
+
+    let result = match store(&photo, &temp_path, storage, renditions).await {
+        Ok(variants) => match repo.mark_ready(pool, photo_id, &variants).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                discard(storage, &variants).await;
+                Ok(())
+            }
+            Err(e) => {
+                discard(storage, &variants).await;
+                Err(e)
+            }
+        },
+        Err(error) => {
+            log::warn!("Processing photo {} failed: {}", photo_id, error);
+            repo.mark_failed(pool, photo_id, &error).await
+        }
+    };
+    match result {
+        Ok(()) => {
+            if let Err(e) = tokio::fs::remove_file(&temp_path).await {
+                log::warn!("Could not remove upload {}: {}", temp_path.display(), e);
+            }
+        }
+        Err(e) => log::error!("Recording photo {} failed: {}", photo_id, e),
+    }
+}
+
+/// Removes stored renditions nothing refers to.
+async fn discard(storage: &dyn DocumentStorage, variants: &[PhotoVariant]) {
+    for variant in variants {
+        if let Err(e) = storage.delete(&variant.storage_key).await {
+            log::warn!("Could not remove {}: {}", variant.storage_key, e);
+        }
+    }
+}
+
+/// The sizes and formats photos are rendered in.
+struct Renditions {
+    widths: Vec<u32>,
+    formats: Vec<PhotoFormat>,
+}
+
+/// One encoded rendition.
+struct Rendition {
+    format: PhotoFormat,
+    width: u32,
+    height: u32,
+    bytes: Vec<u8>,
+}
+
+/// Renders a photo and stores every rendition, removing them again if any
+/// cannot be stored.
+async fn store(
+    photo: &PropertyPhoto,
+    temp_path: &Path,
+    storage: &dyn DocumentStorage,
+    renditions: &Arc<Renditions>,
+) -> Result<Vec<PhotoVariant>, String> {
+    let path = temp_path.to_path_buf();
+    let wanted = renditions.clone();
+    let rendered = tokio::task::spawn_blocking(move || render(&path, &wanted))
+        .await
+        .map_err(|e| format!("Processing stopped: {}", e))??;
+
+    let mut variants = Vec::with_capacity(rendered.len());
+    for rendition in rendered {
+        let storage_key = format!(
+            "photos/{}/{}/{}.{}",
+            photo.property_id,
+            photo.photo_id,
+            rendition.width,
+            rendition.format.extension()
+        );
+        if let Err(e) = storage
+            .put(
+                &storage_key,
+                rendition.format.content_type(),
+                rendition.bytes,
+            )
+            .await
+        {
+            discard(storage, &variants).await;
+            return Err(e.to_string());
+        }
+        variants.push(PhotoVariant {
+            photo_id: photo.photo_id,
+            format: rendition.format,
+            width: rendition.width as i32,
+            height: rendition.height as i32,
+            storage_key,
+            url: None,
+        });
+    }
+    Ok(variants)
+}
+
+/// Decodes an upload and encodes it at each configured width no wider than
+/// the photo itself, or just at its own width if it is narrower than all of
+/// them, in each configured format.
+fn render(path: &Path, renditions: &Renditions) -> Result<Vec<Rendition>, String> {
+    let image = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Could not read the upload: {}", e))?
+        .decode()
+        .map_err(|e| format!("Not a readable image: {}", e))?;
+    // The encoders take 8-bit RGB(A) only.
+    let image = if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    };
+
+    let mut widths: Vec<u32> = renditions
+        .widths
+        .iter()
+        .map(|width| (*width).min(image.width()))
+        .collect();
+    widths.sort_unstable();
+    widths.dedup();
+
+    let mut rendered = Vec::with_capacity(widths.len() * renditions.formats.len());
+    for width in widths {
+        let scaled = if width < image.width() {
+            image.resize(width, u32::MAX, FilterType::Triangle)
+        } else {
+            image.clone()
+        };
+        for format in &renditions.formats {
+            rendered.push(Rendition {
+                format: *format,
+                width: scaled.width(),
+                height: scaled.height(),
+                bytes: encode(&scaled, *format)
+                    .map_err(|e| format!("Could not encode the image: {}", e))?,
+            });
+        }
+    }
+    Ok(rendered)
+}
+
+/// Encodes an 8-bit RGB(A) image.
+fn encode(image: &DynamicImage, format: PhotoFormat) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    match format {
+        PhotoFormat::Avif => image
+            .write_with_encoder(AvifEncoder::new_with_speed_quality(
+                &mut bytes,
+                AVIF_SPEED,
+                AVIF_QUALITY,
+            ))
+            .map_err(|e| e.to_string())?,
+        // The image crate only writes lossless WebP, which is larger than
+        // the JPEG for photographs, so libwebp is used instead.
+        PhotoFormat::Webp => {
+            let encoder = if image.color().has_alpha() {
+                webp::Encoder::from_rgba(image.as_bytes(), image.width(), image.height())
+            } else {
+                webp::Encoder::from_rgb(image.as_bytes(), image.width(), image.height())
+            };
+            bytes = encoder
+                .encode_simple(false, WEBP_QUALITY)
+                .map_err(|e| format!("{:?}", e))?
+                .to_vec();
+        }
+        // JPEG has no transparency.
+        PhotoFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))
+            .map_err(|e| e.to_string())?,
+    }
+    Ok(bytes)
+}
@@ -1,10 +1,31 @@
 use crate::properties::{
-    domain_layer::property_core::PropertyCore,
-    infrastructure_layer::properties_repository::PropertyRepository,
+    domain_layer::{
+        property_core::{PropertyCore, PropertyCorePatch, PropertyCoreUpdate},
+        property_full::{PropertyFull, PropertyFullQueryParams, PropertySection},
+        property_search::{PropertySearchParams, PropertySearchResponse},
+        property_status_history::{DaysOnMarket, PropertyStatusHistory, PropertyStatusTransition},
+    },
+    infrastructure_layer::{
+        properties_repository::{CustomErrors, PropertyRepository},
+        property_full_repository::PropertyFullRepository,
+        property_search_repository::PropertySearchRepository,
+    },
 };
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
+use crate::user::infrastructure_layer::jwt_repo;
 use crate::AppState;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use serde_json::json;
+use uuid::Uuid;
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties",
+    tag = "properties",
+    responses((status = 200, description = "All properties", body = [PropertyCore]))
+)]
 pub async fn get_all(state: web::Data<AppState>) -> impl Responder {
     let repo = PropertyRepository::new();
     match repo.get_all(state.into_inner()).await {
@@ -13,10 +34,345 @@ pub async fn get_all(state: web::Data<AppState>) -> impl Responder {
     }
 }
 
-pub async fn add(state: web::Data<AppState>, property: web::Json<PropertyCore>) -> impl Responder {
+/// Properties matching every filter given, one page at a time. Filters on
+/// bedrooms, rent, town and the like only match properties with those
+/// details recorded.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/search",
+    tag = "properties",
+    params(PropertySearchParams),
+    responses(
+        (status = 200, description = "One page of matching properties", body = PropertySearchResponse),
+        (status = 400, description = "Invalid filter, range or page", body = ErrorResponse),
+    )
+)]
+pub async fn search(
+    state: web::Data<AppState>,
+    params: web::Query<PropertySearchParams>,
+) -> impl Responder {
+    if let Err(message) = params.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    let repo = PropertySearchRepository::new();
+    match repo.search(&state.db, &params).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+/// Creates a property, starting its status history. Replacing an existing
+/// property cannot change its status.
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties",
+    tag = "properties",
+    params(IdempotencyHeader),
+    responses(
+        (status = 200, description = "The saved property", body = PropertyCore),
+        (status = 409, description = "A request with this `Idempotency-Key` is still in progress, or the property exists with another status", body = ErrorResponse),
+        (status = 422, description = "The `Idempotency-Key` was used with a different body", body = ErrorResponse),
+    )
+)]
+pub async fn add(state: web::Data<AppState>, property: web::Json<PropertyCore>, req: HttpRequest) -> impl Responder {
+    let changed_by = jwt_repo::authenticated_user_id(&req, &state).await;
     let repo = PropertyRepository::new();
-    match repo.save_property(state.into_inner(), property.into_inner()).await {
+    match repo.save_property(state.into_inner(), property.into_inner(), changed_by).await {
         Ok(property) => HttpResponse::Ok().json(property),
+        Err(e) => update_error(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "The property", body = PropertyCore, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_one(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let repo = PropertyRepository::new();
+    match repo.get_one_by_id(property_id.into_inner(), state.into_inner()).await {
+        Ok(Some(property)) => conditional_request::conditional_get(&req, Some(property.updated_at), &property),
+        Ok(None) => HttpResponse::NotFound().json(json!({"error": "Property not found"})),
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+/// The property with its address, specifications, rental info, services,
+/// certificates, images, landlord and assigned staff member in one response.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/full",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), PropertyFullQueryParams),
+    responses(
+        (status = 200, description = "The property and the sections asked for", body = PropertyFull),
+        (status = 400, description = "Unknown section in `include`", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_full(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    query: web::Query<PropertyFullQueryParams>,
+) -> impl Responder {
+    let sections: Vec<PropertySection> = match &query.include {
+        Some(include) => {
+            let mut sections = Vec::new();
+            for value in include.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+                match PropertySection::parse(value) {
+                    Some(section) => sections.push(section),
+                    None => {
+                        return HttpResponse::BadRequest().json(json!({
+                            "error": format!(
+                                "Unknown section {}; use {}",
+                                value,
+                                PropertySection::ALL.map(|section| section.as_str()).join(", ")
+                            )
+                        }))
+                    }
+                }
+            }
+            sections
+        }
+        None => PropertySection::ALL.to_vec(),
+    };
+
+    let repo = PropertyFullRepository::new();
+    match repo.get(&state.db, property_id.into_inner(), &sections).await {
+        Ok(Some(property)) => HttpResponse::Ok().json(property),
+        Ok(None) => HttpResponse::NotFound().json(json!({"error": "Property not found"})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+/// Replaces a property's details. Changing the assigned staff member is emailed
+/// the same way as on creation. The status must stay as it is; change it with
+/// `POST /api/v1/properties/{id}/status`.
+#[utoipa::path(
+    put,
+    path = "/api/v1/properties/{property_id}",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IfMatchHeader),
+    request_body = PropertyCoreUpdate,
+    responses(
+        (status = 200, description = "The updated property", body = PropertyCore, headers(("ETag" = String))),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 409, description = "The status was changed", body = ErrorResponse),
+        (status = 412, description = "The property changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn update(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<PropertyCoreUpdate>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
+    let repo = PropertyRepository::new();
+    let details = body.into_inner();
+    match repo.update(property_id.into_inner(), expected_updated_at, state.into_inner(), |_| details).await {
+        Ok(property) => conditional_request::updated(Some(property.updated_at), &property),
+        Err(e) => update_error(e),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/properties/{property_id}",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IfMatchHeader),
+    request_body = PropertyCorePatch,
+    responses(
+        (status = 200, description = "The updated property", body = PropertyCore, headers(("ETag" = String))),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 409, description = "The status was changed", body = ErrorResponse),
+        (status = 412, description = "The property changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn patch(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<PropertyCorePatch>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
+    let repo = PropertyRepository::new();
+    let patch = body.into_inner();
+    match repo.update(property_id.into_inner(), expected_updated_at, state.into_inner(), |property| patch.apply_to(property)).await {
+        Ok(property) => conditional_request::updated(Some(property.updated_at), &property),
+        Err(e) => update_error(e),
+    }
+}
+
+/// Moves a property to another status. Only some moves are allowed: a let
+/// property, for one, can only move on once its tenancy has ended. The change
+/// is recorded in the property's status history and published as a
+/// `PropertyStatusChanged` event.
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/status",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IdempotencyHeader),
+    request_body = PropertyStatusTransition,
+    responses(
+        (status = 200, description = "The property with its new status", body = PropertyCore, headers(("ETag" = String))),
+        (status = 400, description = "Missing reason or tenancy end date", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 409, description = "The property cannot move to that status now, or a request with this `Idempotency-Key` is still in progress", body = ErrorResponse),
+        (status = 422, description = "The `Idempotency-Key` was used with a different body", body = ErrorResponse),
+    )
+)]
+pub async fn transition_status(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<PropertyStatusTransition>,
+    req: HttpRequest,
+) -> impl Responder {
+    let changed_by = match jwt_repo::authenticated_user_id(&req, &state).await {
+        Some(user_id) => user_id,
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Not logged in"})),
+    };
+    let mut transition = body.into_inner();
+    if let Err(message) = transition.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    let repo = PropertyRepository::new();
+    match repo.transition_status(property_id.into_inner(), &transition, changed_by, state.into_inner()).await {
+        Ok(property) => conditional_request::updated(Some(property.updated_at), &property),
+        Err(e) => update_error(e),
+    }
+}
+
+/// Every status a property has had, oldest first, with how long it has spent
+/// on the market.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/status-history",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "The property's status history", body = PropertyStatusHistory),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_status_history(state: web::Data<AppState>, property_id: web::Path<Uuid>) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyRepository::new();
+    match repo.get_status_history(property_id, &state.db).await {
+        Ok(history) => {
+            let metrics = DaysOnMarket::from_history(&history, Utc::now());
+            HttpResponse::Ok().json(PropertyStatusHistory { property_id, history, metrics })
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+/// Details of a property are only reachable through a property the caller can
+/// see that has not been deleted.
+pub async fn require_property(state: &web::Data<AppState>, property_id: Uuid) -> Result<(), HttpResponse> {
+    let repo = PropertyRepository::new();
+    match repo.get_one_by_id(property_id, state.clone().into_inner()).await {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::NotFound().json(json!({"error": "Property not found"}))),
+        Err(e) => Err(HttpResponse::InternalServerError().json(e)),
+    }
+}
+
+fn update_error(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": "Property not found"})),
+        CustomErrors::PreconditionFailed => conditional_request::precondition_failed(),
+        CustomErrors::IllegalTransition(message) => HttpResponse::Conflict().json(json!({"error": message})),
+        CustomErrors::DatabaseError(_) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/landlords/{landlord_id}/properties",
+    tag = "properties",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    responses((status = 200, description = "The landlord's properties", body = [PropertyCore]))
+)]
+pub async fn get_by_landlord(state: web::Data<AppState>, landlord_id: web::Path<Uuid>) -> impl Responder {
+    let repo = PropertyRepository::new();
+    match repo.get_one_by_user_id(landlord_id.into_inner(), state.into_inner()).await {
+        Ok(properties) => HttpResponse::Ok().json(properties),
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}/properties",
+    tag = "properties",
+    params(("user_id" = Uuid, Path, description = "Staff member's user id")),
+    responses((status = 200, description = "Properties assigned to the staff member", body = [PropertyCore]))
+)]
+pub async fn get_by_staff(state: web::Data<AppState>, user_id: web::Path<Uuid>) -> impl Responder {
+    let repo = PropertyRepository::new();
+    match repo.get_by_staff_id(user_id.into_inner(), state.into_inner()).await {
+        Ok(properties) => HttpResponse::Ok().json(properties),
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/properties/{property_id}",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 204, description = "Property archived; restore it with `POST /api/v1/properties/{property_id}/restore`"),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete(state: web::Data<AppState>, property_id: web::Path<Uuid>) -> impl Responder {
+    let repo = PropertyRepository::new();
+    match repo.delete(property_id.into_inner(), state.into_inner()).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json(json!({"error": "Property not found"})),
+        Err(e) => HttpResponse::InternalServerError().json(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/restore",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "The restored property", body = PropertyCore),
+        (status = 404, description = "Deleted property not found", body = ErrorResponse),
+    )
+)]
+pub async fn restore(state: web::Data<AppState>, property_id: web::Path<Uuid>) -> impl Responder {
+    let repo = PropertyRepository::new();
+    match repo.restore(property_id.into_inner(), state.into_inner()).await {
+        Ok(Some(property)) => HttpResponse::Ok().json(property),
+        Ok(None) => HttpResponse::NotFound().json(json!({"error": "Deleted property not found"})),
         Err(e) => HttpResponse::InternalServerError().json(e),
     }
 }
@@ -1,68 +1,146 @@
-use crate::properties::{
-    domain_layer::property_address::PropertyAddress,
-    infrastructure_layer::property_address_repository::PropertyAddressRepository,
-};
-use crate::AppState;
-use actix_web::{web, HttpResponse, Responder};
-use uuid::Uuid;
-
-pub async fn get_all(state: web::Data<AppState>) -> impl Responder {
-    let repo = PropertyAddressRepository::new();
-    match repo.get_all(state.into_inner()).await {
-        Ok(property_addresses) => HttpResponse::Ok().json(property_addresses),
-        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
-    }
-}
-
-pub async fn add(
-    state: web::Data<AppState>,
-    property_address: web::Json<PropertyAddress>,
-) -> impl Responder {
-    let repo = PropertyAddressRepository::new();
-    match repo
-        .save(property_address.into_inner(), state.into_inner())
-        .await
-    {
-        Ok(property_address) => HttpResponse::Ok().json(property_address),
-        Err(e) => HttpResponse::InternalServerError().json(e),
-    }
-}
-
-pub async fn get_by_id(state: web::Data<AppState>, address_id: web::Path<Uuid>) -> impl Responder {
-    let repo = PropertyAddressRepository::new();
-    match repo
-        .get_one_by_id(address_id.into_inner(), state.into_inner())
-        .await
-    {
-        Ok(property_address) => HttpResponse::Ok().json(property_address),
-        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
-    }
-}
-
-pub async fn get_by_property_id(
-    state: web::Data<AppState>,
-    property_id: web::Path<Uuid>,
-) -> impl Responder {
-    let repo = PropertyAddressRepository::new();
-    match repo
-        .get_one_by_property_id(property_id.into_inner(), state.into_inner())
-        .await
-    {
-        Ok(property_addresses) => HttpResponse::Ok().json(property_addresses),
-        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
-    }
-}
-
-pub async fn update(
-    state: web::Data<AppState>,
-    property_address: web::Json<PropertyAddress>,
-) -> impl Responder {
-    let repo = PropertyAddressRepository::new();
-    match repo
-        .save(property_address.into_inner(), state.into_inner())
-        .await
-    {
-        Ok(property_address) => HttpResponse::Ok().json(property_address),
-        Err(e) => HttpResponse::InternalServerError().json(e),
-    }
-}
+use crate::properties::{
+    application_layer::properties_service::require_property,
+    domain_layer::property_address::{PropertyAddress, PropertyAddressRequest},
+    infrastructure_layer::property_address_repository::{CustomErrors, PropertyAddressRepository},
+};
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/address",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "The property's address", body = PropertyAddress, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Property or address not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_by_property_id(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyAddressRepository::new();
+    match repo
+        .get_by_property_id(property_id, state.into_inner())
+        .await
+    {
+        Ok(Some(property_address)) => conditional_request::conditional_get(
+            &req,
+            Some(property_address.updated_at),
+            &property_address,
+        ),
+        Ok(None) => error_response(CustomErrors::NotFound),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Adds the address of a property. A property has one address; change it
+/// with `PUT`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/address",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IdempotencyHeader),
+    request_body = PropertyAddressRequest,
+    responses(
+        (status = 201, description = "The saved address", body = PropertyAddress),
+        (status = 400, description = "Missing address lines or an invalid UK postcode", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 409, description = "The property already has an address", body = ErrorResponse),
+    )
+)]
+pub async fn add(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    property_address: web::Json<PropertyAddressRequest>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    let property_address = match property_address.into_inner().normalise() {
+        Ok(property_address) => property_address,
+        Err(message) => return HttpResponse::BadRequest().json(json!({"error": message})),
+    };
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyAddressRepository::new();
+    match repo
+        .save(property_id, property_address, state.into_inner())
+        .await
+    {
+        Ok(property_address) => HttpResponse::Created().json(property_address),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/properties/{property_id}/address",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IfMatchHeader),
+    request_body = PropertyAddressRequest,
+    responses(
+        (status = 200, description = "The updated address", body = PropertyAddress, headers(("ETag" = String))),
+        (status = 400, description = "Missing address lines or an invalid UK postcode", body = ErrorResponse),
+        (status = 404, description = "Property or address not found", body = ErrorResponse),
+        (status = 412, description = "The address changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn update(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    property_address: web::Json<PropertyAddressRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
+    let property_id = property_id.into_inner();
+    let property_address = match property_address.into_inner().normalise() {
+        Ok(property_address) => property_address,
+        Err(message) => return HttpResponse::BadRequest().json(json!({"error": message})),
+    };
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyAddressRepository::new();
+    match repo
+        .update(
+            property_id,
+            property_address,
+            expected_updated_at,
+            state.into_inner(),
+        )
+        .await
+    {
+        Ok(property_address) => {
+            conditional_request::updated(Some(property_address.updated_at), &property_address)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::PreconditionFailed => conditional_request::precondition_failed(),
+        CustomErrors::AlreadyExists => {
+            HttpResponse::Conflict().json(json!({"error": e.to_string()}))
+        }
+        CustomErrors::DatabaseError(_) => {
+            HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::properties::{
+    application_layer::properties_service::require_property,
+    domain_layer::property_certificates::{Certificate, CertificateRequest},
+    infrastructure_layer::{
+        certificate_document_repository::CertificateDocumentRepository,
+        certificate_repository::{CertificateRepository, CustomErrors},
+    },
+};
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/certificates",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "Certificates of the property, soonest expiry first", body = [Certificate]),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_certificates(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = CertificateRepository::new();
+    match repo.get_all(&state.db, property_id).await {
+        Ok(certificates) => HttpResponse::Ok().json(certificates),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+    ),
+    responses(
+        (status = 200, description = "The certificate", body = Certificate, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Property or certificate not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_certificate(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (property_id, certificate_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = CertificateRepository::new();
+    match repo.get(&state.db, property_id, certificate_id).await {
+        Ok(Some(certificate)) => {
+            conditional_request::conditional_get(&req, certificate.updated_at, &certificate)
+        }
+        Ok(None) => error_response(CustomErrors::NotFound),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/certificates",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IdempotencyHeader),
+    request_body = CertificateRequest,
+    responses(
+        (status = 201, description = "The saved certificate", body = Certificate),
+        (status = 400, description = "An expiry date before the start date", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn add_certificate(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<CertificateRequest>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = CertificateRepository::new();
+    match repo.create(&state.db, property_id, request).await {
+        Ok(certificate) => HttpResponse::Created().json(certificate),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+        IfMatchHeader,
+    ),
+    request_body = CertificateRequest,
+    responses(
+        (status = 200, description = "The updated certificate", body = Certificate, headers(("ETag" = String))),
+        (status = 400, description = "An expiry date before the start date", body = ErrorResponse),
+        (status = 404, description = "Property or certificate not found", body = ErrorResponse),
+        (status = 412, description = "The certificate changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn update_certificate(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<CertificateRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
+    let (property_id, certificate_id) = path.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = CertificateRepository::new();
+    match repo
+        .update(
+            &state.db,
+            property_id,
+            certificate_id,
+            request,
+            expected_updated_at,
+        )
+        .await
+    {
+        Ok(certificate) => conditional_request::updated(certificate.updated_at, &certificate),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/properties/{property_id}/certificates/{certificate_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("certificate_id" = Uuid, Path, description = "Certificate id"),
+    ),
+    responses(
+        (status = 204, description = "Certificate and its documents removed"),
+        (status = 404, description = "Property or certificate not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_certificate(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (property_id, certificate_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let storage_keys = match CertificateDocumentRepository::new()
+        .storage_keys(&state.db, property_id, certificate_id)
+        .await
+    {
+        Ok(storage_keys) => storage_keys,
+        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    };
+    let repo = CertificateRepository::new();
+    match repo.delete(&state.db, property_id, certificate_id).await {
+        Ok(()) => {
+            // The rows went with the certificate; a file left behind is only
+            // wasted space, so failures are logged rather than reported.
+            for storage_key in &storage_keys {
+                if let Err(e) = state.document_storage.delete(storage_key).await {
+                    log::warn!("Could not remove document {}: {}", storage_key, e);
+                }
+            }
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::PreconditionFailed => conditional_request::precondition_failed(),
+        CustomErrors::DatabaseError(_) => {
+            HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))
+        }
+    }
+}
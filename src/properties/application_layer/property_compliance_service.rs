@@ -0,0 +1,119 @@
+use crate::properties::{
+    domain_layer::property_compliance::{
+        warning_days, ComplianceDashboard, ComplianceDashboardParams, ComplianceQueryParams,
+        ComplianceTotals, PropertyCompliance,
+    },
+    infrastructure_layer::property_compliance_repository::PropertyComplianceRepository,
+};
+use crate::shared::domain_layer::api_schema::ErrorResponse;
+use crate::AppState;
+use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use serde_json::json;
+use uuid::Uuid;
+
+const MAX_WARNING_DAYS: i32 = 365;
+
+fn within_days(value: Option<i32>) -> Result<i32, HttpResponse> {
+    match value {
+        None => Ok(warning_days()),
+        Some(days) if (0..=MAX_WARNING_DAYS).contains(&days) => Ok(days),
+        Some(_) => Err(HttpResponse::BadRequest().json(json!({
+            "error": format!("within_days must be between 0 and {}", MAX_WARNING_DAYS)
+        }))),
+    }
+}
+
+/// Whether a property holds the certificates its letting classification
+/// requires.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/compliance",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), ComplianceQueryParams),
+    responses(
+        (status = 200, description = "The property's compliance", body = PropertyCompliance),
+        (status = 400, description = "Invalid `within_days`", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_compliance(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    query: web::Query<ComplianceQueryParams>,
+) -> impl Responder {
+    let within_days = match within_days(query.within_days) {
+        Ok(within_days) => within_days,
+        Err(response) => return response,
+    };
+    let repo = PropertyComplianceRepository::new();
+    match repo
+        .get(
+            &state.db,
+            property_id.into_inner(),
+            Utc::now().date_naive(),
+            within_days,
+        )
+        .await
+    {
+        Ok(Some(compliance)) => HttpResponse::Ok().json(compliance),
+        Ok(None) => HttpResponse::NotFound().json(json!({"error": "Property not found"})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+/// Compliance across the portfolio: how many properties stand where, and
+/// each property's requirements.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/compliance",
+    tag = "properties",
+    params(ComplianceDashboardParams),
+    responses(
+        (status = 200, description = "The compliance dashboard", body = ComplianceDashboard),
+        (status = 400, description = "Invalid `within_days` or `status`", body = ErrorResponse),
+    )
+)]
+pub async fn get_dashboard(
+    state: web::Data<AppState>,
+    query: web::Query<ComplianceDashboardParams>,
+) -> impl Responder {
+    let within_days = match within_days(query.within_days) {
+        Ok(within_days) => within_days,
+        Err(response) => return response,
+    };
+    let repo = PropertyComplianceRepository::new();
+    let mut properties = match repo
+        .get_portfolio(
+            &state.db,
+            query.staff_id,
+            Utc::now().date_naive(),
+            within_days,
+        )
+        .await
+    {
+        Ok(properties) => properties,
+        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    };
+
+    let mut totals = ComplianceTotals::default();
+    for property in &properties {
+        totals.count(property.status);
+    }
+    if let Some(status) = query.status {
+        properties.retain(|property| property.status == status);
+    }
+    properties.sort_by_key(|property| {
+        let soonest = property
+            .requirements
+            .iter()
+            .filter_map(|requirement| requirement.days_remaining)
+            .min();
+        (property.status, soonest.unwrap_or(i64::MAX))
+    });
+    HttpResponse::Ok().json(ComplianceDashboard {
+        within_days,
+        totals,
+        properties,
+    })
+}
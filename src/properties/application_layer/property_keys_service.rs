@@ -0,0 +1,180 @@
+use crate::properties::{
+    application_layer::properties_service::require_property,
+    domain_layer::property_keys_security::{PropertyKeysSecurity, PropertyKeysSecurityRequest},
+    infrastructure_layer::property_keys_repository::{CustomErrors, PropertyKeysRepository},
+};
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/keys",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "Keys held for the property", body = [PropertyKeysSecurity]),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_keys(state: web::Data<AppState>, property_id: web::Path<Uuid>) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyKeysRepository::new();
+    match repo.get_all(&state.db, property_id).await {
+        Ok(keys) => HttpResponse::Ok().json(keys),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/keys/{key_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("key_id" = Uuid, Path, description = "Key id"),
+    ),
+    responses(
+        (status = 200, description = "The key", body = PropertyKeysSecurity, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Property or key not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_key(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (property_id, key_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyKeysRepository::new();
+    match repo.get(&state.db, property_id, key_id).await {
+        Ok(Some(key)) => conditional_request::conditional_get(&req, Some(key.updated_at), &key),
+        Ok(None) => error_response(CustomErrors::NotFound),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/keys",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IdempotencyHeader),
+    request_body = PropertyKeysSecurityRequest,
+    responses(
+        (status = 201, description = "The saved key", body = PropertyKeysSecurity),
+        (status = 400, description = "Missing `key_code`", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn add_key(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<PropertyKeysSecurityRequest>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyKeysRepository::new();
+    match repo.create(&state.db, property_id, request).await {
+        Ok(key) => HttpResponse::Created().json(key),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/properties/{property_id}/keys/{key_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("key_id" = Uuid, Path, description = "Key id"),
+        IfMatchHeader,
+    ),
+    request_body = PropertyKeysSecurityRequest,
+    responses(
+        (status = 200, description = "The updated key", body = PropertyKeysSecurity, headers(("ETag" = String))),
+        (status = 400, description = "Missing `key_code`", body = ErrorResponse),
+        (status = 404, description = "Property or key not found", body = ErrorResponse),
+        (status = 412, description = "The key changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn update_key(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<PropertyKeysSecurityRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
+    let (property_id, key_id) = path.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyKeysRepository::new();
+    match repo
+        .update(&state.db, property_id, key_id, request, expected_updated_at)
+        .await
+    {
+        Ok(key) => conditional_request::updated(Some(key.updated_at), &key),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/properties/{property_id}/keys/{key_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("key_id" = Uuid, Path, description = "Key id"),
+    ),
+    responses(
+        (status = 204, description = "Key removed"),
+        (status = 404, description = "Property or key not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_key(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (property_id, key_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyKeysRepository::new();
+    match repo.delete(&state.db, property_id, key_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::PreconditionFailed => conditional_request::precondition_failed(),
+        CustomErrors::DatabaseError(_) => {
+            HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))
+        }
+    }
+}
@@ -1,12 +1,41 @@
 use crate::properties::{
-    domain_layer::property_images::PropertyImages,
-    infrastructure_layer::property_images_repository::PropertyImagesRepository,
+    application_layer::properties_service::require_property,
+    domain_layer::{
+        property_images::PropertyImages,
+        property_photo::{
+            PhotoFileParams, PhotoFormat, PhotoStatus, PhotoUploadStatus, PhotoVariant,
+            PropertyPhoto, MAX_PHOTOS_PER_UPLOAD, MAX_PHOTO_BYTES,
+        },
+    },
+    infrastructure_layer::{
+        property_images_repository::PropertyImagesRepository,
+        property_photos_repository::{NewPropertyPhoto, PropertyPhotoRepository},
+    },
 };
+use crate::shared::domain_layer::api_schema::{ErrorResponse, PhotoUpload};
+use crate::shared::infrastructure_layer::document_storage::StorageError;
+use crate::user::infrastructure_layer::jwt_repo;
 use crate::AppState;
 use actix_multipart::Multipart;
+use actix_web::http::header;
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use futures_util::TryStreamExt;
+use image::ImageFormat;
+use serde_json::json;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 
+/// Formats the pipeline can decode.
+const ACCEPTED_FORMATS: [ImageFormat; 5] = [
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::WebP,
+    ImageFormat::Gif,
+    ImageFormat::Bmp,
+];
+/// Bytes read before the format of an upload is checked.
+const SNIFF_BYTES: usize = 32;
 
 pub async fn add(
     state: web::Data<AppState>,
@@ -34,26 +63,328 @@ pub async fn get_by_id(state: web::Data<AppState>, property_id: web::Path<Uuid>)
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
+
+fn bad_request(message: impl Into<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(json!({"error": message.into()}))
+}
+
+async fn remove_uploads(photos: &[NewPropertyPhoto]) {
+    for photo in photos {
+        let _ = tokio::fs::remove_file(&photo.temp_path).await;
+    }
+}
+
+/// Streams every file in the upload to `upload_dir`. Nothing is left behind
+/// when the upload is rejected.
+async fn receive_photos(
+    payload: &mut Multipart,
+    upload_dir: &Path,
+) -> Result<Vec<NewPropertyPhoto>, HttpResponse> {
+    let mut photos = Vec::new();
+    let result = receive_into(payload, upload_dir, &mut photos).await;
+    if result.is_err() {
+        remove_uploads(&photos).await;
+    }
+    result.map(|()| photos)
+}
+
+async fn receive_into(
+    payload: &mut Multipart,
+    upload_dir: &Path,
+    photos: &mut Vec<NewPropertyPhoto>,
+) -> Result<(), HttpResponse> {
+    let write_failed = |e: std::io::Error| {
+        log::error!("Saving a photo upload failed: {}", e);
+        HttpResponse::InternalServerError().json(json!({"error": "Could not save the upload"}))
+    };
+    while let Some(mut field) = payload
+        .try_next()
+        .await
+        .map_err(|e| bad_request(e.to_string()))?
+    {
+        let file_name = match field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+        {
+            Some(file_name) => file_name.to_string(),
+            None => continue,
+        };
+        if photos.len() == MAX_PHOTOS_PER_UPLOAD {
+            return Err(bad_request(format!(
+                "At most {} photos can be uploaded at once",
+                MAX_PHOTOS_PER_UPLOAD
+            )));
+        }
+
+        let photo_id = Uuid::new_v4();
+        let temp_path = upload_dir.join(format!("{}.upload", photo_id));
+        let mut file = tokio::fs::File::create(&temp_path)
+            .await
+            .map_err(write_failed)?;
+        photos.push(NewPropertyPhoto {
+            photo_id,
+            file_name: file_name.clone(),
+            size_bytes: 0,
+            temp_path: temp_path.to_string_lossy().to_string(),
+        });
+
+        let mut head = Vec::with_capacity(SNIFF_BYTES);
+        let mut size = 0;
+        while let Some(chunk) = field
+            .try_next()
+            .await
+            .map_err(|e| bad_request(e.to_string()))?
+        {
+            size += chunk.len();
+            if size > MAX_PHOTO_BYTES {
+                return Err(HttpResponse::PayloadTooLarge().json(json!({
+                    "error": format!("{} is larger than {} MB", file_name, MAX_PHOTO_BYTES / (1024 * 1024))
+                })));
+            }
+            if head.len() < SNIFF_BYTES {
+                let take = (SNIFF_BYTES - head.len()).min(chunk.len());
+                head.extend_from_slice(&chunk[..take]);
+            }
+            file.write_all(&chunk).await.map_err(write_failed)?;
+        }
+        file.flush().await.map_err(write_failed)?;
+
+        let accepted =
+            image::guess_format(&head).is_ok_and(|format| ACCEPTED_FORMATS.contains(&format));
+        if !accepted {
+            return Err(bad_request(format!(
+                "{} is not a PNG, JPEG, WebP, GIF or BMP image",
+                file_name
+            )));
+        }
+        if let Some(photo) = photos.last_mut() {
+            photo.size_bytes = size as i64;
+        }
+    }
+    if photos.is_empty() {
+        return Err(bad_request("No photos were uploaded"));
+    }
+    Ok(())
+}
+
+/// Accepts photos of a property. They are processed in the background; poll
+/// the upload's status to see when they are ready.
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/photos/{property_id}",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    request_body(content = PhotoUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 202, description = "Photos accepted and queued for processing", body = PhotoUploadStatus, headers(("Location" = String, description = "Status of the upload"))),
+        (status = 400, description = "No photos, more than ten, or a file that is not a supported image", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 413, description = "A photo larger than 10 MB", body = ErrorResponse),
+    )
+)]
 pub async fn upload_images(
     state: web::Data<AppState>,
     property_id: web::Path<Uuid>,
     mut payload: Multipart,
     req: HttpRequest,
 ) -> impl Responder {
-    let repo = PropertyImagesRepository::new();
-    println!("upload_images");
-    match repo
-        .upload_images(
-            state.into_inner(),
-            property_id.into_inner(),
-            &mut payload,
-            req.into(),
-        )
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let uploaded_by = jwt_repo::authenticated_user_id(&req, &state).await;
+    let photos = match receive_photos(&mut payload, state.photo_pipeline.upload_dir()).await {
+        Ok(photos) => photos,
+        Err(response) => return response,
+    };
+
+    let upload_id = Uuid::new_v4();
+    let repo = PropertyPhotoRepository::new();
+    let recorded = match repo
+        .create(&state.db, property_id, upload_id, uploaded_by, &photos)
         .await
     {
-        Ok((status_code, property_photos)) => {
-            HttpResponse::build(status_code).json(property_photos)
+        Ok(recorded) => recorded,
+        Err(e) => {
+            remove_uploads(&photos).await;
+            return HttpResponse::InternalServerError().json(json!({"error": e.to_string()}));
+        }
+    };
+    for photo in &recorded {
+        state.photo_pipeline.enqueue(photo.photo_id).await;
+    }
+    HttpResponse::Accepted()
+        .insert_header((
+            header::LOCATION,
+            format!(
+                "/api/v1/properties/photos/{}/uploads/{}",
+                property_id, upload_id
+            ),
+        ))
+        .json(PhotoUploadStatus::new(upload_id, recorded))
+}
+
+/// How the photos of one upload are getting on.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/photos/{property_id}/uploads/{upload_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("upload_id" = Uuid, Path, description = "Upload id"),
+    ),
+    responses(
+        (status = 200, description = "Status of the upload and each of its photos", body = PhotoUploadStatus),
+        (status = 404, description = "Property or upload not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_upload_status(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (property_id, upload_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyPhotoRepository::new();
+    match repo.get_upload(&state.db, property_id, upload_id).await {
+        Ok(photos) if photos.is_empty() => {
+            HttpResponse::NotFound().json(json!({"error": "Upload not found"}))
+        }
+        Ok(photos) => HttpResponse::Ok().json(PhotoUploadStatus::new(upload_id, photos)),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+/// Every photo of a property, whatever its status, oldest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/photos/{property_id}",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "Photos of the property", body = [PropertyPhoto]),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_photos(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyPhotoRepository::new();
+    match repo.get_all(&state.db, property_id).await {
+        Ok(photos) => HttpResponse::Ok().json(
+            photos
+                .into_iter()
+                .map(PropertyPhoto::with_url)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+/// The rendition that best suits the client: in the format its `Accept`
+/// header prefers, unless `format` is given, and the narrowest at least
+/// `width` wide. Listing photos are public, so this needs no login.
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/photos/{property_id}/{photo_id}/file",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("photo_id" = Uuid, Path, description = "Photo id"),
+        PhotoFileParams,
+    ),
+    responses(
+        (status = 200, description = "The image", content(
+            ("image/avif"),
+            ("image/webp"),
+            ("image/jpeg"),
+        )),
+        (status = 302, description = "Redirect to the image in storage"),
+        (status = 404, description = "No such photo, or it is not ready", body = ErrorResponse),
+        (status = 406, description = "The photo is in no format the client accepts", body = ErrorResponse),
+    )
+)]
+pub async fn get_photo_file(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    params: web::Query<PhotoFileParams>,
+) -> impl Responder {
+    let (property_id, photo_id) = path.into_inner();
+    let repo = PropertyPhotoRepository::new();
+    let photo = match repo.get(&state.db, property_id, photo_id).await {
+        Ok(Some(photo)) if photo.status == PhotoStatus::Ready => photo,
+        Ok(_) => return HttpResponse::NotFound().json(json!({"error": "Photo not found"})),
+        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    };
+
+    let mut available: Vec<PhotoFormat> = Vec::new();
+    for variant in &photo.variants {
+        if !available.contains(&variant.format) {
+            available.push(variant.format);
+        }
+    }
+    let format = match params.format {
+        Some(format) if available.contains(&format) => Some(format),
+        Some(_) => None,
+        None => PhotoFormat::negotiate(
+            req.headers()
+                .get(header::ACCEPT)
+                .and_then(|accept| accept.to_str().ok()),
+            &available,
+        ),
+    };
+    let variant =
+        match format.and_then(|format| PhotoVariant::pick(&photo.variants, format, params.width)) {
+            Some(variant) => variant,
+            None if available.is_empty() => {
+                return HttpResponse::NotFound().json(json!({"error": "Photo not found"}))
+            }
+            None => {
+                return HttpResponse::NotAcceptable().json(json!({
+                    "error": "The photo is not available in an accepted format",
+                    "formats": available,
+                }))
+            }
+        };
+    serve(&state, &variant.storage_key, variant.format.content_type()).await
+}
+
+/// How long storage links to photos last; photos never change once ready.
+const PHOTO_LINK_SECONDS: u32 = 60 * 60;
+
+async fn serve(state: &AppState, storage_key: &str, content_type: &str) -> HttpResponse {
+    // The rendition sent depends on the Accept header.
+    let vary = (header::VARY, "Accept");
+    match state
+        .document_storage
+        .presigned_url(storage_key, PHOTO_LINK_SECONDS)
+        .await
+    {
+        Ok(Some(url)) => {
+            return HttpResponse::Found()
+                .insert_header((header::LOCATION, url))
+                .insert_header(vary)
+                .finish()
+        }
+        Ok(None) => {}
+        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+    match state.document_storage.get(storage_key).await {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header((header::CACHE_CONTROL, "public, max-age=86400, immutable"))
+            .insert_header(vary)
+            .body(bytes),
+        Err(StorageError::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Photo not found"}))
         }
-        Err((status_code, e)) => HttpResponse::build(status_code).json(e),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
     }
 }
@@ -0,0 +1,191 @@
+use crate::properties::{
+    application_layer::properties_service::require_property,
+    domain_layer::property_services::{PropertyService, PropertyServiceRequest},
+    infrastructure_layer::property_services_repository::{CustomErrors, PropertyServiceRepository},
+};
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/services",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "Services taken for the property", body = [PropertyService]),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_services(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyServiceRepository::new();
+    match repo.get_all(&state.db, property_id).await {
+        Ok(services) => HttpResponse::Ok().json(services),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/services/{service_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("service_id" = Uuid, Path, description = "Service id"),
+    ),
+    responses(
+        (status = 200, description = "The service", body = PropertyService, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Property or service not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_service(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (property_id, service_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyServiceRepository::new();
+    match repo.get(&state.db, property_id, service_id).await {
+        Ok(Some(service)) => {
+            conditional_request::conditional_get(&req, Some(service.updated_at), &service)
+        }
+        Ok(None) => error_response(CustomErrors::NotFound),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/services",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IdempotencyHeader),
+    request_body = PropertyServiceRequest,
+    responses(
+        (status = 201, description = "The saved service", body = PropertyService),
+        (status = 400, description = "A fee percentage outside 0-100 or a negative fee", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+    )
+)]
+pub async fn add_service(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<PropertyServiceRequest>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyServiceRepository::new();
+    match repo.create(&state.db, property_id, request).await {
+        Ok(service) => HttpResponse::Created().json(service),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/properties/{property_id}/services/{service_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("service_id" = Uuid, Path, description = "Service id"),
+        IfMatchHeader,
+    ),
+    request_body = PropertyServiceRequest,
+    responses(
+        (status = 200, description = "The updated service", body = PropertyService, headers(("ETag" = String))),
+        (status = 400, description = "A fee percentage outside 0-100 or a negative fee", body = ErrorResponse),
+        (status = 404, description = "Property or service not found", body = ErrorResponse),
+        (status = 412, description = "The service changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn update_service(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    body: web::Json<PropertyServiceRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
+    let (property_id, service_id) = path.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyServiceRepository::new();
+    match repo
+        .update(
+            &state.db,
+            property_id,
+            service_id,
+            request,
+            expected_updated_at,
+        )
+        .await
+    {
+        Ok(service) => conditional_request::updated(Some(service.updated_at), &service),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/properties/{property_id}/services/{service_id}",
+    tag = "properties",
+    params(
+        ("property_id" = Uuid, Path, description = "Property id"),
+        ("service_id" = Uuid, Path, description = "Service id"),
+    ),
+    responses(
+        (status = 204, description = "Service removed"),
+        (status = 404, description = "Property or service not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_service(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (property_id, service_id) = path.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertyServiceRepository::new();
+    match repo.delete(&state.db, property_id, service_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::PreconditionFailed => conditional_request::precondition_failed(),
+        CustomErrors::DatabaseError(_) => {
+            HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))
+        }
+    }
+}
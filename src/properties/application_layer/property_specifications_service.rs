@@ -0,0 +1,163 @@
+use crate::properties::{
+    application_layer::properties_service::require_property,
+    domain_layer::property_specifications::{
+        PropertySpecifications, PropertySpecificationsRequest,
+    },
+    infrastructure_layer::property_specifications_repository::{
+        CustomErrors, PropertySpecificationsRepository,
+    },
+};
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{ErrorResponse, IdempotencyHeader, IfMatchHeader};
+use crate::AppState;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/{property_id}/specifications",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 200, description = "The property's specifications", body = PropertySpecifications, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Property or specifications not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_specifications(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertySpecificationsRepository::new();
+    match repo.get(&state.db, property_id).await {
+        Ok(Some(specifications)) => conditional_request::conditional_get(
+            &req,
+            Some(specifications.updated_at),
+            &specifications,
+        ),
+        Ok(None) => error_response(CustomErrors::NotFound),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Records bedrooms, bathrooms and the rest of a property's specifications. A property has one set; change it with `PUT`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/specifications",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IdempotencyHeader),
+    request_body = PropertySpecificationsRequest,
+    responses(
+        (status = 201, description = "The saved specifications", body = PropertySpecifications),
+        (status = 400, description = "Negative room counts or a floor area without a unit", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 409, description = "The property already has specifications", body = ErrorResponse),
+    )
+)]
+pub async fn add_specifications(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<PropertySpecificationsRequest>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertySpecificationsRepository::new();
+    match repo.create(&state.db, property_id, request).await {
+        Ok(specifications) => HttpResponse::Created().json(specifications),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/properties/{property_id}/specifications",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id"), IfMatchHeader),
+    request_body = PropertySpecificationsRequest,
+    responses(
+        (status = 200, description = "The updated specifications", body = PropertySpecifications, headers(("ETag" = String))),
+        (status = 400, description = "Negative room counts or a floor area without a unit", body = ErrorResponse),
+        (status = 404, description = "Property or specifications not found", body = ErrorResponse),
+        (status = 412, description = "The specifications changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn update_specifications(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<PropertySpecificationsRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
+    let property_id = property_id.into_inner();
+    let request = body.into_inner();
+    if let Err(message) = request.validate() {
+        return HttpResponse::BadRequest().json(json!({"error": message}));
+    }
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertySpecificationsRepository::new();
+    match repo
+        .update(&state.db, property_id, request, expected_updated_at)
+        .await
+    {
+        Ok(specifications) => {
+            conditional_request::updated(Some(specifications.updated_at), &specifications)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/properties/{property_id}/specifications",
+    tag = "properties",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    responses(
+        (status = 204, description = "Specifications removed"),
+        (status = 404, description = "Property or specifications not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_specifications(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+) -> impl Responder {
+    let property_id = property_id.into_inner();
+    if let Err(response) = require_property(&state, property_id).await {
+        return response;
+    }
+    let repo = PropertySpecificationsRepository::new();
+    match repo.delete(&state.db, property_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::PreconditionFailed => conditional_request::precondition_failed(),
+        CustomErrors::AlreadyExists => {
+            HttpResponse::Conflict().json(json!({"error": e.to_string()}))
+        }
+        CustomErrors::DatabaseError(_) => {
+            HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))
+        }
+    }
+}
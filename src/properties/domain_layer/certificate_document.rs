@@ -0,0 +1,98 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Largest certificate document accepted, in bytes.
+pub const MAX_DOCUMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// One uploaded version of the file behind a certificate.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct CertificateDocument {
+    pub document_id: Uuid,
+    pub certificate_id: Uuid,
+    pub property_id: Uuid,
+    /// 1 for the first upload, counting up with each renewal. The highest
+    /// is the current one.
+    pub version: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Hex SHA-256 of the file.
+    pub sha256: String,
+    #[serde(skip)]
+    pub storage_key: String,
+    /// The certificate's dates this version was uploaded with.
+    pub start_date: NaiveDate,
+    pub expiry_date: Option<NaiveDate>,
+    pub uploaded_by: Uuid,
+    pub uploaded_at: DateTime<Utc>,
+    /// The staff member who checked the document, once someone has.
+    pub verified_by: Option<Uuid>,
+    pub verified_at: Option<DateTime<Utc>>,
+    /// A signed link to the file that works without logging in until it
+    /// expires.
+    #[sqlx(skip)]
+    #[schema(read_only)]
+    pub download_url: Option<String>,
+}
+
+/// The type of a certificate document, from its first bytes rather than
+/// what the client claims. Only PDFs and images are accepted.
+pub fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// The file extension documents of `content_type` are stored with.
+pub fn extension(content_type: &str) -> &'static str {
+    match content_type {
+        "application/pdf" => "pdf",
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/webp" => "webp",
+        _ => "bin",
+    }
+}
+
+/// The dates a renewal upload moves the certificate to. Either may be left
+/// out to keep the certificate's current one.
+#[derive(Debug, Default, Clone)]
+pub struct CertificateRenewal {
+    pub start_date: Option<NaiveDate>,
+    pub expiry_date: Option<NaiveDate>,
+}
+
+impl CertificateRenewal {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(start_date), Some(expiry_date)) = (self.start_date, self.expiry_date) {
+            if expiry_date < start_date {
+                return Err("expiry_date cannot be before start_date".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_renewal(&self) -> bool {
+        self.start_date.is_some() || self.expiry_date.is_some()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DocumentDownloadParams {
+    /// Unix time the link stops working.
+    pub expires: i64,
+    /// Hex HMAC-SHA256 the server signed the link with.
+    pub signature: String,
+}
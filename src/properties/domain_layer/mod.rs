@@ -2,8 +2,14 @@ pub mod property_core;
 pub mod property_address;
 pub mod property_specifications;
 pub mod property_certificates;
+pub mod certificate_document;
+pub mod property_compliance;
 pub mod property_services;
 pub mod property_keys_security;
 pub mod property_features;
+pub mod property_full;
 pub mod property_images;
-pub mod property_rental_info;
\ No newline at end of file
+pub mod property_photo;
+pub mod property_rental_info;
+pub mod property_search;
+pub mod property_status_history;
\ No newline at end of file
@@ -1,10 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct PropertyAddress {
     pub address_id: Uuid,
     pub property_id: Uuid, 
@@ -19,3 +20,90 @@ pub struct PropertyAddress {
     pub created_at: DateTime<Utc>, 
     pub updated_at: DateTime<Utc>
 }
+
+/// A property's address as sent by the client. The server fills in
+/// `display_address` when it is left out and normalises UK postcodes.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PropertyAddressRequest {
+    pub display_address: Option<String>,
+    pub address_line1: String,
+    pub address_line2: Option<String>,
+    pub town_city: String,
+    pub county: Option<String>,
+    pub postcode: String,
+    pub country: String,
+    pub searchable_area: Option<String>,
+}
+
+impl PropertyAddressRequest {
+    /// Checks the request, formats a UK postcode as `SW1A 1AA` and builds
+    /// `display_address` from the address lines if it is missing.
+    pub fn normalise(mut self) -> Result<Self, String> {
+        self.address_line1 = self.address_line1.trim().to_string();
+        self.town_city = self.town_city.trim().to_string();
+        self.country = self.country.trim().to_string();
+        self.address_line2 = self
+            .address_line2
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty());
+        if self.address_line1.is_empty() || self.town_city.is_empty() || self.country.is_empty() {
+            return Err("address_line1, town_city and country are required".to_string());
+        }
+
+        self.postcode = if is_uk(&self.country) {
+            normalise_uk_postcode(&self.postcode)
+                .ok_or_else(|| format!("'{}' is not a valid UK postcode", self.postcode.trim()))?
+        } else {
+            self.postcode.trim().to_string()
+        };
+
+        let display_address = self
+            .display_address
+            .take()
+            .map(|display_address| display_address.trim().to_string())
+            .filter(|display_address| !display_address.is_empty());
+        self.display_address = Some(display_address.unwrap_or_else(|| {
+            [
+                Some(self.address_line1.as_str()),
+                self.address_line2.as_deref(),
+                Some(self.town_city.as_str()),
+                Some(self.postcode.as_str()).filter(|postcode| !postcode.is_empty()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ")
+        }));
+        Ok(self)
+    }
+}
+
+fn is_uk(country: &str) -> bool {
+    matches!(
+        country.to_lowercase().as_str(),
+        "united kingdom" | "uk" | "gb" | "great britain" | "england" | "scotland" | "wales" | "northern ireland"
+    )
+}
+
+/// Upper-cases a UK postcode and puts a single space before the inward code,
+/// or returns `None` if it is not shaped like one.
+pub fn normalise_uk_postcode(postcode: &str) -> Option<String> {
+    let compact: String = postcode
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    if !compact.is_ascii() || compact.len() < 5 || compact.len() > 7 {
+        return None;
+    }
+    let (outward, inward) = compact.split_at(compact.len() - 3);
+    let shape: String = compact
+        .chars()
+        .map(|c| if c.is_ascii_digit() { '9' } else if c.is_ascii_alphabetic() { 'A' } else { '?' })
+        .collect();
+    let outward_shape = &shape[..outward.len()];
+    let valid = compact == "GIR0AA"
+        || (&shape[outward.len()..] == "9AA"
+            && matches!(outward_shape, "A9" | "A99" | "A9A" | "AA9" | "AA99" | "AA9A"));
+    valid.then(|| format!("{} {}", outward, inward))
+}
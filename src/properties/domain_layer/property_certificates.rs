@@ -1,13 +1,15 @@
 use serde::{Serialize, Deserialize};
 use sqlx::prelude::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use chrono::{NaiveDate, DateTime, Utc};
 
 
 
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "certificate_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum CertificateType {
     ElectricalSafetyCertificate,
     GasSafetyCertificate,
@@ -28,9 +30,35 @@ pub enum CertificateType {
     SmokeAndCO2DetectorComplianceCertificate,
 }
 
+impl CertificateType {
+    /// How the certificate is named to staff.
+    pub fn label(&self) -> &'static str {
+        match self {
+            CertificateType::ElectricalSafetyCertificate => "Electrical installation condition report",
+            CertificateType::GasSafetyCertificate => "Gas safety certificate",
+            CertificateType::EnergyPerformanceCertificate => "Energy performance certificate",
+            CertificateType::FireSafetyCertificate => "Fire safety certificate",
+            CertificateType::WaterSafetyCertificate => "Water safety certificate",
+            CertificateType::BuildingRegulationComplianceCertificate => "Building regulations compliance certificate",
+            CertificateType::PATTestingCertificate => "PAT testing certificate",
+            CertificateType::FENSACertificate => "FENSA certificate",
+            CertificateType::AsbestosSurveyCertificate => "Asbestos survey",
+            CertificateType::StructuralSafetyCertificate => "Structural safety certificate",
+            CertificateType::LandlordInsuranceCertificate => "Landlord insurance certificate",
+            CertificateType::HealthAndSafetyRiskAssessment => "Health and safety risk assessment",
+            CertificateType::BoilerServiceCertificate => "Boiler service certificate",
+            CertificateType::ChimneySafetyCertificate => "Chimney safety certificate",
+            CertificateType::FoodHygieneCertificate => "Food hygiene certificate",
+            CertificateType::WasteCarrierCertificate => "Waste carrier certificate",
+            CertificateType::SmokeAndCO2DetectorComplianceCertificate => "Smoke and carbon monoxide alarm certificate",
+        }
+    }
+}
+
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
-#[sqlx(type_name = "responsibility_type ", rename_all = "lowercase")]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "responsibility_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum ResponsibilityType {
     Landlord,
     PropertyManager,
@@ -42,7 +70,7 @@ pub enum ResponsibilityType {
     Investor,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Certificate {
     pub certificate_id: Uuid,
     pub property_id: Uuid,
@@ -54,3 +82,21 @@ pub struct Certificate {
     pub created_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CertificateRequest {
+    pub certificate_type: CertificateType,
+    /// Left out for certificates that do not expire.
+    pub expiry_date: Option<NaiveDate>,
+    /// Who is responsible for renewing the certificate.
+    pub responsibility_name: ResponsibilityType,
+    pub start_date: NaiveDate,
+}
+
+impl CertificateRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.expiry_date.is_some_and(|expiry_date| expiry_date < self.start_date) {
+            return Err("expiry_date cannot be before start_date".to_string());
+        }
+        Ok(())
+    }
+}
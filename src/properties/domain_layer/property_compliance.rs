@@ -0,0 +1,235 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::{
+    property_certificates::{Certificate, CertificateType},
+    property_core::{LettingClassification, PropertyStatus},
+};
+
+pub const DEFAULT_WARNING_DAYS: i32 = 30;
+
+/// How many days ahead an expiry counts as coming up, from
+/// `CERTIFICATE_EXPIRY_WARNING_DAYS` (default 30).
+pub fn warning_days() -> i32 {
+    std::env::var("CERTIFICATE_EXPIRY_WARNING_DAYS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_WARNING_DAYS)
+}
+
+/// The certificates a property must hold to be let under `classification`.
+/// HMOs also need fire safety certification, whether classified as `hmo` or
+/// flagged as one in their specifications.
+pub fn required_certificates(
+    classification: &LettingClassification,
+    is_hmo: bool,
+) -> Vec<CertificateType> {
+    let mut required = match classification {
+        LettingClassification::Commercial => vec![
+            CertificateType::ElectricalSafetyCertificate,
+            CertificateType::EnergyPerformanceCertificate,
+            CertificateType::FireSafetyCertificate,
+        ],
+        LettingClassification::Holiday => vec![
+            CertificateType::GasSafetyCertificate,
+            CertificateType::ElectricalSafetyCertificate,
+            CertificateType::FireSafetyCertificate,
+            CertificateType::SmokeAndCO2DetectorComplianceCertificate,
+        ],
+        LettingClassification::Residential
+        | LettingClassification::Student
+        | LettingClassification::ShortTerm
+        | LettingClassification::Hmo => vec![
+            CertificateType::GasSafetyCertificate,
+            CertificateType::ElectricalSafetyCertificate,
+            CertificateType::EnergyPerformanceCertificate,
+            CertificateType::SmokeAndCO2DetectorComplianceCertificate,
+        ],
+    };
+    let hmo = is_hmo || matches!(classification, LettingClassification::Hmo);
+    if hmo && !required.contains(&CertificateType::FireSafetyCertificate) {
+        required.push(CertificateType::FireSafetyCertificate);
+    }
+    required
+}
+
+/// Worst first, so the overall status of a property is the smallest of its
+/// requirements'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ComplianceStatus {
+    Missing,
+    Expired,
+    Expiring,
+    Compliant,
+}
+
+impl ComplianceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplianceStatus::Missing => "missing",
+            ComplianceStatus::Expired => "expired",
+            ComplianceStatus::Expiring => "expiring",
+            ComplianceStatus::Compliant => "compliant",
+        }
+    }
+
+    /// Whether the property may be let as it stands.
+    pub fn allows_letting(&self) -> bool {
+        matches!(
+            self,
+            ComplianceStatus::Expiring | ComplianceStatus::Compliant
+        )
+    }
+}
+
+/// A property as far as compliance is concerned.
+#[derive(Debug, Clone, FromRow)]
+pub struct ComplianceSubject {
+    pub property_id: Uuid,
+    pub status: PropertyStatus,
+    pub letting_classification: LettingClassification,
+    pub staff_assigned: Option<Uuid>,
+    pub branch_id: Uuid,
+    pub display_address: Option<String>,
+    pub is_hmo: Option<bool>,
+}
+
+/// How one required certificate stands.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RequirementCompliance {
+    pub certificate_type: CertificateType,
+    pub status: ComplianceStatus,
+    /// The certificate of this type that lasts longest, if any.
+    pub certificate_id: Option<Uuid>,
+    pub expiry_date: Option<NaiveDate>,
+    /// Negative once expired.
+    pub days_remaining: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PropertyCompliance {
+    pub property_id: Uuid,
+    pub display_address: Option<String>,
+    pub property_status: PropertyStatus,
+    pub letting_classification: LettingClassification,
+    pub is_hmo: bool,
+    pub staff_assigned: Option<Uuid>,
+    #[serde(skip)]
+    pub branch_id: Uuid,
+    /// The worst status among the requirements.
+    pub status: ComplianceStatus,
+    pub requirements: Vec<RequirementCompliance>,
+}
+
+impl PropertyCompliance {
+    /// Checks `certificates` against what `subject` requires on `today`.
+    /// Certificates expiring within `within_days` count as expiring.
+    pub fn assess(
+        subject: &ComplianceSubject,
+        certificates: &[Certificate],
+        today: NaiveDate,
+        within_days: i32,
+    ) -> Self {
+        let is_hmo = subject.is_hmo.unwrap_or(false);
+        let requirements: Vec<RequirementCompliance> =
+            required_certificates(&subject.letting_classification, is_hmo)
+                .into_iter()
+                .map(|certificate_type| {
+                    // Certificates without an expiry date never expire.
+                    let best = certificates
+                        .iter()
+                        .filter(|certificate| certificate.certificate_type == certificate_type)
+                        .max_by_key(|certificate| {
+                            certificate.expiry_date.unwrap_or(NaiveDate::MAX)
+                        });
+                    let days_remaining = best
+                        .and_then(|certificate| certificate.expiry_date)
+                        .map(|expiry_date| (expiry_date - today).num_days());
+                    let status = match (best, days_remaining) {
+                        (None, _) => ComplianceStatus::Missing,
+                        (Some(_), Some(days)) if days < 0 => ComplianceStatus::Expired,
+                        (Some(_), Some(days)) if days <= i64::from(within_days) => {
+                            ComplianceStatus::Expiring
+                        }
+                        (Some(_), _) => ComplianceStatus::Compliant,
+                    };
+                    RequirementCompliance {
+                        certificate_type,
+                        status,
+                        certificate_id: best.map(|certificate| certificate.certificate_id),
+                        expiry_date: best.and_then(|certificate| certificate.expiry_date),
+                        days_remaining,
+                    }
+                })
+                .collect();
+        let status = requirements
+            .iter()
+            .map(|requirement| requirement.status)
+            .min()
+            .unwrap_or(ComplianceStatus::Compliant);
+        PropertyCompliance {
+            property_id: subject.property_id,
+            display_address: subject.display_address.clone(),
+            property_status: subject.status.clone(),
+            letting_classification: subject.letting_classification.clone(),
+            is_hmo,
+            staff_assigned: subject.staff_assigned,
+            branch_id: subject.branch_id,
+            status,
+            requirements,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ComplianceQueryParams {
+    /// Days ahead an expiry counts as coming up. Defaults to
+    /// `CERTIFICATE_EXPIRY_WARNING_DAYS`, or 30.
+    pub within_days: Option<i32>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ComplianceDashboardParams {
+    /// Days ahead an expiry counts as coming up. Defaults to
+    /// `CERTIFICATE_EXPIRY_WARNING_DAYS`, or 30.
+    pub within_days: Option<i32>,
+    /// Only properties with this overall status.
+    pub status: Option<ComplianceStatus>,
+    /// Only properties assigned to this staff member.
+    pub staff_id: Option<Uuid>,
+}
+
+/// How many properties have each overall status.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ComplianceTotals {
+    pub compliant: i64,
+    pub expiring: i64,
+    pub expired: i64,
+    pub missing: i64,
+}
+
+impl ComplianceTotals {
+    pub fn count(&mut self, status: ComplianceStatus) {
+        match status {
+            ComplianceStatus::Compliant => self.compliant += 1,
+            ComplianceStatus::Expiring => self.expiring += 1,
+            ComplianceStatus::Expired => self.expired += 1,
+            ComplianceStatus::Missing => self.missing += 1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComplianceDashboard {
+    pub within_days: i32,
+    /// Across every property matching `staff_id`, whatever `status` is asked for.
+    pub totals: ComplianceTotals,
+    /// Worst first, then soonest to expire.
+    pub properties: Vec<PropertyCompliance>,
+}
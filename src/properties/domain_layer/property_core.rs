@@ -1,13 +1,13 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{self, FromRow};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "property_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
-
 pub enum PropertyStatus {
     Available,
     LetAgreed,
@@ -17,10 +17,57 @@ pub enum PropertyStatus {
     Maintenance,
 }
 
+impl PropertyStatus {
+    /// The value stored in the `property_status` enum column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PropertyStatus::Available => "available",
+            PropertyStatus::LetAgreed => "letagreed",
+            PropertyStatus::Let => "let",
+            PropertyStatus::Withdrawn => "withdrawn",
+            PropertyStatus::Unavailable => "unavailable",
+            PropertyStatus::Maintenance => "maintenance",
+        }
+    }
+
+    /// Whether a property may go straight from this status to `next`. Leaving
+    /// `Let` also needs the tenancy to have ended.
+    pub fn can_become(&self, next: &PropertyStatus) -> bool {
+        matches!(
+            (self, next),
+            (
+                PropertyStatus::Available,
+                PropertyStatus::LetAgreed
+                    | PropertyStatus::Withdrawn
+                    | PropertyStatus::Unavailable
+                    | PropertyStatus::Maintenance
+            ) | (
+                PropertyStatus::LetAgreed,
+                PropertyStatus::Let | PropertyStatus::Available | PropertyStatus::Withdrawn
+            ) | (
+                PropertyStatus::Let,
+                PropertyStatus::Available
+                    | PropertyStatus::Withdrawn
+                    | PropertyStatus::Unavailable
+                    | PropertyStatus::Maintenance
+            ) | (
+                PropertyStatus::Withdrawn,
+                PropertyStatus::Available | PropertyStatus::Unavailable
+            ) | (
+                PropertyStatus::Unavailable,
+                PropertyStatus::Available | PropertyStatus::Withdrawn | PropertyStatus::Maintenance
+            ) | (
+                PropertyStatus::Maintenance,
+                PropertyStatus::Available | PropertyStatus::Withdrawn | PropertyStatus::Unavailable
+            )
+        )
+    }
+}
 
 
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "property_type", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum PropertyType {
@@ -38,10 +85,9 @@ pub enum PropertyType {
 
 
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "letting_classification", rename_all = "lowercase")]
-#[serde(rename_all = "lowercase")] 
-
+#[serde(rename_all = "lowercase")]
 pub enum LettingClassification {
     Residential,
     Commercial,
@@ -51,7 +97,7 @@ pub enum LettingClassification {
     Hmo,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct PropertyCore {
     pub property_id: Option<Uuid>,
     pub status: PropertyStatus,
@@ -62,4 +108,53 @@ pub struct PropertyCore {
     pub date_available: Option<NaiveDate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Branch the property belongs to. New properties go to the creator's branch; use
+    /// `POST /api/v1/properties/{id}/transfer` to move one.
+    #[sqlx(default)]
+    #[schema(read_only)]
+    pub branch_id: Option<Uuid>,
+    /// When the current tenancy ends. Set by letting the property through
+    /// `POST /api/v1/properties/{id}/status`.
+    #[sqlx(default)]
+    #[serde(default)]
+    #[schema(read_only)]
+    pub tenancy_end_date: Option<NaiveDate>,
+}
+
+/// Replaces a property's details. Optional fields left out are cleared.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PropertyCoreUpdate {
+    pub status: PropertyStatus,
+    pub property_type: PropertyType,
+    pub letting_classification: LettingClassification,
+    pub staff_assigned: Option<Uuid>,
+    pub landlord_id: Option<Uuid>,
+    pub date_available: Option<NaiveDate>,
+}
+
+/// Partial update; fields left out keep their current value.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PropertyCorePatch {
+    pub status: Option<PropertyStatus>,
+    pub property_type: Option<PropertyType>,
+    pub letting_classification: Option<LettingClassification>,
+    pub staff_assigned: Option<Uuid>,
+    pub landlord_id: Option<Uuid>,
+    pub date_available: Option<NaiveDate>,
+}
+
+impl PropertyCorePatch {
+    /// The full set of details after applying this patch to `property`.
+    pub fn apply_to(self, property: &PropertyCore) -> PropertyCoreUpdate {
+        PropertyCoreUpdate {
+            status: self.status.unwrap_or_else(|| property.status.clone()),
+            property_type: self.property_type.unwrap_or_else(|| property.property_type.clone()),
+            letting_classification: self
+                .letting_classification
+                .unwrap_or_else(|| property.letting_classification.clone()),
+            staff_assigned: self.staff_assigned.or(property.staff_assigned),
+            landlord_id: self.landlord_id.or(property.landlord_id),
+            date_available: self.date_available.or(property.date_available),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::landlord::domain_layer::landlord_details::{LandlordStatus, LandlordTypeEnum};
+use crate::properties::domain_layer::{
+    property_address::PropertyAddress, property_certificates::Certificate,
+    property_core::PropertyCore, property_photo::PropertyPhoto,
+    property_rental_info::PropertyRentalInfo, property_services::PropertyService,
+    property_specifications::PropertySpecifications,
+};
+use crate::user::domain_layer::user::StaffUserFullNames;
+
+/// Parts of a property listing that `GET /api/v1/properties/{id}/full` can
+/// return alongside the property itself.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PropertySection {
+    Address,
+    Specifications,
+    RentalInfo,
+    Services,
+    Certificates,
+    Images,
+    Landlord,
+    Staff,
+}
+
+impl PropertySection {
+    pub const ALL: [PropertySection; 8] = [
+        PropertySection::Address,
+        PropertySection::Specifications,
+        PropertySection::RentalInfo,
+        PropertySection::Services,
+        PropertySection::Certificates,
+        PropertySection::Images,
+        PropertySection::Landlord,
+        PropertySection::Staff,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PropertySection::Address => "address",
+            PropertySection::Specifications => "specifications",
+            PropertySection::RentalInfo => "rental_info",
+            PropertySection::Services => "services",
+            PropertySection::Certificates => "certificates",
+            PropertySection::Images => "images",
+            PropertySection::Landlord => "landlord",
+            PropertySection::Staff => "staff",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|section| section.as_str() == value)
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PropertyFullQueryParams {
+    /// Comma-separated sections to return: `address`, `specifications`,
+    /// `rental_info`, `services`, `certificates`, `images`, `landlord` and
+    /// `staff`. All of them when left out.
+    pub include: Option<String>,
+}
+
+/// Who owns a property, without their financial details.
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+pub struct LandlordSummary {
+    pub landlord_id: Uuid,
+    pub landlord_type: LandlordTypeEnum,
+    pub company_name: Option<String>,
+    pub full_name: Option<String>,
+    pub email: Option<String>,
+    pub phone_nr: String,
+    pub status: LandlordStatus,
+}
+
+/// A property with the sections asked for. Sections that were not asked for
+/// are left out; ones that were but have nothing recorded are `null` or `[]`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PropertyFull {
+    #[serde(flatten)]
+    pub property: PropertyCore,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<Option<PropertyAddress>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub specifications: Option<Option<PropertySpecifications>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rental_info: Option<Option<PropertyRentalInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub services: Option<Vec<PropertyService>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificates: Option<Vec<Certificate>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Processed photos, each with its renditions.
+    pub images: Option<Vec<PropertyPhoto>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub landlord: Option<Option<LandlordSummary>>,
+    /// The assigned staff member.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staff: Option<Option<StaffUserFullNames>>,
+}
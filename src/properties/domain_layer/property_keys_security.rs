@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A set of keys held for a property, with any alarm or key safe code.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct PropertyKeysSecurity {
     pub key_id: Uuid,
     pub property_id: Uuid,
@@ -11,4 +14,21 @@ pub struct PropertyKeysSecurity {
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PropertyKeysSecurityRequest {
+    /// The label on the key tag.
+    pub key_code: String,
+    pub security_code: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl PropertyKeysSecurityRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.key_code.trim().is_empty() {
+            return Err("key_code is required".to_string());
+        }
+        Ok(())
+    }
+}
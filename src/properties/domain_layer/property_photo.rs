@@ -0,0 +1,273 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Largest photo accepted, in bytes.
+pub const MAX_PHOTO_BYTES: usize = 10 * 1024 * 1024;
+/// Most photos accepted in one upload.
+pub const MAX_PHOTOS_PER_UPLOAD: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "photo_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PhotoStatus {
+    /// Uploaded and waiting to be processed.
+    Pending,
+    Ready,
+    Failed,
+}
+
+/// Image formats photos are rendered in. JPEG is the fallback every client
+/// can show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "photo_format", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PhotoFormat {
+    Avif,
+    Webp,
+    Jpeg,
+}
+
+impl PhotoFormat {
+    pub const ALL: [PhotoFormat; 3] = [PhotoFormat::Avif, PhotoFormat::Webp, PhotoFormat::Jpeg];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "avif" => Some(PhotoFormat::Avif),
+            "webp" => Some(PhotoFormat::Webp),
+            "jpeg" | "jpg" => Some(PhotoFormat::Jpeg),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PhotoFormat::Avif => "avif",
+            PhotoFormat::Webp => "webp",
+            PhotoFormat::Jpeg => "jpeg",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            PhotoFormat::Avif => "image/avif",
+            PhotoFormat::Webp => "image/webp",
+            PhotoFormat::Jpeg => "image/jpeg",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PhotoFormat::Avif => "avif",
+            PhotoFormat::Webp => "webp",
+            PhotoFormat::Jpeg => "jpg",
+        }
+    }
+
+    /// Picks the format to send a client with `accept` (its `Accept` header).
+    /// The highest quality wins. Among equals, formats the client names come
+    /// before those it only accepts through a wildcard; named formats go
+    /// smallest first (AVIF, WebP, JPEG), wildcard ones most widely shown
+    /// first (JPEG). No header means JPEG.
+    pub fn negotiate(accept: Option<&str>, available: &[PhotoFormat]) -> Option<PhotoFormat> {
+        let accept = match accept {
+            Some(accept) if !accept.trim().is_empty() => accept,
+            _ => return Self::fallback(available),
+        };
+        available
+            .iter()
+            .filter_map(|format| {
+                let (named, quality) = accept_quality(accept, format.content_type())?;
+                let preference = match (named, format) {
+                    (true, PhotoFormat::Avif) | (false, PhotoFormat::Jpeg) => 2,
+                    (_, PhotoFormat::Webp) => 1,
+                    _ => 0,
+                };
+                Some((quality, named, preference, *format))
+            })
+            .filter(|(quality, ..)| *quality > 0)
+            .max_by_key(|(quality, named, preference, _)| (*quality, *named, *preference))
+            .map(|(.., format)| format)
+    }
+
+    fn fallback(available: &[PhotoFormat]) -> Option<PhotoFormat> {
+        [PhotoFormat::Jpeg, PhotoFormat::Webp, PhotoFormat::Avif]
+            .into_iter()
+            .find(|format| available.contains(format))
+    }
+}
+
+/// How much an `Accept` header wants `content_type`, in thousandths, by the
+/// most specific range that matches it, and whether that range names it.
+/// `None` when nothing matches.
+fn accept_quality(accept: &str, content_type: &str) -> Option<(bool, u16)> {
+    let (kind, _) = content_type.split_once('/')?;
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let media = parts.next()?.trim().to_ascii_lowercase();
+            let specificity = if media == content_type {
+                2
+            } else if media == format!("{}/*", kind) {
+                1
+            } else if media == "*/*" {
+                0
+            } else {
+                return None;
+            };
+            let quality = parts
+                .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0);
+            Some((specificity, (quality * 1000.0).round() as u16))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map(|(specificity, quality)| (specificity == 2, quality))
+}
+
+/// One rendition of a photo.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PhotoVariant {
+    #[serde(skip)]
+    pub photo_id: Uuid,
+    pub format: PhotoFormat,
+    pub width: i32,
+    pub height: i32,
+    #[serde(skip)]
+    pub storage_key: String,
+    #[sqlx(skip)]
+    #[schema(read_only)]
+    pub url: Option<String>,
+}
+
+impl PhotoVariant {
+    /// The rendition in `format` to send for display `width` pixels wide:
+    /// the narrowest at least that wide, or the widest there is. Without a
+    /// width, the widest.
+    pub fn pick(
+        variants: &[PhotoVariant],
+        format: PhotoFormat,
+        width: Option<i32>,
+    ) -> Option<&PhotoVariant> {
+        let mut candidates: Vec<&PhotoVariant> = variants
+            .iter()
+            .filter(|variant| variant.format == format)
+            .collect();
+        candidates.sort_by_key(|variant| variant.width);
+        match width {
+            Some(width) => candidates
+                .iter()
+                .find(|variant| variant.width >= width)
+                .or(candidates.last())
+                .copied(),
+            None => candidates.last().copied(),
+        }
+    }
+}
+
+/// One uploaded photo of a property.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PropertyPhoto {
+    pub photo_id: Uuid,
+    pub property_id: Uuid,
+    /// Shared by the photos uploaded in the same request.
+    pub upload_id: Uuid,
+    pub status: PhotoStatus,
+    /// The name the file was uploaded with.
+    pub file_name: String,
+    /// Size of the upload.
+    pub size_bytes: i64,
+    #[serde(skip)]
+    pub temp_path: Option<String>,
+    /// Size of the widest rendition, once ready.
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    /// Why processing failed.
+    pub error: Option<String>,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+    /// Serves the rendition that best suits the client's `Accept` header and
+    /// the `width` asked for, once ready.
+    #[sqlx(skip)]
+    #[schema(read_only)]
+    pub url: Option<String>,
+    /// Every rendition, narrowest first.
+    #[sqlx(skip)]
+    #[schema(read_only)]
+    pub variants: Vec<PhotoVariant>,
+}
+
+impl PropertyPhoto {
+    pub fn with_url(mut self) -> Self {
+        if self.status == PhotoStatus::Ready {
+            let url = format!(
+                "/api/v1/properties/photos/{}/{}/file",
+                self.property_id, self.photo_id
+            );
+            for variant in &mut self.variants {
+                variant.url = Some(format!(
+                    "{}?format={}&width={}",
+                    url,
+                    variant.format.as_str(),
+                    variant.width
+                ));
+            }
+            self.url = Some(url);
+        }
+        self
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PhotoFileParams {
+    /// Width the photo is shown at, in pixels. The narrowest rendition at
+    /// least this wide is sent. Defaults to the widest.
+    pub width: Option<i32>,
+    /// Send this format whatever the `Accept` header says.
+    pub format: Option<PhotoFormat>,
+}
+
+/// How the photos of one upload are getting on.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PhotoUploadStatus {
+    pub upload_id: Uuid,
+    /// `pending` while any photo is, then `ready` if at least one photo
+    /// could be processed, otherwise `failed`.
+    pub status: PhotoStatus,
+    pub pending: usize,
+    pub ready: usize,
+    pub failed: usize,
+    pub photos: Vec<PropertyPhoto>,
+}
+
+impl PhotoUploadStatus {
+    pub fn new(upload_id: Uuid, photos: Vec<PropertyPhoto>) -> Self {
+        let count = |status| photos.iter().filter(|photo| photo.status == status).count();
+        let (pending, ready, failed) = (
+            count(PhotoStatus::Pending),
+            count(PhotoStatus::Ready),
+            count(PhotoStatus::Failed),
+        );
+        let status = if pending > 0 {
+            PhotoStatus::Pending
+        } else if ready > 0 {
+            PhotoStatus::Ready
+        } else {
+            PhotoStatus::Failed
+        };
+        PhotoUploadStatus {
+            upload_id,
+            status,
+            pending,
+            ready,
+            failed,
+            photos: photos.into_iter().map(PropertyPhoto::with_url).collect(),
+        }
+    }
+}
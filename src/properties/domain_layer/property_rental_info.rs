@@ -1,8 +1,12 @@
 use uuid::Uuid;
 use chrono::DateTime;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
 
-#[derive(Debug)]
+/// Amounts are monthly, in pounds.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct PropertyRentalInfo {
     pub rental_info_id: Uuid,
     pub property_id: Uuid, 
@@ -15,4 +19,42 @@ pub struct PropertyRentalInfo {
     pub holding_deposit: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PropertyRentalInfoRequest {
+    pub rent_amount: Option<f64>,
+    /// Rent on application; `rent_amount` may then be left out.
+    #[serde(default)]
+    pub rent_poa: bool,
+    pub valuation_rent_min: Option<f64>,
+    pub valuation_rent_max: Option<f64>,
+    pub minimum_rent: Option<f64>,
+    pub deposit: Option<f64>,
+    pub holding_deposit: Option<f64>,
+}
+
+impl PropertyRentalInfoRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        let amounts = [
+            self.rent_amount,
+            self.valuation_rent_min,
+            self.valuation_rent_max,
+            self.minimum_rent,
+            self.deposit,
+            self.holding_deposit,
+        ];
+        if amounts.iter().flatten().any(|amount| *amount < 0.0) {
+            return Err("Amounts cannot be negative".to_string());
+        }
+        if self.rent_amount.is_none() && !self.rent_poa {
+            return Err("rent_amount is required unless rent_poa is set".to_string());
+        }
+        if let (Some(min), Some(max)) = (self.valuation_rent_min, self.valuation_rent_max) {
+            if min > max {
+                return Err("valuation_rent_min cannot be above valuation_rent_max".to_string());
+            }
+        }
+        Ok(())
+    }
+}
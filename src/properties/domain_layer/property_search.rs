@@ -0,0 +1,161 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::{
+    property_core::{LettingClassification, PropertyCore, PropertyStatus, PropertyType},
+    property_specifications::FurnishedStatus,
+};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+/// Keeps the row offset of a page well inside `i64`.
+pub const MAX_PAGE: i64 = 10_000;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertySort {
+    Rent,
+    DateAvailable,
+    #[default]
+    CreatedAt,
+}
+
+impl PropertySort {
+    pub fn column(&self) -> &'static str {
+        match self {
+            PropertySort::Rent => "r.rent_amount",
+            PropertySort::DateAvailable => "p.date_available",
+            PropertySort::CreatedAt => "p.created_at",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PropertySearchParams {
+    pub status: Option<PropertyStatus>,
+    pub property_type: Option<PropertyType>,
+    pub letting_classification: Option<LettingClassification>,
+    pub min_bedrooms: Option<i32>,
+    pub max_bedrooms: Option<i32>,
+    pub min_bathrooms: Option<i32>,
+    pub max_bathrooms: Option<i32>,
+    /// Monthly rent, in pounds.
+    pub min_rent: Option<f64>,
+    pub max_rent: Option<f64>,
+    pub furnished_status: Option<FurnishedStatus>,
+    pub is_hmo: Option<bool>,
+    /// Available on or before this date.
+    pub available_before: Option<NaiveDate>,
+    /// Available on or after this date.
+    pub available_after: Option<NaiveDate>,
+    /// Town or city, ignoring case.
+    pub town: Option<String>,
+    /// Outward code of the postcode, e.g. `LS6`.
+    pub postcode_district: Option<String>,
+    pub landlord_id: Option<Uuid>,
+    /// Assigned staff member.
+    pub staff_id: Option<Uuid>,
+    /// `created_at` when left out.
+    pub sort: Option<PropertySort>,
+    /// `asc` by default, except `desc` for `created_at`.
+    pub order: Option<SortOrder>,
+    /// From 1 to 10 000.
+    pub page: Option<i64>,
+    /// 20 by default, up to 100.
+    pub per_page: Option<i64>,
+}
+
+impl PropertySearchParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(min), Some(max)) = (self.min_bedrooms, self.max_bedrooms) {
+            if min > max {
+                return Err("min_bedrooms cannot be above max_bedrooms".to_string());
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_bathrooms, self.max_bathrooms) {
+            if min > max {
+                return Err("min_bathrooms cannot be above max_bathrooms".to_string());
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_rent, self.max_rent) {
+            if min > max {
+                return Err("min_rent cannot be above max_rent".to_string());
+            }
+        }
+        if let (Some(before), Some(after)) = (self.available_before, self.available_after) {
+            if after > before {
+                return Err("available_after cannot be later than available_before".to_string());
+            }
+        }
+        if self.page.is_some_and(|page| !(1..=MAX_PAGE).contains(&page)) {
+            return Err(format!("page must be between 1 and {}", MAX_PAGE));
+        }
+        if self
+            .per_page
+            .is_some_and(|per_page| !(1..=MAX_PAGE_SIZE).contains(&per_page))
+        {
+            return Err(format!("per_page must be between 1 and {}", MAX_PAGE_SIZE));
+        }
+        Ok(())
+    }
+
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn order(&self) -> SortOrder {
+        self.order.unwrap_or(match self.sort.unwrap_or_default() {
+            PropertySort::CreatedAt => SortOrder::Desc,
+            _ => SortOrder::Asc,
+        })
+    }
+}
+
+/// A property with the details a results list shows.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct PropertySearchResult {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub property: PropertyCore,
+    pub display_address: Option<String>,
+    pub town_city: Option<String>,
+    pub postcode: Option<String>,
+    pub bedrooms: Option<i32>,
+    pub bathrooms: Option<i32>,
+    pub furnished_status: Option<FurnishedStatus>,
+    pub is_hmo: Option<bool>,
+    pub rent_amount: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PropertySearchResponse {
+    pub results: Vec<PropertySearchResult>,
+    pub page: i64,
+    pub per_page: i64,
+    /// Matching properties across all pages.
+    pub total: i64,
+}
@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "letting_service_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum LettingServiceTypeEnum {
     TenantFindOnly,
     FullManagement,
@@ -17,7 +19,8 @@ pub enum LettingServiceTypeEnum {
     MarketAppraisal,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::FromRow)]
+/// A service the agency provides for a property and what it charges for it.
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::FromRow, ToSchema)]
 pub struct PropertyService {
     pub service_id: Uuid,
     pub property_id: Uuid,
@@ -26,5 +29,29 @@ pub struct PropertyService {
     pub letting_fee_amount: Option<f64>,
     pub management_fee_percentage: Option<f64>,
     pub management_fee_amount: Option<f64>,
-    pub created_at: DateTime<Utc>
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Deserialize, Debug, ToSchema)]
+pub struct PropertyServiceRequest {
+    pub service_type: LettingServiceTypeEnum,
+    pub letting_fee_percentage: Option<f64>,
+    pub letting_fee_amount: Option<f64>,
+    pub management_fee_percentage: Option<f64>,
+    pub management_fee_amount: Option<f64>,
+}
+
+impl PropertyServiceRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        let percentages = [self.letting_fee_percentage, self.management_fee_percentage];
+        if percentages.iter().flatten().any(|percentage| !(0.0..=100.0).contains(percentage)) {
+            return Err("Fee percentages must be between 0 and 100".to_string());
+        }
+        let amounts = [self.letting_fee_amount, self.management_fee_amount];
+        if amounts.iter().flatten().any(|amount| *amount < 0.0) {
+            return Err("Fee amounts cannot be negative".to_string());
+        }
+        Ok(())
+    }
 }
@@ -1,10 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "furnished_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum FurnishedStatus {
     Unfurnished,
     PartFurnished,
@@ -12,7 +15,7 @@ pub enum FurnishedStatus {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct PropertySpecifications {
     pub spec_id: Uuid,
     pub property_id: Uuid, 
@@ -25,4 +28,33 @@ pub struct PropertySpecifications {
     pub floor_area_unit: Option<String>, 
     pub created_at: DateTime<Utc>, 
     pub updated_at: DateTime<Utc>, 
-}
\ No newline at end of file
+}
+
+/// Bedrooms, bathrooms and the rest of a property's specifications.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PropertySpecificationsRequest {
+    pub bedrooms: i32,
+    pub receptions: i32,
+    pub bathrooms: i32,
+    #[serde(default)]
+    pub is_hmo: bool,
+    pub furnished_status: FurnishedStatus,
+    pub floor_area_size: Option<f64>,
+    /// For example `sqft` or `sqm`.
+    pub floor_area_unit: Option<String>,
+}
+
+impl PropertySpecificationsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bedrooms < 0 || self.receptions < 0 || self.bathrooms < 0 {
+            return Err("bedrooms, receptions and bathrooms cannot be negative".to_string());
+        }
+        if self.floor_area_size.is_some_and(|size| size <= 0.0) {
+            return Err("floor_area_size must be positive".to_string());
+        }
+        if self.floor_area_size.is_some() && self.floor_area_unit.is_none() {
+            return Err("floor_area_unit is required with floor_area_size".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::properties::domain_layer::property_core::{PropertyCore, PropertyStatus};
+
+/// A request to move a property to another status.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PropertyStatusTransition {
+    pub status: PropertyStatus,
+    /// Required when withdrawing a property.
+    pub reason: Option<String>,
+    /// Required when letting a property: when the tenancy ends. When a let
+    /// property is moved on, the day the tenancy actually ended, if earlier
+    /// than planned.
+    pub tenancy_end_date: Option<NaiveDate>,
+}
+
+impl PropertyStatusTransition {
+    pub fn validate(&mut self) -> Result<(), String> {
+        self.reason = self
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_string);
+        if self.status == PropertyStatus::Withdrawn && self.reason.is_none() {
+            return Err("reason is required to withdraw a property".to_string());
+        }
+        if self.status == PropertyStatus::Let && self.tenancy_end_date.is_none() {
+            return Err("tenancy_end_date is required to let a property".to_string());
+        }
+        Ok(())
+    }
+
+    /// Checks the move is allowed from `property`'s current status on `today`,
+    /// and returns the tenancy end date to record: the new one when letting,
+    /// the one that has passed when leaving `Let`.
+    pub fn check(
+        &self,
+        property: &PropertyCore,
+        today: NaiveDate,
+    ) -> Result<Option<NaiveDate>, String> {
+        if !property.status.can_become(&self.status) {
+            return Err(format!(
+                "A property cannot go from {} to {}",
+                property.status.as_str(),
+                self.status.as_str()
+            ));
+        }
+        if self.status == PropertyStatus::Let {
+            return match self.tenancy_end_date {
+                Some(end) if end > today => Ok(Some(end)),
+                _ => Err("tenancy_end_date must be in the future".to_string()),
+            };
+        }
+        if property.status == PropertyStatus::Let {
+            return match self.tenancy_end_date.or(property.tenancy_end_date) {
+                Some(end) if end <= today => Ok(Some(end)),
+                Some(end) => Err(format!(
+                    "The tenancy runs until {}; give the earlier tenancy_end_date if it ended early",
+                    end
+                )),
+                None => Err("No tenancy end date is recorded; give tenancy_end_date".to_string()),
+            };
+        }
+        if self.tenancy_end_date.is_some() {
+            return Err(
+                "tenancy_end_date only applies when letting a property or moving a let one on"
+                    .to_string(),
+            );
+        }
+        Ok(None)
+    }
+}
+
+/// One change of a property's status.
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PropertyStatusHistoryEntry {
+    pub history_id: Uuid,
+    pub property_id: Uuid,
+    /// `null` for the status the property was created with.
+    pub previous_status: Option<PropertyStatus>,
+    pub status: PropertyStatus,
+    pub reason: Option<String>,
+    pub tenancy_end_date: Option<NaiveDate>,
+    /// The staff member who made the change.
+    pub changed_by: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// How long a property has spent on the market, which is the time it was
+/// `available`. Periods are counted in whole days.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DaysOnMarket {
+    pub status: Option<PropertyStatus>,
+    /// When the property took its current status.
+    pub status_since: Option<DateTime<Utc>>,
+    pub days_in_status: i64,
+    /// All time spent available.
+    pub days_on_market: i64,
+    /// Days since the property last became available, while it still is.
+    pub current_days_on_market: Option<i64>,
+    /// Times the property went from available to let agreed or let.
+    pub lettings: i64,
+    /// Average days available before each of those lettings.
+    pub average_days_to_let: Option<f64>,
+}
+
+impl DaysOnMarket {
+    /// Works the metrics out from `history`, oldest first, up to `now`.
+    pub fn from_history(history: &[PropertyStatusHistoryEntry], now: DateTime<Utc>) -> Self {
+        let mut metrics = DaysOnMarket {
+            status: None,
+            status_since: None,
+            days_in_status: 0,
+            days_on_market: 0,
+            current_days_on_market: None,
+            lettings: 0,
+            average_days_to_let: None,
+        };
+        let mut days_to_let = 0;
+        for (index, entry) in history.iter().enumerate() {
+            let next = history.get(index + 1);
+            let days = (next.map_or(now, |next| next.changed_at) - entry.changed_at).num_days();
+            if entry.status != PropertyStatus::Available {
+                continue;
+            }
+            metrics.days_on_market += days;
+            match next {
+                Some(next)
+                    if matches!(next.status, PropertyStatus::LetAgreed | PropertyStatus::Let) =>
+                {
+                    metrics.lettings += 1;
+                    days_to_let += days;
+                }
+                Some(_) => {}
+                None => metrics.current_days_on_market = Some(days),
+            }
+        }
+        if let Some(last) = history.last() {
+            metrics.status = Some(last.status.clone());
+            metrics.status_since = Some(last.changed_at);
+            metrics.days_in_status = (now - last.changed_at).num_days();
+        }
+        if metrics.lettings > 0 {
+            metrics.average_days_to_let = Some(days_to_let as f64 / metrics.lettings as f64);
+        }
+        metrics
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PropertyStatusHistory {
+    pub property_id: Uuid,
+    /// Oldest first.
+    pub history: Vec<PropertyStatusHistoryEntry>,
+    pub metrics: DaysOnMarket,
+}
@@ -0,0 +1,231 @@
+use chrono::NaiveDate;
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::certificate_document::{
+    CertificateDocument, CertificateRenewal,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Certificate not found")]
+    CertificateNotFound,
+    #[display(fmt = "Document not found")]
+    NotFound,
+    #[display(fmt = "{}", _0)]
+    Invalid(String),
+    #[display(fmt = "Document has already been verified")]
+    AlreadyVerified,
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+/// A stored file to record as the next version of a certificate's document.
+pub struct NewCertificateDocument {
+    pub document_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub storage_key: String,
+    pub uploaded_by: Uuid,
+}
+
+pub struct CertificateDocumentRepository {}
+
+impl CertificateDocumentRepository {
+    pub fn new() -> Self {
+        CertificateDocumentRepository {}
+    }
+
+    /// Every version of a certificate's document, newest first.
+    pub async fn get_all(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+    ) -> Result<Vec<CertificateDocument>, CustomErrors> {
+        let rows = sqlx::query_as::<_, CertificateDocument>(
+            "SELECT * FROM certificate_documents
+             WHERE property_id = $1 AND certificate_id = $2
+             ORDER BY version DESC",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+        document_id: Uuid,
+    ) -> Result<Option<CertificateDocument>, CustomErrors> {
+        let row = sqlx::query_as::<_, CertificateDocument>(
+            "SELECT * FROM certificate_documents
+             WHERE property_id = $1 AND certificate_id = $2 AND document_id = $3",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .bind(document_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Records `document` as the certificate's next version. A renewal moves
+    /// the certificate to the new dates in the same transaction; either way
+    /// the version keeps the dates the certificate has once it is saved.
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+        document: NewCertificateDocument,
+        renewal: &CertificateRenewal,
+    ) -> Result<CertificateDocument, CustomErrors> {
+        let mut tx = pool.begin().await?;
+        // Locking the certificate keeps concurrent uploads from taking the
+        // same version number.
+        let dates = sqlx::query_as::<_, (NaiveDate, Option<NaiveDate>)>(
+            "SELECT start_date, expiry_date FROM property_certificates
+             WHERE property_id = $1 AND certificate_id = $2
+             FOR UPDATE",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (start_date, expiry_date) = match dates {
+            Some((start_date, expiry_date)) => (
+                renewal.start_date.unwrap_or(start_date),
+                renewal.expiry_date.or(expiry_date),
+            ),
+            None => return Err(CustomErrors::CertificateNotFound),
+        };
+        if expiry_date.is_some_and(|expiry_date| expiry_date < start_date) {
+            return Err(CustomErrors::Invalid(
+                "expiry_date cannot be before start_date".to_string(),
+            ));
+        }
+
+        if renewal.is_renewal() {
+            sqlx::query(
+                "UPDATE property_certificates
+                 SET start_date = $2, expiry_date = $3, updated_at = CURRENT_TIMESTAMP
+                 WHERE certificate_id = $1",
+            )
+            .bind(certificate_id)
+            .bind(start_date)
+            .bind(expiry_date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let row = sqlx::query_as::<_, CertificateDocument>(
+            "INSERT INTO certificate_documents
+                 (document_id, certificate_id, property_id, version, file_name, content_type,
+                  size_bytes, sha256, storage_key, start_date, expiry_date, uploaded_by)
+             VALUES ($1, $2, $3,
+                     (SELECT COALESCE(MAX(version), 0) + 1 FROM certificate_documents WHERE certificate_id = $2),
+                     $4, $5, $6, $7, $8, $9, $10, $11)
+             RETURNING *",
+        )
+        .bind(document.document_id)
+        .bind(certificate_id)
+        .bind(property_id)
+        .bind(document.file_name)
+        .bind(document.content_type)
+        .bind(document.size_bytes)
+        .bind(document.sha256)
+        .bind(document.storage_key)
+        .bind(start_date)
+        .bind(expiry_date)
+        .bind(document.uploaded_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    /// Records that `verified_by` checked the document. A document is only
+    /// verified once, so the first check stands.
+    pub async fn verify(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+        document_id: Uuid,
+        verified_by: Uuid,
+    ) -> Result<CertificateDocument, CustomErrors> {
+        let row = sqlx::query_as::<_, CertificateDocument>(
+            "UPDATE certificate_documents
+             SET verified_by = $4, verified_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1 AND certificate_id = $2 AND document_id = $3
+               AND verified_at IS NULL
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .bind(document_id)
+        .bind(verified_by)
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(row),
+            None => match self
+                .get(pool, property_id, certificate_id, document_id)
+                .await?
+            {
+                Some(_) => Err(CustomErrors::AlreadyVerified),
+                None => Err(CustomErrors::NotFound),
+            },
+        }
+    }
+
+    /// Looks a document up by id alone, for signed download links.
+    pub async fn get_by_id(
+        &self,
+        pool: &Pool<Postgres>,
+        document_id: Uuid,
+    ) -> Result<Option<CertificateDocument>, CustomErrors> {
+        let row = sqlx::query_as::<_, CertificateDocument>(
+            "SELECT * FROM certificate_documents WHERE document_id = $1",
+        )
+        .bind(document_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Where every version of a certificate's document is stored, so the
+    /// files can be removed with it.
+    pub async fn storage_keys(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+    ) -> Result<Vec<String>, CustomErrors> {
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT storage_key FROM certificate_documents
+             WHERE property_id = $1 AND certificate_id = $2",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(keys)
+    }
+}
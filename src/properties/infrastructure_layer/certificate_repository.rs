@@ -0,0 +1,196 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::property_certificates::{Certificate, CertificateRequest};
+use crate::shared::{
+    domain_layer::outbox_event::{CertificateExpiring, OutboxEvent, PROPERTY_EVENTS_TOPIC},
+    infrastructure_layer::outbox_repository::OutboxRepository,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Certificate not found")]
+    NotFound,
+    #[display(fmt = "Certificate was modified by someone else")]
+    PreconditionFailed,
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+pub struct CertificateRepository {}
+
+impl CertificateRepository {
+    pub fn new() -> Self {
+        CertificateRepository {}
+    }
+
+    pub async fn get_all(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Vec<Certificate>, CustomErrors> {
+        let rows = sqlx::query_as::<_, Certificate>(
+            "SELECT * FROM property_certificates WHERE property_id = $1 ORDER BY expiry_date NULLS LAST",
+        )
+        .bind(property_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+    ) -> Result<Option<Certificate>, CustomErrors> {
+        let row = sqlx::query_as::<_, Certificate>(
+            "SELECT * FROM property_certificates WHERE property_id = $1 AND certificate_id = $2",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        request: CertificateRequest,
+    ) -> Result<Certificate, CustomErrors> {
+        let row = sqlx::query_as::<_, Certificate>(
+            "INSERT INTO property_certificates (certificate_id, property_id, certificate_type, expiry_date, responsibility_name, start_date, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(property_id)
+        .bind(request.certificate_type)
+        .bind(request.expiry_date)
+        .bind(request.responsibility_name)
+        .bind(request.start_date)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Replaces the row only if it still has the `updated_at` the caller last
+    /// saw; `expected_updated_at = None` skips the check.
+    pub async fn update(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+        request: CertificateRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Certificate, CustomErrors> {
+        let row = sqlx::query_as::<_, Certificate>(
+            "UPDATE property_certificates
+             SET certificate_type = $3, expiry_date = $4, responsibility_name = $5, start_date = $6, updated_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1 AND certificate_id = $2 AND ($7::timestamptz IS NULL OR updated_at = $7)
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .bind(request.certificate_type)
+        .bind(request.expiry_date)
+        .bind(request.responsibility_name)
+        .bind(request.start_date)
+        .bind(expected_updated_at)
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(row),
+            None => match self.get(pool, property_id, certificate_id).await? {
+                Some(_) => Err(CustomErrors::PreconditionFailed),
+                None => Err(CustomErrors::NotFound),
+            },
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        certificate_id: Uuid,
+    ) -> Result<(), CustomErrors> {
+        let result = sqlx::query(
+            "DELETE FROM property_certificates WHERE property_id = $1 AND certificate_id = $2",
+        )
+        .bind(property_id)
+        .bind(certificate_id)
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Raises a `CertificateExpiring` event for each certificate of a live
+    /// property that expires within `within_days` (or already has) and has not
+    /// been announced for its current expiry date. Returns how many were raised.
+    pub async fn announce_expiring(
+        &self,
+        pool: &Pool<Postgres>,
+        today: NaiveDate,
+        within_days: i32,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let expiring = sqlx::query_as::<_, (Uuid, Uuid, String, NaiveDate)>(
+            "SELECT c.certificate_id, c.property_id, c.certificate_type::text, c.expiry_date
+             FROM property_certificates c
+             JOIN property_core p ON p.property_id = c.property_id
+             WHERE p.deleted_at IS NULL
+               AND c.expiry_date IS NOT NULL
+               AND c.expiry_date <= $1::date + $2
+               AND c.expiry_notified_for IS DISTINCT FROM c.expiry_date
+             FOR UPDATE OF c SKIP LOCKED",
+        )
+        .bind(today)
+        .bind(within_days)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (certificate_id, property_id, certificate_type, expiry_date) in &expiring {
+            let event = CertificateExpiring {
+                certificate_id: certificate_id.to_string(),
+                property_id: property_id.to_string(),
+                certificate_type: certificate_type.clone(),
+                expiry_date: expiry_date.to_string(),
+                days_remaining: (*expiry_date - today).num_days(),
+            };
+            OutboxRepository::enqueue(
+                &mut tx,
+                &OutboxEvent::new(
+                    PROPERTY_EVENTS_TOPIC,
+                    "property",
+                    *property_id,
+                    "CertificateExpiring",
+                    &event,
+                ),
+            )
+            .await?;
+            sqlx::query(
+                "UPDATE property_certificates SET expiry_notified_for = expiry_date WHERE certificate_id = $1",
+            )
+            .bind(certificate_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(expiring.len() as u64)
+    }
+}
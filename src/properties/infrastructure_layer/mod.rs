@@ -1,6 +1,15 @@
 pub mod auth_repo;
+pub mod certificate_document_repository;
+pub mod certificate_repository;
 pub mod custom_error_repo;
-pub mod kafka_consumer;
 pub mod properties_repository;
 pub mod property_address_repository;
+pub mod property_compliance_repository;
+pub mod property_full_repository;
 pub mod property_images_repository;
+pub mod property_keys_repository;
+pub mod property_photos_repository;
+pub mod property_rental_info_repository;
+pub mod property_search_repository;
+pub mod property_services_repository;
+pub mod property_specifications_repository;
@@ -1,14 +1,49 @@
 #![allow(dead_code)]
 
 use crate::{
-    properties::domain_layer::property_core::{PropertyCore, PropertyStatus},
+    properties::domain_layer::{
+        property_compliance::warning_days,
+        property_core::{PropertyCore, PropertyCoreUpdate, PropertyStatus},
+        property_status_history::{PropertyStatusHistoryEntry, PropertyStatusTransition},
+    },
+    shared::{
+        domain_layer::{
+            email::{EmailRecipient, EmailTemplate, QueuedEmail},
+            outbox_event::{OutboxEvent, PropertyStatusChanged, PROPERTY_EVENTS_TOPIC},
+        },
+        infrastructure_layer::{
+            email_queue_repository::EmailQueueRepository, outbox_repository::OutboxRepository,
+        },
+    },
     AppState,
 };
+use crate::properties::infrastructure_layer::property_compliance_repository::PropertyComplianceRepository;
 use actix_web::web::Json;
+use chrono::{DateTime, NaiveDate, Utc};
+use derive_more::Display;
+use serde::Serialize;
 use serde_json::json;
+use sqlx::{Pool, Postgres, Transaction};
 use std::sync::Arc;
 use uuid::Uuid;
-use chrono::Utc;
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Property not found")]
+    NotFound,
+    #[display(fmt = "Property was modified by someone else")]
+    PreconditionFailed,
+    #[display(fmt = "{}", _0)]
+    IllegalTransition(String),
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
 
 pub struct PropertyRepository {}
 
@@ -17,19 +52,29 @@ impl PropertyRepository {
         PropertyRepository {}
     }
 
-    pub async fn save_property(&self, state: Arc<AppState>, property: PropertyCore) -> Result<PropertyCore, Json<String>> {
-        let property_id = Uuid::new_v4();
+    /// Creates a property, or replaces it when `property_id` is set and already exists.
+    /// An existing property keeps its status; see `transition_status`.
+    pub async fn save_property(&self, state: Arc<AppState>, property: PropertyCore, changed_by: Option<Uuid>) -> Result<PropertyCore, CustomErrors> {
+        let property_id = property.property_id.unwrap_or_else(Uuid::new_v4);
         let query = r#"
             INSERT INTO property_core (property_id, status, property_type, letting_classification, staff_assigned, landlord_id, date_available, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CURRENT_TIMESTAMP)
             ON CONFLICT (property_id) 
             DO UPDATE 
-            SET status = $2, property_type = $3, letting_classification = $4, staff_assigned = $5, landlord_id = $6, date_available = $7, updated_at = $9
+            SET status = $2, property_type = $3, letting_classification = $4, staff_assigned = $5, landlord_id = $6, date_available = $7, updated_at = CURRENT_TIMESTAMP
             RETURNING *;
         "#;
         
+        let mut tx = state.db.begin().await?;
+        let previous = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE property_id = $1 FOR UPDATE")
+            .bind(property_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if previous.as_ref().is_some_and(|previous| previous.status != property.status) {
+            return Err(Self::status_not_editable(property_id));
+        }
         let result = sqlx::query_as::<_, PropertyCore>(query)
-            .bind(&property_id)
+            .bind(property_id)
             .bind(property.status)
             .bind(property.property_type)
             .bind(property.letting_classification)
@@ -37,21 +82,211 @@ impl PropertyRepository {
             .bind(property.landlord_id)
             .bind(property.date_available)
             .bind(property.created_at)
-            .bind(property.updated_at)
-            .fetch_one(&state.db)
-            .await;
-        
-        match result {
-            Ok(saved_property) => Ok(saved_property),
-            Err(e) => Err(Json(e.to_string())),
+            .fetch_one(&mut *tx)
+            .await?;
+
+        if previous.is_none() {
+            Self::insert_history(&mut tx, &Self::history_entry(None, &result, changed_by)).await?;
         }
+        Self::record_changes(&mut tx, previous.as_ref(), &result).await?;
+        tx.commit().await?;
+        Ok(result)
     }
 
-    
+    // Update a property with the details `change` works out from its current row, only if it
+    // still has the `updated_at` the caller last saw. `expected_updated_at = None` skips the check.
+    pub async fn update(
+        &self,
+        property_id: Uuid,
+        expected_updated_at: Option<DateTime<Utc>>,
+        state: Arc<AppState>,
+        change: impl FnOnce(&PropertyCore) -> PropertyCoreUpdate,
+    ) -> Result<PropertyCore, CustomErrors> {
+        let mut tx = state.db.begin().await?;
+        let previous = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE property_id = $1 AND deleted_at IS NULL FOR UPDATE")
+            .bind(property_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(CustomErrors::NotFound)?;
+        if expected_updated_at.is_some_and(|expected| expected != previous.updated_at) {
+            return Err(CustomErrors::PreconditionFailed);
+        }
+
+        let details = change(&previous);
+        if details.status != previous.status {
+            return Err(Self::status_not_editable(property_id));
+        }
+        let result = sqlx::query_as::<_, PropertyCore>(
+            "UPDATE property_core
+             SET status = $2, property_type = $3, letting_classification = $4, staff_assigned = $5, landlord_id = $6, date_available = $7, updated_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(details.status)
+        .bind(details.property_type)
+        .bind(details.letting_classification)
+        .bind(details.staff_assigned)
+        .bind(details.landlord_id)
+        .bind(details.date_available)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::record_changes(&mut tx, Some(&previous), &result).await?;
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    /// Moves a property to another status, if `transition` allows it from the
+    /// status it has now, and records the change in its status history.
+    pub async fn transition_status(
+        &self,
+        property_id: Uuid,
+        transition: &PropertyStatusTransition,
+        changed_by: Uuid,
+        state: Arc<AppState>,
+    ) -> Result<PropertyCore, CustomErrors> {
+        let mut tx = state.db.begin().await?;
+        let previous = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE property_id = $1 AND deleted_at IS NULL FOR UPDATE")
+            .bind(property_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(CustomErrors::NotFound)?;
+        let today = Utc::now().date_naive();
+        let tenancy_end_date = transition
+            .check(&previous, today)
+            .map_err(CustomErrors::IllegalTransition)?;
+        if transition.status == PropertyStatus::Let {
+            Self::require_compliant(&mut tx, property_id, today).await?;
+        }
+
+        // Only a let property has a tenancy running.
+        let result = sqlx::query_as::<_, PropertyCore>(
+            "UPDATE property_core
+             SET status = $2, tenancy_end_date = $3, updated_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(transition.status.clone())
+        .bind(tenancy_end_date.filter(|_| transition.status == PropertyStatus::Let))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let entry = PropertyStatusHistoryEntry {
+            reason: transition.reason.clone(),
+            tenancy_end_date,
+            ..Self::history_entry(Some(&previous.status), &result, Some(changed_by))
+        };
+        Self::insert_history(&mut tx, &entry).await?;
+        Self::record_changes(&mut tx, Some(&previous), &result).await?;
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    // A property's status changes, oldest first
+    pub async fn get_status_history(&self, property_id: Uuid, pool: &Pool<Postgres>) -> Result<Vec<PropertyStatusHistoryEntry>, sqlx::Error> {
+        sqlx::query_as::<_, PropertyStatusHistoryEntry>("SELECT * FROM property_status_history WHERE property_id = $1 ORDER BY changed_at, history_id")
+            .bind(property_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    // Letting a property without the certificates it requires is against the law
+    async fn require_compliant(tx: &mut Transaction<'_, Postgres>, property_id: Uuid, today: NaiveDate) -> Result<(), CustomErrors> {
+        let assessed = PropertyComplianceRepository::assess(tx, Some(property_id), None, today, warning_days())
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        let problems: Vec<String> = assessed
+            .iter()
+            .flat_map(|compliance| &compliance.requirements)
+            .filter(|requirement| !requirement.status.allows_letting())
+            .map(|requirement| format!("{} {}", requirement.certificate_type.label(), requirement.status.as_str()))
+            .collect();
+        if problems.is_empty() {
+            return Ok(());
+        }
+        Err(CustomErrors::IllegalTransition(format!("The property cannot be let: {}", problems.join(", "))))
+    }
+
+    fn status_not_editable(property_id: Uuid) -> CustomErrors {
+        CustomErrors::IllegalTransition(format!("Change the status with POST /api/v1/properties/{}/status", property_id))
+    }
+
+    fn history_entry(previous_status: Option<&PropertyStatus>, result: &PropertyCore, changed_by: Option<Uuid>) -> PropertyStatusHistoryEntry {
+        PropertyStatusHistoryEntry {
+            history_id: Uuid::new_v4(),
+            property_id: result.property_id.unwrap_or_default(),
+            previous_status: previous_status.cloned(),
+            status: result.status.clone(),
+            reason: None,
+            tenancy_end_date: result.tenancy_end_date,
+            changed_by,
+            changed_at: result.updated_at,
+        }
+    }
+
+    async fn insert_history(tx: &mut Transaction<'_, Postgres>, entry: &PropertyStatusHistoryEntry) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO property_status_history (history_id, property_id, previous_status, status, reason, tenancy_end_date, changed_by, changed_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(entry.history_id)
+        .bind(entry.property_id)
+        .bind(entry.previous_status.clone())
+        .bind(entry.status.clone())
+        .bind(entry.reason.clone())
+        .bind(entry.tenancy_end_date)
+        .bind(entry.changed_by)
+        .bind(entry.changed_at)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    // Queue the status event and assignment email for whatever changed
+    async fn record_changes(
+        tx: &mut Transaction<'_, Postgres>,
+        previous: Option<&PropertyCore>,
+        result: &PropertyCore,
+    ) -> Result<(), sqlx::Error> {
+        let property_id = result.property_id.unwrap_or_default();
+        // A new property has no previous status.
+        let previous_status = previous.map(|p| p.status.as_str()).unwrap_or("");
+        if previous_status != result.status.as_str() {
+            let status_changed = PropertyStatusChanged {
+                property_id: property_id.to_string(),
+                previous_status: previous_status.to_string(),
+                status: result.status.as_str().to_string(),
+            };
+            let event = OutboxEvent::new(
+                PROPERTY_EVENTS_TOPIC,
+                "property",
+                property_id,
+                "PropertyStatusChanged",
+                &status_changed,
+            );
+            OutboxRepository::enqueue(tx, &event).await?;
+        }
+
+        let previous_staff = previous.and_then(|p| p.staff_assigned);
+        if let Some(staff_id) = result.staff_assigned.filter(|id| Some(*id) != previous_staff) {
+            let email = QueuedEmail::new(
+                EmailTemplate::AssignmentChanged,
+                EmailRecipient::Staff(staff_id),
+                json!({
+                    "subject": format!("property {}", property_id),
+                    "details": format!("Status: {}", result.status.as_str()),
+                }),
+            );
+            EmailQueueRepository::enqueue(tx, &email).await?;
+        }
+        Ok(())
+    }
 
     // Get all properties
     pub async fn get_all(&self, state: Arc<AppState>) -> Result<Vec<PropertyCore>, Json<String>> {
-        let result = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core")
+        let result = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE deleted_at IS NULL")
             .fetch_all(&state.db)
             .await;
         match result {
@@ -61,10 +296,10 @@ impl PropertyRepository {
     }
 
     // Get one property by its ID
-    pub async fn get_one_by_id(&self, property_id: Uuid, state: Arc<AppState>) -> Result<PropertyCore, Json<String>> {
-        let result = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE property_id = $1")
+    pub async fn get_one_by_id(&self, property_id: Uuid, state: Arc<AppState>) -> Result<Option<PropertyCore>, Json<String>> {
+        let result = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE property_id = $1 AND deleted_at IS NULL")
             .bind(property_id)
-            .fetch_one(&state.db)
+            .fetch_optional(&state.db)
             .await;
         
         match result {
@@ -75,7 +310,7 @@ impl PropertyRepository {
 
     // Get properties by landlord ID
     pub async fn get_one_by_user_id(&self, landlord_id: Uuid, state: Arc<AppState>) -> Result<Vec<PropertyCore>, Json<String>> {
-        let result = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE landlord_id = $1")
+        let result = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE landlord_id = $1 AND deleted_at IS NULL")
             .bind(landlord_id)
             .fetch_all(&state.db)
             .await;
@@ -85,4 +320,43 @@ impl PropertyRepository {
             Err(e) => Err(Json(e.to_string())),
         }
     }
+
+    // Get properties assigned to a staff member
+    pub async fn get_by_staff_id(&self, staff_id: Uuid, state: Arc<AppState>) -> Result<Vec<PropertyCore>, Json<String>> {
+        let result = sqlx::query_as::<_, PropertyCore>("SELECT * FROM property_core WHERE staff_assigned = $1 AND deleted_at IS NULL")
+            .bind(staff_id)
+            .fetch_all(&state.db)
+            .await;
+
+        match result {
+            Ok(properties) => Ok(properties),
+            Err(e) => Err(Json(e.to_string())),
+        }
+    }
+
+    // Soft-delete a property; returns false when there was nothing to delete
+    pub async fn delete(&self, property_id: Uuid, state: Arc<AppState>) -> Result<bool, Json<String>> {
+        let result = sqlx::query("UPDATE property_core SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE property_id = $1 AND deleted_at IS NULL")
+            .bind(property_id)
+            .execute(&state.db)
+            .await;
+
+        match result {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => Err(Json(e.to_string())),
+        }
+    }
+
+    // Restore a soft-deleted property
+    pub async fn restore(&self, property_id: Uuid, state: Arc<AppState>) -> Result<Option<PropertyCore>, Json<String>> {
+        let result = sqlx::query_as::<_, PropertyCore>("UPDATE property_core SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE property_id = $1 AND deleted_at IS NOT NULL RETURNING *")
+            .bind(property_id)
+            .fetch_optional(&state.db)
+            .await;
+
+        match result {
+            Ok(property) => Ok(property),
+            Err(e) => Err(Json(e.to_string())),
+        }
+    }
 }
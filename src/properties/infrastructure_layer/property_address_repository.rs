@@ -1,13 +1,37 @@
 #![allow(dead_code)]
 
 use crate::{
-    properties::domain_layer::property_address::PropertyAddress,
+    properties::domain_layer::property_address::{PropertyAddress, PropertyAddressRequest},
     AppState,
 };
 use actix_web::web::Json;
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
 use uuid::Uuid;
 use std::sync::Arc;
 
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Property address not found")]
+    NotFound,
+    #[display(fmt = "Property address was modified by someone else")]
+    PreconditionFailed,
+    #[display(fmt = "Property already has an address")]
+    AlreadyExists,
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => CustomErrors::AlreadyExists,
+            _ => CustomErrors::DatabaseError(e.to_string()),
+        }
+    }
+}
+
 pub struct PropertyAddressRepository {}
 
 impl PropertyAddressRepository {
@@ -15,20 +39,17 @@ impl PropertyAddressRepository {
         PropertyAddressRepository {}
     }
 
-    // Save a property address (insert or update)
-    pub async fn save(&self, address: PropertyAddress, state: Arc<AppState>) -> Result<PropertyAddress, Json<String>> {
+    // Save the address of a property that does not have one yet
+    pub async fn save(&self, property_id: Uuid, address: PropertyAddressRequest, state: Arc<AppState>) -> Result<PropertyAddress, CustomErrors> {
         let query = r#"
             INSERT INTO property_address (address_id, property_id, display_address, address_line1, address_line2, town_city, county, postcode, country, searchable_area, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-            ON CONFLICT (address_id) 
-            DO UPDATE 
-            SET property_id = $2, display_address = $3, address_line1 = $4, address_line2 = $5, town_city = $6, county = $7, postcode = $8, country = $9, searchable_area = $10, updated_at = $12
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
             RETURNING *;
         "#;
 
-        let result = sqlx::query_as::<_, PropertyAddress>(query)
-            .bind(address.address_id)
-            .bind(address.property_id)
+        let saved_address = sqlx::query_as::<_, PropertyAddress>(query)
+            .bind(Uuid::new_v4())
+            .bind(property_id)
             .bind(address.display_address)
             .bind(address.address_line1)
             .bind(address.address_line2)
@@ -37,14 +58,41 @@ impl PropertyAddressRepository {
             .bind(address.postcode)
             .bind(address.country)
             .bind(address.searchable_area)
-            .bind(address.created_at)
-            .bind(address.updated_at)
             .fetch_one(&state.db)
-            .await;
+            .await?;
+        Ok(saved_address)
+    }
+
+    // Update a property's address, only if it still has the `updated_at` the caller last saw.
+    // `expected_updated_at = None` skips the check (`If-Match: *`).
+    pub async fn update(&self, property_id: Uuid, address: PropertyAddressRequest, expected_updated_at: Option<DateTime<Utc>>, state: Arc<AppState>) -> Result<PropertyAddress, CustomErrors> {
+        let query = r#"
+            UPDATE property_address
+            SET display_address = $2, address_line1 = $3, address_line2 = $4, town_city = $5, county = $6, postcode = $7, country = $8, searchable_area = $9, updated_at = CURRENT_TIMESTAMP
+            WHERE property_id = $1 AND ($10::timestamptz IS NULL OR updated_at = $10)
+            RETURNING *;
+        "#;
+
+        let result = sqlx::query_as::<_, PropertyAddress>(query)
+            .bind(property_id)
+            .bind(address.display_address)
+            .bind(address.address_line1)
+            .bind(address.address_line2)
+            .bind(address.town_city)
+            .bind(address.county)
+            .bind(address.postcode)
+            .bind(address.country)
+            .bind(address.searchable_area)
+            .bind(expected_updated_at)
+            .fetch_optional(&state.db)
+            .await?;
 
         match result {
-            Ok(saved_address) => Ok(saved_address),
-            Err(e) => Err(Json(e.to_string())),
+            Some(updated_address) => Ok(updated_address),
+            None => match self.get_by_property_id(property_id, state).await? {
+                Some(_) => Err(CustomErrors::PreconditionFailed),
+                None => Err(CustomErrors::NotFound),
+            },
         }
     }
 
@@ -73,16 +121,12 @@ impl PropertyAddressRepository {
         }
     }
 
-    // Get property addresses by property_id
-    pub async fn get_one_by_property_id(&self, property_id: Uuid, state: Arc<AppState>) -> Result<Vec<PropertyAddress>, Json<String>> {
-        let result = sqlx::query_as::<_, PropertyAddress>("SELECT * FROM property_address WHERE property_id = $1")
+    // Get the address of a property
+    pub async fn get_by_property_id(&self, property_id: Uuid, state: Arc<AppState>) -> Result<Option<PropertyAddress>, CustomErrors> {
+        let address = sqlx::query_as::<_, PropertyAddress>("SELECT * FROM property_address WHERE property_id = $1")
             .bind(property_id)
-            .fetch_all(&state.db)
-            .await;
-
-        match result {
-            Ok(addresses) => Ok(addresses),
-            Err(e) => Err(Json(e.to_string())),
-        }
+            .fetch_optional(&state.db)
+            .await?;
+        Ok(address)
     }
 }
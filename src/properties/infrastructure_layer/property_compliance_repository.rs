@@ -0,0 +1,214 @@
+use chrono::{NaiveDate, NaiveTime};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{PgConnection, Pool, Postgres};
+use uuid::Uuid;
+
+use crate::diary::{
+    domain_layer::diary_event_types::{Event, EventDetails, EventType, NoteDetails},
+    infrastructure_layer::diary_event_repo::EventRepository,
+};
+use crate::properties::domain_layer::{
+    property_certificates::Certificate,
+    property_compliance::{ComplianceStatus, ComplianceSubject, PropertyCompliance},
+    property_core::PropertyStatus,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+pub struct PropertyComplianceRepository {}
+
+impl PropertyComplianceRepository {
+    pub fn new() -> Self {
+        PropertyComplianceRepository {}
+    }
+
+    /// How one live property stands, or `None` when it cannot be found.
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        today: NaiveDate,
+        within_days: i32,
+    ) -> Result<Option<PropertyCompliance>, CustomErrors> {
+        let mut conn = pool.acquire().await?;
+        Self::assess(&mut conn, Some(property_id), None, today, within_days)
+            .await
+            .map(|mut assessed| assessed.pop())
+    }
+
+    /// How every live property stands, or only those assigned to `staff_id`.
+    pub async fn get_portfolio(
+        &self,
+        pool: &Pool<Postgres>,
+        staff_id: Option<Uuid>,
+        today: NaiveDate,
+        within_days: i32,
+    ) -> Result<Vec<PropertyCompliance>, CustomErrors> {
+        let mut conn = pool.acquire().await?;
+        Self::assess(&mut conn, None, staff_id, today, within_days).await
+    }
+
+    /// Checks live properties against the certificates they require. Takes a
+    /// connection so it can run inside a caller's transaction.
+    pub async fn assess(
+        conn: &mut PgConnection,
+        property_id: Option<Uuid>,
+        staff_id: Option<Uuid>,
+        today: NaiveDate,
+        within_days: i32,
+    ) -> Result<Vec<PropertyCompliance>, CustomErrors> {
+        let subjects = sqlx::query_as::<_, ComplianceSubject>(
+            "SELECT p.property_id, p.status, p.letting_classification, p.staff_assigned, p.branch_id,
+                    a.display_address, s.is_hmo
+             FROM property_core p
+             LEFT JOIN property_address a ON a.property_id = p.property_id
+             LEFT JOIN property_specifications s ON s.property_id = p.property_id
+             WHERE p.deleted_at IS NULL
+               AND ($1::uuid IS NULL OR p.property_id = $1)
+               AND ($2::uuid IS NULL OR p.staff_assigned = $2)",
+        )
+        .bind(property_id)
+        .bind(staff_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        let property_ids: Vec<Uuid> = subjects.iter().map(|subject| subject.property_id).collect();
+        let certificates = sqlx::query_as::<_, Certificate>(
+            "SELECT * FROM property_certificates WHERE property_id = ANY($1)",
+        )
+        .bind(&property_ids)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(subjects
+            .iter()
+            .map(|subject| {
+                let held: Vec<Certificate> = certificates
+                    .iter()
+                    .filter(|certificate| certificate.property_id == subject.property_id)
+                    .cloned()
+                    .collect();
+                PropertyCompliance::assess(subject, &held, today, within_days)
+            })
+            .collect())
+    }
+
+    /// Raises a diary note for the assigned staff member of each live,
+    /// not withdrawn property for every required certificate that is missing,
+    /// expired or expiring within `within_days`. Each is raised once per
+    /// status and expiry date. Returns how many were raised.
+    pub async fn raise_reminders(
+        &self,
+        pool: &Pool<Postgres>,
+        today: NaiveDate,
+        within_days: i32,
+    ) -> Result<u64, CustomErrors> {
+        let mut tx = pool.begin().await?;
+        let assessed = Self::assess(&mut tx, None, None, today, within_days).await?;
+
+        let mut raised = 0;
+        for property in &assessed {
+            let staff_id = match property.staff_assigned {
+                Some(staff_id) if property.property_status != PropertyStatus::Withdrawn => staff_id,
+                _ => continue,
+            };
+            for requirement in &property.requirements {
+                if requirement.status == ComplianceStatus::Compliant {
+                    continue;
+                }
+                let reminder_id = sqlx::query_scalar::<_, Uuid>(
+                    "INSERT INTO compliance_reminders (reminder_id, property_id, certificate_type, status, expiry_date)
+                     VALUES ($1, $2, $3, $4, $5)
+                     ON CONFLICT DO NOTHING
+                     RETURNING reminder_id",
+                )
+                .bind(Uuid::new_v4())
+                .bind(property.property_id)
+                .bind(requirement.certificate_type.clone())
+                .bind(requirement.status.as_str())
+                .bind(requirement.expiry_date)
+                .fetch_optional(&mut *tx)
+                .await?;
+                let reminder_id = match reminder_id {
+                    Some(reminder_id) => reminder_id,
+                    None => continue,
+                };
+
+                let label = requirement.certificate_type.label();
+                let problem = match (requirement.status, requirement.expiry_date) {
+                    (ComplianceStatus::Expired, Some(expiry_date)) => {
+                        format!("{} expired on {}", label, expiry_date)
+                    }
+                    (ComplianceStatus::Expiring, Some(expiry_date)) => {
+                        format!("{} expires on {}", label, expiry_date)
+                    }
+                    _ => format!("{} missing", label),
+                };
+                let address = property.display_address.as_deref().unwrap_or("Property");
+                let event = Event {
+                    id: None,
+                    external_id: format!("compliance-{}", reminder_id),
+                    event_type: EventType::Note,
+                    date: today,
+                    start_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+                    end_time: NaiveTime::from_hms_opt(9, 15, 0).unwrap_or_default(),
+                    title: Some(format!("{}: {}", address, problem)),
+                    description: Some(format!(
+                        "{}. The property cannot be let until it holds a valid one.",
+                        problem
+                    )),
+                    created_by: staff_id,
+                    created_at: None,
+                    updated_at: None,
+                    branch_id: None,
+                };
+                let details = EventDetails::Note(NoteDetails {
+                    note_type: "compliance_reminder".to_string(),
+                    assigned_staff: Some(vec![staff_id]),
+                    is_private: Some(false),
+                    category: Some("compliance".to_string()),
+                    priority: Some(
+                        if requirement.status == ComplianceStatus::Expiring {
+                            "normal"
+                        } else {
+                            "high"
+                        }
+                        .to_string(),
+                    ),
+                    related_entity_type: Some("property".to_string()),
+                    related_entity_id: Some(property.property_id.to_string()),
+                    status: Some("open".to_string()),
+                    completion_date: None,
+                    completed_by: None,
+                });
+                let event = EventRepository::insert_event(
+                    &mut tx,
+                    event,
+                    details,
+                    Some(property.branch_id),
+                )
+                .await
+                .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+                sqlx::query("UPDATE compliance_reminders SET event_id = $2 WHERE reminder_id = $1")
+                    .bind(reminder_id)
+                    .bind(event.id)
+                    .execute(&mut *tx)
+                    .await?;
+                raised += 1;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(raised)
+    }
+}
@@ -0,0 +1,203 @@
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::{
+    property_address::PropertyAddress,
+    property_certificates::Certificate,
+    property_core::PropertyCore,
+    property_full::{LandlordSummary, PropertyFull, PropertySection},
+    property_photo::PropertyPhoto,
+    property_rental_info::PropertyRentalInfo,
+    property_services::PropertyService,
+    property_specifications::PropertySpecifications,
+};
+use crate::properties::infrastructure_layer::property_photos_repository::{
+    self, PropertyPhotoRepository,
+};
+use crate::user::domain_layer::user::StaffUserFullNames;
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+impl From<property_photos_repository::CustomErrors> for CustomErrors {
+    fn from(e: property_photos_repository::CustomErrors) -> Self {
+        match e {
+            property_photos_repository::CustomErrors::DatabaseError(e) => {
+                CustomErrors::DatabaseError(e)
+            }
+        }
+    }
+}
+
+pub struct PropertyFullRepository {}
+
+impl PropertyFullRepository {
+    pub fn new() -> Self {
+        PropertyFullRepository {}
+    }
+
+    /// A property with the requested sections: one query for the property,
+    /// then one per section, run concurrently. `None` when the property does
+    /// not exist, is deleted or is outside the caller's branches.
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        sections: &[PropertySection],
+    ) -> Result<Option<PropertyFull>, CustomErrors> {
+        let property = sqlx::query_as::<_, PropertyCore>(
+            "SELECT * FROM property_core WHERE property_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await?;
+        let property = match property {
+            Some(property) => property,
+            None => return Ok(None),
+        };
+        let wants = |section| sections.contains(&section);
+
+        let address = async {
+            if !wants(PropertySection::Address) {
+                return Ok(None);
+            }
+            sqlx::query_as::<_, PropertyAddress>(
+                "SELECT * FROM property_address WHERE property_id = $1",
+            )
+            .bind(property_id)
+            .fetch_optional(pool)
+            .await
+            .map(Some)
+            .map_err(CustomErrors::from)
+        };
+        let specifications = async {
+            if !wants(PropertySection::Specifications) {
+                return Ok(None);
+            }
+            sqlx::query_as::<_, PropertySpecifications>(
+                "SELECT * FROM property_specifications WHERE property_id = $1",
+            )
+            .bind(property_id)
+            .fetch_optional(pool)
+            .await
+            .map(Some)
+            .map_err(CustomErrors::from)
+        };
+        let rental_info = async {
+            if !wants(PropertySection::RentalInfo) {
+                return Ok(None);
+            }
+            sqlx::query_as::<_, PropertyRentalInfo>(
+                "SELECT * FROM property_rental_info WHERE property_id = $1",
+            )
+            .bind(property_id)
+            .fetch_optional(pool)
+            .await
+            .map(Some)
+            .map_err(CustomErrors::from)
+        };
+        let services = async {
+            if !wants(PropertySection::Services) {
+                return Ok(None);
+            }
+            sqlx::query_as::<_, PropertyService>(
+                "SELECT * FROM property_services WHERE property_id = $1 ORDER BY created_at",
+            )
+            .bind(property_id)
+            .fetch_all(pool)
+            .await
+            .map(Some)
+            .map_err(CustomErrors::from)
+        };
+        let certificates = async {
+            if !wants(PropertySection::Certificates) {
+                return Ok(None);
+            }
+            sqlx::query_as::<_, Certificate>(
+                "SELECT * FROM property_certificates WHERE property_id = $1 ORDER BY expiry_date NULLS LAST",
+            )
+            .bind(property_id)
+            .fetch_all(pool)
+            .await
+            .map(Some)
+            .map_err(CustomErrors::from)
+        };
+        let images = async {
+            if !wants(PropertySection::Images) {
+                return Ok(None);
+            }
+            let photos = PropertyPhotoRepository::new()
+                .get_ready(pool, property_id)
+                .await?;
+            Ok(Some(
+                photos.into_iter().map(PropertyPhoto::with_url).collect(),
+            ))
+        };
+        let landlord = async {
+            let landlord_id = match property.landlord_id {
+                Some(landlord_id) if wants(PropertySection::Landlord) => landlord_id,
+                _ => return Ok(wants(PropertySection::Landlord).then_some(None)),
+            };
+            sqlx::query_as::<_, LandlordSummary>(
+                "SELECT landlord_id, landlord_type, company_name, full_name, email, phone_nr, status
+                 FROM landlord_details
+                 WHERE landlord_id = $1 AND deleted_at IS NULL",
+            )
+            .bind(landlord_id)
+            .fetch_optional(pool)
+            .await
+            .map(Some)
+            .map_err(CustomErrors::from)
+        };
+        let staff = async {
+            let staff_id = match property.staff_assigned {
+                Some(staff_id) if wants(PropertySection::Staff) => staff_id,
+                _ => return Ok(wants(PropertySection::Staff).then_some(None)),
+            };
+            sqlx::query_as::<_, StaffUserFullNames>(
+                "SELECT user_id, COALESCE(name, username) AS name
+                 FROM staff_users
+                 WHERE user_id = $1 AND deleted_at IS NULL",
+            )
+            .bind(staff_id)
+            .fetch_optional(pool)
+            .await
+            .map(Some)
+            .map_err(CustomErrors::from)
+        };
+
+        let (address, specifications, rental_info, services, certificates, images, landlord, staff) =
+            tokio::try_join!(
+                address,
+                specifications,
+                rental_info,
+                services,
+                certificates,
+                images,
+                landlord,
+                staff
+            )?;
+        Ok(Some(PropertyFull {
+            property,
+            address,
+            specifications,
+            rental_info,
+            services,
+            certificates,
+            images,
+            landlord,
+            staff,
+        }))
+    }
+}
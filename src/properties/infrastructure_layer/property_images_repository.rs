@@ -1,22 +1,7 @@
 use crate::{properties::domain_layer::property_images::PropertyImages, AppState};
 use actix_web::web::Json;
-use actix_web::HttpResponse;
-use actix_web::{http::StatusCode, HttpRequest};
-use crossbeam_channel::bounded;
-use dotenv::dotenv;
-use futures_util::StreamExt;
-use futures_util::TryStreamExt as _;
-use image::codecs::avif;
-use image::codecs::png::FilterType;
-use image::DynamicImage;
-use mime::{self, Mime, IMAGE, IMAGE_BMP, IMAGE_JPEG, IMAGE_PNG, IMAGE_SVG};
 use serde_json::json;
-use std::fs;
-use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::Mutex;
-use tokio::fs as tfs;
-use tokio::io::AsyncWriteExt as _;
 use uuid::Uuid;
 
 pub struct PropertyImagesRepository {}
@@ -33,25 +18,25 @@ impl PropertyImagesRepository {
     ) -> Result<serde_json::Value, Json<String>> {
         let result = sqlx::query_as::<_, PropertyImages>(
             r#"
-            INSERT INTO property_photos (property_photos_id, property_id, photo_urls, image_descriptions, created_at, updated_at)
+            INSERT INTO property_images (image_list_id, property_id, image_urls, image_descriptions, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6)
             RETURNING *
             "#,
         )
-        .bind(&property_images.image_list_id)
-        .bind(&property_images.property_id)
+        .bind(property_images.image_list_id)
+        .bind(property_images.property_id)
         .bind(&property_images.image_urls)
         .bind(&property_images.image_descriptions)
-        .bind(&property_images.created_at)
-        .bind(&property_images.updated_at)
+        .bind(property_images.created_at)
+        .bind(property_images.updated_at)
         .fetch_one(&state.db)
         .await;
-    
+
         match result {
             Ok(property_images) => {
-                let json_with_message: serde_json::Value = json!({ 
-                    "message": "success", 
-                    "property_images": property_images 
+                let json_with_message: serde_json::Value = json!({
+                    "message": "success",
+                    "property_images": property_images
                 });
                 Ok(json_with_message)
             }
@@ -67,7 +52,7 @@ impl PropertyImagesRepository {
         let result = sqlx::query_as::<_, PropertyImages>(
             "SELECT * FROM property_images WHERE property_id = $1",
         )
-        .bind(&property_id)
+        .bind(property_id)
         .fetch_all(&state.db)
         .await;
 
@@ -76,132 +61,4 @@ impl PropertyImagesRepository {
             Err(e) => Err(Json(e.to_string())),
         }
     }
-
-    pub async fn upload_images(
-        &self,
-        state: Arc<AppState>,
-        property_id: Uuid,
-        payload: &mut actix_multipart::Multipart,
-        req: HttpRequest,
-    ) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, Json<serde_json::Value>)> {
-        let content_length: usize = match req.headers().get("content-length") {
-            Some(header_value) => header_value.to_str().unwrap_or("0").parse().unwrap(),
-            None => "0".parse().unwrap(),
-        };
-
-        let max_file_count: usize = 10;
-        let max_file_size: usize = 10_000_000;
-        let avif: mime::Mime = "image/avif".parse().unwrap();
-        let webp: mime::Mime = "image/webp".parse().unwrap();
-        let legal_filetypes: Vec<Mime> =
-            vec![IMAGE_PNG, IMAGE_JPEG, IMAGE_BMP, IMAGE_SVG, avif, webp];
-        let mut image_url_vector: Vec<String> = Vec::with_capacity(10 as usize);
-        let mut current_count: usize = 0;
-        let dir: &str = "./data/";
-
-        if !Path::new(dir).exists() {
-            fs::create_dir_all(dir).expect("Failed to create directory");
-        }
-
-        if content_length > max_file_size {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "error": "Payload Too Large",
-                    "message": "The payload is too large",
-                })),
-            ));
-        }
-
-        while let Ok(Some(mut field)) = payload.try_next().await {
-            if current_count >= max_file_count {
-                break;
-            }
-
-            let filetype: Option<&Mime> = field.content_type();
-            if filetype.is_none() {
-                continue;
-            }
-
-            if !legal_filetypes.contains(&filetype.unwrap()) {
-                continue;
-            }
-
-            let destination: String = format!(
-                "{}{}-{}",
-                dir,
-                Uuid::new_v4(),
-                field.content_disposition().unwrap().get_filename().unwrap()
-            );
-
-            let (snd, rcv) = bounded(10);
-            let img_clone = Arc::new(Mutex::new(
-                image::load_from_memory(&field.next().await.unwrap().unwrap()).unwrap(),
-            ));
-            let snd_clone = snd.clone();
-            let rcv_clone = rcv.clone();
-
-            tokio::spawn(async move {
-                let resized_img = img_clone.lock();
-                match resized_img {
-                    Ok(img) => {
-                        let img = img.clone();
-                        let img = img.resize(1920, 1080, image::imageops::FilterType::Triangle);
-                        snd_clone.send(img).unwrap();
-                    }
-                    Err(e) => {
-                        println!("Error: {:#?}", e);
-                    }
-                }
-            });
-
-            tokio::spawn(async move {
-                match rcv_clone.recv() {
-                    Ok(rcv_clone) => {
-                        let server_port =
-                            std::env::var("SERVER_PORT").expect("SERVER_PORT must be set");
-                        let server_host =
-                            std::env::var("SERVER_HOST").expect("SERVER_HOST must be set");
-                        let img_path = remove_extension(&Path::new(&destination))
-                            .to_string_lossy()
-                            .to_string()
-                            + ".avif";
-                        let img_path_cleaned = remove_dot_slash(&Path::new(&img_path))
-                            .to_string_lossy()
-                            .to_string();
-                        let full_image_path = format!(
-                            "http://{}:{}/{}",
-                            server_host, server_port, img_path_cleaned
-                        );
-                        match rcv_clone.save(&img_path) {
-                            Ok(_) => println!("Image saved successfully to {}", full_image_path),
-                            Err(e) => println!("Failed to save image: {:#?}", e),
-                        }
-                    }
-                    Err(e) => {
-                        println!("Error: {:#?}", e);
-                    }
-                }
-            });
-        }
-
-        Ok((
-            StatusCode::OK,
-            Json(json!({
-                "message": "success",
-                "address": "Images uploaded successfully",
-            })),
-        ))
-    }
-}
-
-fn remove_extension(path: &Path) -> PathBuf {
-    let mut path_buf = PathBuf::from(path);
-    path_buf.set_extension("");
-    path_buf
-}
-
-fn remove_dot_slash(path: &Path) -> PathBuf {
-    let path_str = path.to_string_lossy().to_string();
-    PathBuf::from(path_str.trim_start_matches("./"))
 }
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::property_keys_security::{
+    PropertyKeysSecurity, PropertyKeysSecurityRequest,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Key not found")]
+    NotFound,
+    #[display(fmt = "Key was modified by someone else")]
+    PreconditionFailed,
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+/// Keys held for a property.
+pub struct PropertyKeysRepository {}
+
+impl PropertyKeysRepository {
+    pub fn new() -> Self {
+        PropertyKeysRepository {}
+    }
+
+    pub async fn get_all(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyKeysSecurity>, CustomErrors> {
+        let rows = sqlx::query_as::<_, PropertyKeysSecurity>(
+            "SELECT * FROM property_keys_security WHERE property_id = $1 ORDER BY key_code",
+        )
+        .bind(property_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        key_id: Uuid,
+    ) -> Result<Option<PropertyKeysSecurity>, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyKeysSecurity>(
+            "SELECT * FROM property_keys_security WHERE property_id = $1 AND key_id = $2",
+        )
+        .bind(property_id)
+        .bind(key_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        request: PropertyKeysSecurityRequest,
+    ) -> Result<PropertyKeysSecurity, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyKeysSecurity>(
+            "INSERT INTO property_keys_security (key_id, property_id, key_code, security_code, notes, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(property_id)
+        .bind(request.key_code)
+        .bind(request.security_code)
+        .bind(request.notes)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Replaces the row only if it still has the `updated_at` the caller last
+    /// saw; `expected_updated_at = None` skips the check.
+    pub async fn update(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        key_id: Uuid,
+        request: PropertyKeysSecurityRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<PropertyKeysSecurity, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyKeysSecurity>(
+            "UPDATE property_keys_security
+             SET key_code = $3, security_code = $4, notes = $5, updated_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1 AND key_id = $2 AND ($6::timestamptz IS NULL OR updated_at = $6)
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(key_id)
+        .bind(request.key_code)
+        .bind(request.security_code)
+        .bind(request.notes)
+        .bind(expected_updated_at)
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(row),
+            None => match self.get(pool, property_id, key_id).await? {
+                Some(_) => Err(CustomErrors::PreconditionFailed),
+                None => Err(CustomErrors::NotFound),
+            },
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        key_id: Uuid,
+    ) -> Result<(), CustomErrors> {
+        let result = sqlx::query(
+            "DELETE FROM property_keys_security WHERE property_id = $1 AND key_id = $2",
+        )
+        .bind(property_id)
+        .bind(key_id)
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound);
+        }
+        Ok(())
+    }
+}
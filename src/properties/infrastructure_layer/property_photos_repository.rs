@@ -0,0 +1,280 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::property_photo::{PhotoVariant, PropertyPhoto};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+/// An upload saved to temporary storage, waiting to be recorded.
+pub struct NewPropertyPhoto {
+    pub photo_id: Uuid,
+    pub file_name: String,
+    pub size_bytes: i64,
+    pub temp_path: String,
+}
+
+pub struct PropertyPhotoRepository {}
+
+impl PropertyPhotoRepository {
+    pub fn new() -> Self {
+        PropertyPhotoRepository {}
+    }
+
+    /// Records the photos of one upload as pending.
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        upload_id: Uuid,
+        uploaded_by: Option<Uuid>,
+        photos: &[NewPropertyPhoto],
+    ) -> Result<Vec<PropertyPhoto>, CustomErrors> {
+        let mut tx = pool.begin().await?;
+        let mut created = Vec::with_capacity(photos.len());
+        for photo in photos {
+            let row = sqlx::query_as::<_, PropertyPhoto>(
+                "INSERT INTO property_photos
+                     (photo_id, property_id, upload_id, file_name, size_bytes, temp_path, uploaded_by, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, clock_timestamp())
+                 RETURNING *",
+            )
+            .bind(photo.photo_id)
+            .bind(property_id)
+            .bind(upload_id)
+            .bind(&photo.file_name)
+            .bind(photo.size_bytes)
+            .bind(&photo.temp_path)
+            .bind(uploaded_by)
+            .fetch_one(&mut *tx)
+            .await?;
+            created.push(row);
+        }
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    /// Photos of a property, oldest first.
+    pub async fn get_all(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyPhoto>, CustomErrors> {
+        let rows = sqlx::query_as::<_, PropertyPhoto>(
+            "SELECT * FROM property_photos WHERE property_id = $1 ORDER BY created_at, photo_id",
+        )
+        .bind(property_id)
+        .fetch_all(pool)
+        .await?;
+        self.with_variants(pool, rows).await
+    }
+
+    /// Processed photos of a property, oldest first.
+    pub async fn get_ready(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyPhoto>, CustomErrors> {
+        let rows = sqlx::query_as::<_, PropertyPhoto>(
+            "SELECT * FROM property_photos
+             WHERE property_id = $1 AND status = 'ready'
+             ORDER BY created_at, photo_id",
+        )
+        .bind(property_id)
+        .fetch_all(pool)
+        .await?;
+        self.with_variants(pool, rows).await
+    }
+
+    /// Photos of one upload, in the order they were sent.
+    pub async fn get_upload(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        upload_id: Uuid,
+    ) -> Result<Vec<PropertyPhoto>, CustomErrors> {
+        let rows = sqlx::query_as::<_, PropertyPhoto>(
+            "SELECT * FROM property_photos
+             WHERE property_id = $1 AND upload_id = $2
+             ORDER BY created_at, photo_id",
+        )
+        .bind(property_id)
+        .bind(upload_id)
+        .fetch_all(pool)
+        .await?;
+        self.with_variants(pool, rows).await
+    }
+
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        photo_id: Uuid,
+    ) -> Result<Option<PropertyPhoto>, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyPhoto>(
+            "SELECT * FROM property_photos WHERE property_id = $1 AND photo_id = $2",
+        )
+        .bind(property_id)
+        .bind(photo_id)
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(self.with_variants(pool, vec![row]).await?.pop()),
+            None => Ok(None),
+        }
+    }
+
+    /// Fills in the renditions of `photos`.
+    async fn with_variants(
+        &self,
+        pool: &Pool<Postgres>,
+        mut photos: Vec<PropertyPhoto>,
+    ) -> Result<Vec<PropertyPhoto>, CustomErrors> {
+        let photo_ids: Vec<Uuid> = photos.iter().map(|photo| photo.photo_id).collect();
+        if photo_ids.is_empty() {
+            return Ok(photos);
+        }
+        let variants = sqlx::query_as::<_, PhotoVariant>(
+            "SELECT * FROM property_photo_variants
+             WHERE photo_id = ANY($1)
+             ORDER BY width, format",
+        )
+        .bind(&photo_ids)
+        .fetch_all(pool)
+        .await?;
+        for variant in variants {
+            if let Some(photo) = photos
+                .iter_mut()
+                .find(|photo| photo.photo_id == variant.photo_id)
+            {
+                photo.variants.push(variant);
+            }
+        }
+        Ok(photos)
+    }
+
+    /// A photo still waiting to be processed.
+    pub async fn get_pending(
+        &self,
+        pool: &Pool<Postgres>,
+        photo_id: Uuid,
+    ) -> Result<Option<PropertyPhoto>, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyPhoto>(
+            "SELECT * FROM property_photos WHERE photo_id = $1 AND status = 'pending'",
+        )
+        .bind(photo_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Every photo waiting to be processed, oldest first.
+    pub async fn get_all_pending(
+        &self,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<PropertyPhoto>, CustomErrors> {
+        let rows = sqlx::query_as::<_, PropertyPhoto>(
+            "SELECT * FROM property_photos WHERE status = 'pending' ORDER BY created_at",
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Records the renditions of a photo and marks it ready. Returns false,
+    /// recording nothing, if the photo was no longer pending.
+    pub async fn mark_ready(
+        &self,
+        pool: &Pool<Postgres>,
+        photo_id: Uuid,
+        variants: &[PhotoVariant],
+    ) -> Result<bool, CustomErrors> {
+        let (width, height) = variants
+            .iter()
+            .max_by_key(|variant| variant.width)
+            .map(|variant| (variant.width, variant.height))
+            .unzip();
+        let mut tx = pool.begin().await?;
+        let updated = sqlx::query(
+            "UPDATE property_photos
+             SET status = 'ready', width = $2, height = $3,
+                 temp_path = NULL, error = NULL, processed_at = CURRENT_TIMESTAMP
+             WHERE photo_id = $1 AND status = 'pending'",
+        )
+        .bind(photo_id)
+        .bind(width)
+        .bind(height)
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+        for variant in variants {
+            sqlx::query(
+                "INSERT INTO property_photo_variants (photo_id, format, width, height, storage_key)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(photo_id)
+            .bind(variant.format)
+            .bind(variant.width)
+            .bind(variant.height)
+            .bind(&variant.storage_key)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn mark_failed(
+        &self,
+        pool: &Pool<Postgres>,
+        photo_id: Uuid,
+        error: &str,
+    ) -> Result<(), CustomErrors> {
+        sqlx::query(
+            "UPDATE property_photos
+             SET status = 'failed', error = $2, temp_path = NULL, processed_at = CURRENT_TIMESTAMP
+             WHERE photo_id = $1 AND status = 'pending'",
+        )
+        .bind(photo_id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Fails photos that have been pending since before `cutoff`, whose
+    /// uploads no instance is going to process any more. Returns how many.
+    pub async fn fail_stale(
+        &self,
+        pool: &Pool<Postgres>,
+        photo_ids: &[Uuid],
+        cutoff: DateTime<Utc>,
+        error: &str,
+    ) -> Result<u64, CustomErrors> {
+        let result = sqlx::query(
+            "UPDATE property_photos
+             SET status = 'failed', error = $3, temp_path = NULL, processed_at = CURRENT_TIMESTAMP
+             WHERE photo_id = ANY($1) AND status = 'pending' AND created_at < $2",
+        )
+        .bind(photo_ids)
+        .bind(cutoff)
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
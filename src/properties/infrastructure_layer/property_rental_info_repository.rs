@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::property_rental_info::{
+    PropertyRentalInfo, PropertyRentalInfoRequest,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Rental info not found")]
+    NotFound,
+    #[display(fmt = "Rental info was modified by someone else")]
+    PreconditionFailed,
+    #[display(fmt = "Property already has rental info")]
+    AlreadyExists,
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => CustomErrors::AlreadyExists,
+            _ => CustomErrors::DatabaseError(e.to_string()),
+        }
+    }
+}
+
+/// Rent and deposits of a property; at most one row per property.
+pub struct PropertyRentalInfoRepository {}
+
+impl PropertyRentalInfoRepository {
+    pub fn new() -> Self {
+        PropertyRentalInfoRepository {}
+    }
+
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Option<PropertyRentalInfo>, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyRentalInfo>(
+            "SELECT * FROM property_rental_info WHERE property_id = $1",
+        )
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        request: PropertyRentalInfoRequest,
+    ) -> Result<PropertyRentalInfo, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyRentalInfo>(
+            "INSERT INTO property_rental_info (rental_info_id, property_id, rent_amount, rent_poa, valuation_rent_min, valuation_rent_max, minimum_rent, deposit, holding_deposit, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(property_id)
+        .bind(request.rent_amount)
+        .bind(request.rent_poa)
+        .bind(request.valuation_rent_min)
+        .bind(request.valuation_rent_max)
+        .bind(request.minimum_rent)
+        .bind(request.deposit)
+        .bind(request.holding_deposit)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Replaces the row only if it still has the `updated_at` the caller last
+    /// saw; `expected_updated_at = None` skips the check.
+    pub async fn update(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        request: PropertyRentalInfoRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<PropertyRentalInfo, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyRentalInfo>(
+            "UPDATE property_rental_info
+             SET rent_amount = $2, rent_poa = $3, valuation_rent_min = $4, valuation_rent_max = $5, minimum_rent = $6, deposit = $7, holding_deposit = $8, updated_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1 AND ($9::timestamptz IS NULL OR updated_at = $9)
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(request.rent_amount)
+        .bind(request.rent_poa)
+        .bind(request.valuation_rent_min)
+        .bind(request.valuation_rent_max)
+        .bind(request.minimum_rent)
+        .bind(request.deposit)
+        .bind(request.holding_deposit)
+        .bind(expected_updated_at)
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(row),
+            None => match self.get(pool, property_id).await? {
+                Some(_) => Err(CustomErrors::PreconditionFailed),
+                None => Err(CustomErrors::NotFound),
+            },
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<(), CustomErrors> {
+        let result = sqlx::query("DELETE FROM property_rental_info WHERE property_id = $1")
+            .bind(property_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use sqlx::{Pool, Postgres, QueryBuilder};
+
+use crate::properties::domain_layer::property_search::{
+    PropertySearchParams, PropertySearchResponse, PropertySearchResult,
+};
+
+const FROM: &str = " FROM property_core p
+     LEFT JOIN property_address a ON a.property_id = p.property_id
+     LEFT JOIN property_specifications s ON s.property_id = p.property_id
+     LEFT JOIN property_rental_info r ON r.property_id = p.property_id
+     WHERE p.deleted_at IS NULL";
+
+pub struct PropertySearchRepository {}
+
+impl PropertySearchRepository {
+    pub fn new() -> Self {
+        PropertySearchRepository {}
+    }
+
+    /// One page of the properties matching `params`, and how many match in
+    /// total. Every value is bound; only whitelisted column names and sort
+    /// directions are written into the SQL.
+    pub async fn search(
+        &self,
+        pool: &Pool<Postgres>,
+        params: &PropertySearchParams,
+    ) -> Result<PropertySearchResponse, sqlx::Error> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT p.*, a.display_address, a.town_city, a.postcode,
+                    s.bedrooms, s.bathrooms, s.furnished_status, s.is_hmo, r.rent_amount",
+        );
+        query.push(FROM);
+        push_filters(&mut query, params);
+        let sort = params.sort.unwrap_or_default();
+        query
+            .push(" ORDER BY ")
+            .push(sort.column())
+            .push(" ")
+            .push(params.order().as_sql())
+            .push(" NULLS LAST, p.property_id LIMIT ")
+            .push_bind(params.per_page())
+            .push(" OFFSET ")
+            .push_bind((params.page() - 1) * params.per_page());
+        let results = query
+            .build_query_as::<PropertySearchResult>()
+            .fetch_all(pool)
+            .await?;
+
+        let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*)");
+        count.push(FROM);
+        push_filters(&mut count, params);
+        let total = count.build_query_scalar::<i64>().fetch_one(pool).await?;
+
+        Ok(PropertySearchResponse {
+            results,
+            page: params.page(),
+            per_page: params.per_page(),
+            total,
+        })
+    }
+}
+
+fn push_filters(query: &mut QueryBuilder<'_, Postgres>, params: &PropertySearchParams) {
+    if let Some(status) = &params.status {
+        query.push(" AND p.status = ").push_bind(status.clone());
+    }
+    if let Some(property_type) = &params.property_type {
+        query
+            .push(" AND p.property_type = ")
+            .push_bind(property_type.clone());
+    }
+    if let Some(letting_classification) = &params.letting_classification {
+        query
+            .push(" AND p.letting_classification = ")
+            .push_bind(letting_classification.clone());
+    }
+    if let Some(min_bedrooms) = params.min_bedrooms {
+        query.push(" AND s.bedrooms >= ").push_bind(min_bedrooms);
+    }
+    if let Some(max_bedrooms) = params.max_bedrooms {
+        query.push(" AND s.bedrooms <= ").push_bind(max_bedrooms);
+    }
+    if let Some(min_bathrooms) = params.min_bathrooms {
+        query.push(" AND s.bathrooms >= ").push_bind(min_bathrooms);
+    }
+    if let Some(max_bathrooms) = params.max_bathrooms {
+        query.push(" AND s.bathrooms <= ").push_bind(max_bathrooms);
+    }
+    if let Some(min_rent) = params.min_rent {
+        query.push(" AND r.rent_amount >= ").push_bind(min_rent);
+    }
+    if let Some(max_rent) = params.max_rent {
+        query.push(" AND r.rent_amount <= ").push_bind(max_rent);
+    }
+    if let Some(furnished_status) = &params.furnished_status {
+        query
+            .push(" AND s.furnished_status = ")
+            .push_bind(furnished_status.clone());
+    }
+    if let Some(is_hmo) = params.is_hmo {
+        query.push(" AND s.is_hmo = ").push_bind(is_hmo);
+    }
+    if let Some(available_before) = params.available_before {
+        query
+            .push(" AND p.date_available <= ")
+            .push_bind(available_before);
+    }
+    if let Some(available_after) = params.available_after {
+        query
+            .push(" AND p.date_available >= ")
+            .push_bind(available_after);
+    }
+    if let Some(town) = params.town.as_deref().map(str::trim) {
+        query
+            .push(" AND lower(a.town_city) = lower(")
+            .push_bind(town.to_string())
+            .push(")");
+    }
+    if let Some(district) = &params.postcode_district {
+        // The outward code is everything before the last three characters.
+        query
+            .push(" AND upper(left(replace(a.postcode, ' ', ''), -3)) = ")
+            .push_bind(district.replace(' ', "").to_uppercase());
+    }
+    if let Some(landlord_id) = params.landlord_id {
+        query.push(" AND p.landlord_id = ").push_bind(landlord_id);
+    }
+    if let Some(staff_id) = params.staff_id {
+        query.push(" AND p.staff_assigned = ").push_bind(staff_id);
+    }
+}
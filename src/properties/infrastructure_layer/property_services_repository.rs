@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::property_services::{PropertyService, PropertyServiceRequest};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Service not found")]
+    NotFound,
+    #[display(fmt = "Service was modified by someone else")]
+    PreconditionFailed,
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+/// Letting and management services taken for a property.
+pub struct PropertyServiceRepository {}
+
+impl PropertyServiceRepository {
+    pub fn new() -> Self {
+        PropertyServiceRepository {}
+    }
+
+    pub async fn get_all(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyService>, CustomErrors> {
+        let rows = sqlx::query_as::<_, PropertyService>(
+            "SELECT * FROM property_services WHERE property_id = $1 ORDER BY created_at",
+        )
+        .bind(property_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        service_id: Uuid,
+    ) -> Result<Option<PropertyService>, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyService>(
+            "SELECT * FROM property_services WHERE property_id = $1 AND service_id = $2",
+        )
+        .bind(property_id)
+        .bind(service_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        request: PropertyServiceRequest,
+    ) -> Result<PropertyService, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyService>(
+            "INSERT INTO property_services (service_id, property_id, service_type, letting_fee_percentage, letting_fee_amount, management_fee_percentage, management_fee_amount, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(property_id)
+        .bind(request.service_type)
+        .bind(request.letting_fee_percentage)
+        .bind(request.letting_fee_amount)
+        .bind(request.management_fee_percentage)
+        .bind(request.management_fee_amount)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Replaces the row only if it still has the `updated_at` the caller last
+    /// saw; `expected_updated_at = None` skips the check.
+    pub async fn update(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        service_id: Uuid,
+        request: PropertyServiceRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<PropertyService, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertyService>(
+            "UPDATE property_services
+             SET service_type = $3, letting_fee_percentage = $4, letting_fee_amount = $5, management_fee_percentage = $6, management_fee_amount = $7, updated_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1 AND service_id = $2 AND ($8::timestamptz IS NULL OR updated_at = $8)
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(service_id)
+        .bind(request.service_type)
+        .bind(request.letting_fee_percentage)
+        .bind(request.letting_fee_amount)
+        .bind(request.management_fee_percentage)
+        .bind(request.management_fee_amount)
+        .bind(expected_updated_at)
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(row),
+            None => match self.get(pool, property_id, service_id).await? {
+                Some(_) => Err(CustomErrors::PreconditionFailed),
+                None => Err(CustomErrors::NotFound),
+            },
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        service_id: Uuid,
+    ) -> Result<(), CustomErrors> {
+        let result =
+            sqlx::query("DELETE FROM property_services WHERE property_id = $1 AND service_id = $2")
+                .bind(property_id)
+                .bind(service_id)
+                .execute(pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound);
+        }
+        Ok(())
+    }
+}
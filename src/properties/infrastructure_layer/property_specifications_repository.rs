@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::properties::domain_layer::property_specifications::{
+    PropertySpecifications, PropertySpecificationsRequest,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Specifications not found")]
+    NotFound,
+    #[display(fmt = "Specifications was modified by someone else")]
+    PreconditionFailed,
+    #[display(fmt = "Property already has specifications")]
+    AlreadyExists,
+}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => CustomErrors::AlreadyExists,
+            _ => CustomErrors::DatabaseError(e.to_string()),
+        }
+    }
+}
+
+/// Specifications of a property; at most one row per property.
+pub struct PropertySpecificationsRepository {}
+
+impl PropertySpecificationsRepository {
+    pub fn new() -> Self {
+        PropertySpecificationsRepository {}
+    }
+
+    pub async fn get(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Option<PropertySpecifications>, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertySpecifications>(
+            "SELECT * FROM property_specifications WHERE property_id = $1",
+        )
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        request: PropertySpecificationsRequest,
+    ) -> Result<PropertySpecifications, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertySpecifications>(
+            "INSERT INTO property_specifications (spec_id, property_id, bedrooms, receptions, bathrooms, is_hmo, furnished_status, floor_area_size, floor_area_unit, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(property_id)
+        .bind(request.bedrooms)
+        .bind(request.receptions)
+        .bind(request.bathrooms)
+        .bind(request.is_hmo)
+        .bind(request.furnished_status)
+        .bind(request.floor_area_size)
+        .bind(request.floor_area_unit)
+        .fetch_one(pool)
+        .await?;
+        Ok(row)
+    }
+
+    /// Replaces the row only if it still has the `updated_at` the caller last
+    /// saw; `expected_updated_at = None` skips the check.
+    pub async fn update(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+        request: PropertySpecificationsRequest,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<PropertySpecifications, CustomErrors> {
+        let row = sqlx::query_as::<_, PropertySpecifications>(
+            "UPDATE property_specifications
+             SET bedrooms = $2, receptions = $3, bathrooms = $4, is_hmo = $5, furnished_status = $6, floor_area_size = $7, floor_area_unit = $8, updated_at = CURRENT_TIMESTAMP
+             WHERE property_id = $1 AND ($9::timestamptz IS NULL OR updated_at = $9)
+             RETURNING *",
+        )
+        .bind(property_id)
+        .bind(request.bedrooms)
+        .bind(request.receptions)
+        .bind(request.bathrooms)
+        .bind(request.is_hmo)
+        .bind(request.furnished_status)
+        .bind(request.floor_area_size)
+        .bind(request.floor_area_unit)
+        .bind(expected_updated_at)
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(row),
+            None => match self.get(pool, property_id).await? {
+                Some(_) => Err(CustomErrors::PreconditionFailed),
+                None => Err(CustomErrors::NotFound),
+            },
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<(), CustomErrors> {
+        let result = sqlx::query("DELETE FROM property_specifications WHERE property_id = $1")
+            .bind(property_id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
 pub mod properties_controller;
 pub mod property_address_controller;
+pub mod property_details_controller;
 pub mod property_images_controller;
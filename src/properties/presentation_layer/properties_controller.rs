@@ -1,10 +1,40 @@
-use actix_web::web;
-use crate::properties::application_layer::properties_service;
+use actix_web::{guard, web};
+use crate::properties::application_layer::{properties_service, property_compliance_service};
+use crate::properties::presentation_layer::{
+    property_address_controller::configure_address_routes,
+    property_details_controller::configure_detail_routes,
+};
+use crate::shared::application_layer::branch_service;
+use crate::shared::infrastructure_layer::idempotency_repo::Idempotency;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/properties")
             .route("", web::get().to(properties_service::get_all))
-            .route("", web::post().to(properties_service::add))
+            .route("/search", web::get().to(properties_service::search))
+            .route("/compliance", web::get().to(property_compliance_service::get_dashboard))
+            .service(
+                web::resource("")
+                    .guard(guard::Post())
+                    .wrap(Idempotency)
+                    .route(web::post().to(properties_service::add)),
+            )
+            .route("/{property_id}", web::get().to(properties_service::get_one))
+            .route("/{property_id}/full", web::get().to(properties_service::get_full))
+            .route("/{property_id}", web::put().to(properties_service::update))
+            .route("/{property_id}", web::patch().to(properties_service::patch))
+            .route("/{property_id}", web::delete().to(properties_service::delete))
+            .route("/{property_id}/restore", web::post().to(properties_service::restore))
+            .route("/{property_id}/transfer", web::post().to(branch_service::transfer_property))
+            .service(
+                web::resource("/{property_id}/status")
+                    .guard(guard::Post())
+                    .wrap(Idempotency)
+                    .route(web::post().to(properties_service::transition_status)),
+            )
+            .route("/{property_id}/status-history", web::get().to(properties_service::get_status_history))
+            .route("/{property_id}/compliance", web::get().to(property_compliance_service::get_compliance))
+            .configure(configure_address_routes)
+            .configure(configure_detail_routes)
     );
 }
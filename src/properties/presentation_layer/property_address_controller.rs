@@ -1,10 +1,23 @@
-use actix_web::web;
+use actix_web::{guard, web};
 
-use crate::properties::application_layer::properties_service;
+use crate::properties::application_layer::property_address_service;
+use crate::shared::infrastructure_layer::idempotency_repo::Idempotency;
 
+/// A property's address. Registered inside the `/api/v1/properties` scope,
+/// which would otherwise shadow these paths.
 pub fn configure_address_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api/v1/properties/address")
-            .route("", web::get().to(properties_service::get_all))
+    cfg.route(
+        "/{property_id}/address",
+        web::get().to(property_address_service::get_by_property_id),
+    )
+    .service(
+        web::resource("/{property_id}/address")
+            .guard(guard::Post())
+            .wrap(Idempotency)
+            .route(web::post().to(property_address_service::add)),
+    )
+    .route(
+        "/{property_id}/address",
+        web::put().to(property_address_service::update),
     );
 }
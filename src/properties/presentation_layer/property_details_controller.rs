@@ -0,0 +1,141 @@
+use actix_web::{guard, web};
+
+use crate::properties::application_layer::{
+    certificate_documents_service, property_certificates_service, property_keys_service,
+    property_rental_info_service, property_services_service, property_specifications_service,
+};
+use crate::shared::infrastructure_layer::idempotency_repo::Idempotency;
+
+/// Specifications, rental info, services, keys and certificates of a
+/// property. Registered inside the `/api/v1/properties` scope, like the
+/// address.
+pub fn configure_detail_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        "/{property_id}/specifications",
+        web::get().to(property_specifications_service::get_specifications),
+    )
+    .service(
+        web::resource("/{property_id}/specifications")
+            .guard(guard::Post())
+            .wrap(Idempotency)
+            .route(web::post().to(property_specifications_service::add_specifications)),
+    )
+    .route(
+        "/{property_id}/specifications",
+        web::put().to(property_specifications_service::update_specifications),
+    )
+    .route(
+        "/{property_id}/specifications",
+        web::delete().to(property_specifications_service::delete_specifications),
+    )
+    .route(
+        "/{property_id}/rental-info",
+        web::get().to(property_rental_info_service::get_rental_info),
+    )
+    .service(
+        web::resource("/{property_id}/rental-info")
+            .guard(guard::Post())
+            .wrap(Idempotency)
+            .route(web::post().to(property_rental_info_service::add_rental_info)),
+    )
+    .route(
+        "/{property_id}/rental-info",
+        web::put().to(property_rental_info_service::update_rental_info),
+    )
+    .route(
+        "/{property_id}/rental-info",
+        web::delete().to(property_rental_info_service::delete_rental_info),
+    )
+    .route(
+        "/{property_id}/services",
+        web::get().to(property_services_service::get_services),
+    )
+    .service(
+        web::resource("/{property_id}/services")
+            .guard(guard::Post())
+            .wrap(Idempotency)
+            .route(web::post().to(property_services_service::add_service)),
+    )
+    .route(
+        "/{property_id}/services/{service_id}",
+        web::get().to(property_services_service::get_service),
+    )
+    .route(
+        "/{property_id}/services/{service_id}",
+        web::put().to(property_services_service::update_service),
+    )
+    .route(
+        "/{property_id}/services/{service_id}",
+        web::delete().to(property_services_service::delete_service),
+    )
+    .route(
+        "/{property_id}/keys",
+        web::get().to(property_keys_service::get_keys),
+    )
+    .service(
+        web::resource("/{property_id}/keys")
+            .guard(guard::Post())
+            .wrap(Idempotency)
+            .route(web::post().to(property_keys_service::add_key)),
+    )
+    .route(
+        "/{property_id}/keys/{key_id}",
+        web::get().to(property_keys_service::get_key),
+    )
+    .route(
+        "/{property_id}/keys/{key_id}",
+        web::put().to(property_keys_service::update_key),
+    )
+    .route(
+        "/{property_id}/keys/{key_id}",
+        web::delete().to(property_keys_service::delete_key),
+    )
+    .route(
+        "/{property_id}/certificates",
+        web::get().to(property_certificates_service::get_certificates),
+    )
+    .service(
+        web::resource("/{property_id}/certificates")
+            .guard(guard::Post())
+            .wrap(Idempotency)
+            .route(web::post().to(property_certificates_service::add_certificate)),
+    )
+    .route(
+        "/{property_id}/certificates/{certificate_id}",
+        web::get().to(property_certificates_service::get_certificate),
+    )
+    .route(
+        "/{property_id}/certificates/{certificate_id}",
+        web::put().to(property_certificates_service::update_certificate),
+    )
+    .route(
+        "/{property_id}/certificates/{certificate_id}",
+        web::delete().to(property_certificates_service::delete_certificate),
+    )
+    .route(
+        "/{property_id}/certificates/{certificate_id}/documents",
+        web::get().to(certificate_documents_service::get_documents),
+    )
+    // Not behind Idempotency, which buffers request bodies too small for a
+    // document.
+    .route(
+        "/{property_id}/certificates/{certificate_id}/documents",
+        web::post().to(certificate_documents_service::upload_document),
+    )
+    .route(
+        "/{property_id}/certificates/{certificate_id}/documents/{document_id}",
+        web::get().to(certificate_documents_service::get_document),
+    )
+    .service(
+        web::resource(
+            "/{property_id}/certificates/{certificate_id}/documents/{document_id}/verify",
+        )
+        .guard(guard::Post())
+        .wrap(Idempotency)
+        .route(web::post().to(certificate_documents_service::verify_document)),
+    )
+    .route(
+        "/{property_id}/certificates/{certificate_id}/documents/{document_id}/download",
+        web::get().to(certificate_documents_service::download_document),
+    );
+}
@@ -3,8 +3,23 @@ use actix_web::web;
 use crate::properties::application_layer::property_photos_service;
 
 pub fn configure_photos_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("/api/v1/properties/photos").route(
-        "/{property_id}",
-        web::post().to(property_photos_service::upload_images),
-    ));
+    cfg.service(
+        web::scope("/api/v1/properties/photos")
+            .route(
+                "/{property_id}",
+                web::post().to(property_photos_service::upload_images),
+            )
+            .route(
+                "/{property_id}",
+                web::get().to(property_photos_service::get_photos),
+            )
+            .route(
+                "/{property_id}/uploads/{upload_id}",
+                web::get().to(property_photos_service::get_upload_status),
+            )
+            .route(
+                "/{property_id}/{photo_id}/file",
+                web::get().to(property_photos_service::get_photo_file),
+            ),
+    );
 }
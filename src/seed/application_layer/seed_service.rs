@@ -0,0 +1,20 @@
+use sqlx::{Pool, Postgres};
+
+use crate::seed::{
+    domain_layer::seed_dataset::SeedOptions,
+    infrastructure_layer::{
+        seed_generator::SeedGenerator,
+        seed_repository::{CustomErrors, SeedRepository, SeedSummary},
+    },
+};
+use crate::shared::infrastructure_layer::field_cipher::KeyRing;
+
+pub async fn run_seed(
+    pool: &Pool<Postgres>,
+    options: SeedOptions,
+    key_ring: &KeyRing,
+) -> Result<SeedSummary, CustomErrors> {
+    let dataset = SeedGenerator::new(options).generate();
+    let repo = SeedRepository::new();
+    repo.save(pool, dataset, key_ring).await
+}
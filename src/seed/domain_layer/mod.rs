@@ -0,0 +1 @@
+pub mod seed_dataset;
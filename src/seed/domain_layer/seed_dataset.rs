@@ -0,0 +1,47 @@
+use chrono::NaiveDate;
+
+use crate::{
+    diary::domain_layer::diary_event_types::{Event, EventDetails},
+    landlord::domain_layer::{
+        landlord_details::LandlordDetails, landlords_address::LandlordsAddress,
+        landlords_bank_details::LandlordsBankDetails,
+        landlords_lettings_management::LandlordsLettingsManagement,
+    },
+    properties::domain_layer::{property_address::PropertyAddress, property_core::PropertyCore},
+    user::domain_layer::user::StaffUser,
+};
+
+#[derive(Clone, Debug)]
+pub struct SeedOptions {
+    pub seed: u64,
+    pub start_date: NaiveDate,
+    pub staff_password: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct SeedLandlord {
+    pub details: LandlordDetails,
+    pub address: LandlordsAddress,
+    pub bank_details: LandlordsBankDetails,
+    pub lettings: LandlordsLettingsManagement,
+}
+
+#[derive(Clone, Debug)]
+pub struct SeedProperty {
+    pub core: PropertyCore,
+    pub address: PropertyAddress,
+}
+
+#[derive(Clone, Debug)]
+pub struct SeedEvent {
+    pub event: Event,
+    pub details: EventDetails,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SeedDataset {
+    pub staff: Vec<StaffUser>,
+    pub landlords: Vec<SeedLandlord>,
+    pub properties: Vec<SeedProperty>,
+    pub events: Vec<SeedEvent>,
+}
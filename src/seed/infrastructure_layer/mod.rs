@@ -0,0 +1,2 @@
+pub mod seed_generator;
+pub mod seed_repository;
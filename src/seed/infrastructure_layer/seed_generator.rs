@@ -0,0 +1,639 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, Utc, Weekday};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+use crate::{
+    diary::domain_layer::diary_event_types::{
+        AppointmentDetails, CallbackDetails, Event, EventDetails, EventType, InspectionDetails,
+        LeaveDetails, MaintenanceDetails, MeetingDetails, NoteDetails, PublicHolidayDetails,
+        StaffHolidayDetails, TrainingDetails, ValuationDetails, ViewingDetails,
+    },
+    landlord::domain_layer::{
+        landlord_details::{LandlordDetails, LandlordStatus, LandlordTitle, LandlordTypeEnum},
+        landlords_address::LandlordsAddress,
+        landlords_bank_details::LandlordsBankDetails,
+        landlords_lettings_management::{LandlordPaymentFrequency, LandlordsLettingsManagement},
+    },
+    properties::domain_layer::{
+        property_address::PropertyAddress,
+        property_core::{LettingClassification, PropertyCore, PropertyStatus, PropertyType},
+    },
+    seed::domain_layer::seed_dataset::{
+        SeedDataset, SeedEvent, SeedLandlord, SeedOptions, SeedProperty,
+    },
+    user::domain_layer::user::{StaffUser, UserLevel, UserStatus},
+};
+
+const FIRST_NAMES: &[&str] = &[
+    "Oliver", "Amelia", "George", "Isla", "Harry", "Ava", "Noah", "Mia", "Jack", "Ivy", "Leo",
+    "Grace", "Arthur", "Freya", "Oscar", "Lily", "Charlie", "Sophia", "Theo", "Evie", "Alfie",
+    "Poppy", "Henry", "Ella", "Freddie", "Rosie",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Smith", "Jones", "Taylor", "Brown", "Williams", "Wilson", "Johnson", "Davies", "Patel",
+    "Robinson", "Wright", "Thompson", "Evans", "Walker", "White", "Roberts", "Green", "Hall",
+    "Wood", "Jackson", "Clarke", "Khan", "Lewis", "Hughes",
+];
+
+const COMPANY_SUFFIXES: &[&str] = &[
+    "Estates Ltd",
+    "Property Holdings Ltd",
+    "Lettings LLP",
+    "Homes Ltd",
+    "Investments Ltd",
+];
+
+const STREETS: &[&str] = &[
+    "Mill Lane",
+    "High Street",
+    "Station Road",
+    "Church Street",
+    "Victoria Road",
+    "Park Avenue",
+    "Queen Street",
+    "Kingsway",
+    "Manor Road",
+    "The Crescent",
+    "Orchard Close",
+    "Meadow View",
+];
+
+/// (town, county, outward postcode)
+const TOWNS: &[(&str, &str, &str)] = &[
+    ("Bristol", "Avon", "BS6"),
+    ("Bath", "Somerset", "BA1"),
+    ("Reading", "Berkshire", "RG1"),
+    ("Oxford", "Oxfordshire", "OX4"),
+    ("Cambridge", "Cambridgeshire", "CB1"),
+    ("Leeds", "West Yorkshire", "LS6"),
+    ("Manchester", "Greater Manchester", "M20"),
+    ("Brighton", "East Sussex", "BN2"),
+];
+
+const BANKS: &[&str] = &["NWBK", "BARC", "LOYD", "HBUK", "MIDL"];
+
+const USER_LEVELS: &[(UserLevel, usize)] = &[
+    (UserLevel::Admin, 1),
+    (UserLevel::Manager, 2),
+    (UserLevel::Staff, 5),
+    (UserLevel::Trainee, 2),
+];
+
+const PROPERTY_TYPES: &[PropertyType] = &[
+    PropertyType::House,
+    PropertyType::Flat,
+    PropertyType::Apartment,
+    PropertyType::Bungalow,
+    PropertyType::Maisonette,
+    PropertyType::Studio,
+    PropertyType::Cottage,
+    PropertyType::Terraced,
+    PropertyType::SemiDetached,
+    PropertyType::Detached,
+];
+
+const LETTING_CLASSIFICATIONS: &[LettingClassification] = &[
+    LettingClassification::Residential,
+    LettingClassification::Commercial,
+    LettingClassification::Student,
+    LettingClassification::ShortTerm,
+    LettingClassification::Holiday,
+    LettingClassification::Hmo,
+];
+
+const PROPERTY_STATUSES: &[PropertyStatus] = &[
+    PropertyStatus::Available,
+    PropertyStatus::LetAgreed,
+    PropertyStatus::Let,
+    PropertyStatus::Withdrawn,
+    PropertyStatus::Unavailable,
+    PropertyStatus::Maintenance,
+];
+
+const PAYMENT_FREQUENCIES: &[LandlordPaymentFrequency] = &[
+    LandlordPaymentFrequency::Weekly,
+    LandlordPaymentFrequency::Monthly,
+    LandlordPaymentFrequency::Quarterly,
+    LandlordPaymentFrequency::SemiAnnually,
+    LandlordPaymentFrequency::Annually,
+    LandlordPaymentFrequency::Termly,
+    LandlordPaymentFrequency::TwoWeekly,
+    LandlordPaymentFrequency::FourWeekly,
+];
+
+const LANDLORD_TITLES: &[LandlordTitle] = &[
+    LandlordTitle::Mr,
+    LandlordTitle::Mrs,
+    LandlordTitle::Miss,
+    LandlordTitle::Ms,
+    LandlordTitle::Dr,
+    LandlordTitle::Prof,
+];
+
+/// One entry per `EventDetails` variant, so every kind of diary event is represented.
+const EVENT_KINDS: &[EventType] = &[
+    EventType::Viewing,
+    EventType::Appointment,
+    EventType::Inspection,
+    EventType::SickLeave,
+    EventType::StaffMeeting,
+    EventType::Valuation,
+    EventType::Callback,
+    EventType::Maintenance,
+    EventType::StaffHoliday,
+    EventType::Training,
+    EventType::PublicHoliday,
+    EventType::Note,
+];
+
+const LANDLORD_COUNT: usize = 12;
+const PROPERTY_COUNT: usize = 30;
+const EVENTS_PER_WORKING_DAY: usize = 3;
+
+/// Builds a coherent dataset from a seed. The same seed and start date always
+/// produce the same rows, including identifiers.
+pub struct SeedGenerator {
+    rng: ChaCha8Rng,
+    options: SeedOptions,
+}
+
+impl SeedGenerator {
+    pub fn new(options: SeedOptions) -> Self {
+        SeedGenerator {
+            rng: ChaCha8Rng::seed_from_u64(options.seed),
+            options,
+        }
+    }
+
+    pub fn generate(mut self) -> SeedDataset {
+        let staff = self.generate_staff();
+        let landlords = self.generate_landlords(&staff);
+        let properties = self.generate_properties(&staff, &landlords);
+        let events = self.generate_events(&staff, &properties);
+
+        SeedDataset {
+            staff,
+            landlords,
+            properties,
+            events,
+        }
+    }
+
+    fn generate_staff(&mut self) -> Vec<StaffUser> {
+        let mut staff = Vec::new();
+        for (level, count) in USER_LEVELS {
+            for _ in 0..*count {
+                let first = self.pick(FIRST_NAMES);
+                let last = self.pick(LAST_NAMES);
+                let index = staff.len();
+                let username = format!("{}.{}{}", first, last, index).to_lowercase();
+                staff.push(StaffUser {
+                    user_id: Some(self.uuid()),
+                    name: Some(format!("{} {}", first, last)),
+                    email: Some(format!("{}@example.com", username)),
+                    username,
+                    mob_phone: Some(self.mobile_number()),
+                    passwd: self.options.staff_password.clone(),
+                    acc_level: Some(level.clone()),
+                    // Keep one trainee suspended so both statuses show up in the UI.
+                    status: Some(match level {
+                        UserLevel::Trainee if index == staff_total() - 1 => UserStatus::Suspended,
+                        _ => UserStatus::Active,
+                    }),
+                    a_created: Some(self.timestamp_before_start(365).naive_utc()),
+                    updated_at: None,
+                    branch_id: None,
+                });
+            }
+        }
+        staff
+    }
+
+    fn generate_landlords(&mut self, staff: &[StaffUser]) -> Vec<SeedLandlord> {
+        (0..LANDLORD_COUNT)
+            .map(|index| {
+                let landlord_id = self.uuid();
+                let landlord_type = if index % 2 == 0 {
+                    LandlordTypeEnum::Private
+                } else {
+                    LandlordTypeEnum::Company
+                };
+                let first = self.pick(FIRST_NAMES);
+                let last = self.pick(LAST_NAMES);
+                let full_name = format!("{} {}", first, last);
+                let (title, company_name) = match landlord_type {
+                    LandlordTypeEnum::Private => (Some(self.pick(LANDLORD_TITLES).clone()), None),
+                    LandlordTypeEnum::Company => {
+                        (None, Some(format!("{} {}", last, self.pick(COMPANY_SUFFIXES))))
+                    }
+                };
+                let created_at = self.timestamp_before_start(180);
+                let is_private = matches!(landlord_type, LandlordTypeEnum::Private);
+
+                let details = LandlordDetails {
+                    landlord_id: Some(landlord_id),
+                    landlord_type,
+                    title,
+                    company_name,
+                    full_name: Some(full_name.clone()),
+                    email: Some(format!("{}.{}{}@example.com", first, last, index).to_lowercase()),
+                    phone_nr: self.mobile_number(),
+                    status: if index % 5 == 4 {
+                        LandlordStatus::Inactive
+                    } else {
+                        LandlordStatus::Active
+                    },
+                    staff_assigned: self.pick(staff).user_id,
+                    created_at: Some(created_at),
+                    updated_at: Some(created_at),
+                    branch_id: None,
+                };
+
+                let (town, county, outward) = *self.pick(TOWNS);
+                let address = LandlordsAddress {
+                    id: self.uuid(),
+                    landlord_id,
+                    address_line_1: format!("{} {}", self.rng.gen_range(1..120), self.pick(STREETS)),
+                    address_line_2: None,
+                    city: town.to_string(),
+                    county: Some(county.to_string()),
+                    postcode: self.postcode(outward),
+                    country: "United Kingdom".to_string(),
+                    is_primary: true,
+                };
+
+                let sort_code = format!(
+                    "{:02}{:02}{:02}",
+                    self.rng.gen_range(10..99),
+                    self.rng.gen_range(0..99),
+                    self.rng.gen_range(0..99)
+                );
+                let account_number = format!("{:08}", self.rng.gen_range(0..100_000_000u32));
+                let bank = self.pick(BANKS);
+                let bank_details = LandlordsBankDetails {
+                    id: self.uuid(),
+                    landlord_id,
+                    account_name: details
+                        .company_name
+                        .clone()
+                        .unwrap_or_else(|| full_name.clone()),
+                    iban: Some(uk_iban(bank, &sort_code, &account_number)),
+                    bic: Some(format!("{}GB2L", bank)),
+                    account_number,
+                    sort_code: format!("{}-{}-{}", &sort_code[0..2], &sort_code[2..4], &sort_code[4..6]),
+                    is_primary: true,
+                };
+
+                let lettings = LandlordsLettingsManagement {
+                    id: self.uuid(),
+                    landlord_id,
+                    payment_frequency: self.pick(PAYMENT_FREQUENCIES).clone(),
+                    is_exempt_from_nrl_tax: false,
+                    nrl_exemption_reference: None,
+                    is_exempt_from_vat: is_private,
+                    vat_number: (!is_private)
+                        .then(|| format!("GB{:09}", self.rng.gen_range(0..1_000_000_000u32))),
+                    ni_number: is_private.then(|| self.ni_number()),
+                    unique_taxpayer_reference: Some(format!(
+                        "{:010}",
+                        self.rng.gen_range(0..10_000_000_000u64)
+                    )),
+                    statement_template_override: None,
+                    statement_email_subject_override: None,
+                    statement_payment_ref: Some(format!("LL{:05}", index + 1)),
+                    accountant_email: (!is_private)
+                        .then(|| format!("accounts{}@example.com", index)),
+                };
+
+                SeedLandlord {
+                    details,
+                    address,
+                    bank_details,
+                    lettings,
+                }
+            })
+            .collect()
+    }
+
+    fn generate_properties(
+        &mut self,
+        staff: &[StaffUser],
+        landlords: &[SeedLandlord],
+    ) -> Vec<SeedProperty> {
+        (0..PROPERTY_COUNT)
+            .map(|index| {
+                let property_id = self.uuid();
+                let created_at = self.timestamp_before_start(120);
+                let status = self.pick(PROPERTY_STATUSES).clone();
+                // Tenancies on let properties are already under way and end
+                // between a month and a year after the start date.
+                let tenancy_end_date = (status == PropertyStatus::Let)
+                    .then(|| self.options.start_date + Duration::days(self.rng.gen_range(30..365)));
+                let core = PropertyCore {
+                    property_id: Some(property_id),
+                    status,
+                    property_type: PROPERTY_TYPES[index % PROPERTY_TYPES.len()].clone(),
+                    letting_classification: LETTING_CLASSIFICATIONS
+                        [index % LETTING_CLASSIFICATIONS.len()]
+                    .clone(),
+                    staff_assigned: self.pick(staff).user_id,
+                    landlord_id: self.pick(landlords).details.landlord_id,
+                    date_available: Some(
+                        self.options.start_date + Duration::days(self.rng.gen_range(0..60)),
+                    ),
+                    created_at,
+                    updated_at: created_at,
+                    branch_id: None,
+                    tenancy_end_date,
+                };
+
+                let (town, county, outward) = *self.pick(TOWNS);
+                let address_line1 = format!("{} {}", self.rng.gen_range(1..200), self.pick(STREETS));
+                let address_line2 = matches!(
+                    core.property_type,
+                    PropertyType::Flat | PropertyType::Apartment | PropertyType::Studio
+                )
+                .then(|| format!("Flat {}", self.rng.gen_range(1..30)));
+                let display_address = match &address_line2 {
+                    Some(line2) => format!("{}, {}, {}", line2, address_line1, town),
+                    None => format!("{}, {}", address_line1, town),
+                };
+                let address = PropertyAddress {
+                    address_id: self.uuid(),
+                    property_id,
+                    display_address: Some(display_address),
+                    address_line1,
+                    address_line2,
+                    town_city: town.to_string(),
+                    county: Some(county.to_string()),
+                    postcode: self.postcode(outward),
+                    country: "United Kingdom".to_string(),
+                    searchable_area: Some(format!("{} {}", town, outward)),
+                    created_at,
+                    updated_at: created_at,
+                };
+
+                SeedProperty { core, address }
+            })
+            .collect()
+    }
+
+    fn generate_events(&mut self, staff: &[StaffUser], properties: &[SeedProperty]) -> Vec<SeedEvent> {
+        let start = self.options.start_date;
+        let end = start
+            .checked_add_months(Months::new(1))
+            .unwrap_or(start + Duration::days(30));
+        let mut events = Vec::new();
+        let mut date = start;
+
+        while date < end {
+            if !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                for slot in 0..EVENTS_PER_WORKING_DAY {
+                    let kind = EVENT_KINDS[events.len() % EVENT_KINDS.len()].clone();
+                    events.push(self.event(events.len(), kind, date, slot, staff, properties));
+                }
+            }
+            date += Duration::days(1);
+        }
+        events
+    }
+
+    fn event(
+        &mut self,
+        index: usize,
+        kind: EventType,
+        date: NaiveDate,
+        slot: usize,
+        staff: &[StaffUser],
+        properties: &[SeedProperty],
+    ) -> SeedEvent {
+        let organiser = self.pick(staff).user_id.unwrap_or_default();
+        let colleague = self.pick(staff).user_id.unwrap_or_default();
+        let property = self.pick(properties);
+        let property_id = property.core.property_id.unwrap_or_default().to_string();
+        let property_address = property.address.display_address.clone().unwrap_or_default();
+        let client_name = format!("{} {}", self.pick(FIRST_NAMES), self.pick(LAST_NAMES));
+        let contact_number = self.mobile_number();
+
+        let (title, details) = match kind {
+            EventType::Viewing => (
+                format!("Viewing at {}", property_address),
+                EventDetails::Viewing(ViewingDetails {
+                    property_id,
+                    client_name,
+                    contact_number,
+                    viewing_type: self.pick(&["accompanied", "virtual", "open house"]).to_string(),
+                    notification_length: Some("30m".to_string()),
+                    client_email: None,
+                }),
+            ),
+            EventType::Appointment => (
+                format!("Landlord meeting about {}", property_address),
+                EventDetails::Appointment(AppointmentDetails {
+                    location: Some("Office".to_string()),
+                    property_id: Some(property_id),
+                    is_private: Some(false),
+                    notification: Some(true),
+                    is_recurring: Some(false),
+                    recurrence_pattern: None,
+                }),
+            ),
+            EventType::Inspection => (
+                format!("Mid-term inspection at {}", property_address),
+                EventDetails::Inspection(InspectionDetails {
+                    property_id,
+                    contractor: "In-house".to_string(),
+                    notification: Some(true),
+                }),
+            ),
+            EventType::SickLeave => (
+                "Sick leave".to_string(),
+                EventDetails::SickLeave(LeaveDetails {
+                    staff_member: colleague.to_string(),
+                    is_half_day: Some(self.rng.gen_bool(0.3)),
+                }),
+            ),
+            EventType::StaffMeeting => (
+                "Weekly lettings meeting".to_string(),
+                EventDetails::StaffMeeting(MeetingDetails {
+                    location: Some("Meeting room 1".to_string()),
+                    is_recurring: Some(true),
+                    recurrence_pattern: Some("weekly".to_string()),
+                }),
+            ),
+            EventType::Valuation => (
+                format!("Rental valuation at {}", property_address),
+                EventDetails::Valuation(ValuationDetails {
+                    property_id,
+                    client_name,
+                    contact_number,
+                    notification: Some(true),
+                    client_email: None,
+                }),
+            ),
+            EventType::Callback => (
+                format!("Call back {}", client_name),
+                EventDetails::Callback(CallbackDetails {
+                    contact_name: client_name,
+                    phone_number: contact_number,
+                    is_urgent: Some(self.rng.gen_bool(0.2)),
+                }),
+            ),
+            EventType::Maintenance => (
+                format!("Boiler repair at {}", property_address),
+                EventDetails::Maintenance(MaintenanceDetails {
+                    property_id,
+                    contractor: self
+                        .pick(&["A1 Plumbing", "Bright Sparks Electrical", "FixIt Handyman"])
+                        .to_string(),
+                    notification: Some(true),
+                }),
+            ),
+            EventType::StaffHoliday => (
+                "Annual leave".to_string(),
+                EventDetails::StaffHoliday(StaffHolidayDetails {
+                    staff_member: colleague,
+                    holiday_type: Some("annual".to_string()),
+                    is_half_day: Some(false),
+                    approval_status: Some("approved".to_string()),
+                    approved_by: Some(organiser),
+                    approval_date: time::OffsetDateTime::from_unix_timestamp(
+                        self.timestamp_before_start(14).timestamp(),
+                    )
+                    .ok(),
+                    remaining_days: Some(f64::from(self.rng.gen_range(2..25))),
+                }),
+            ),
+            EventType::Training => (
+                "Tenancy deposit compliance training".to_string(),
+                EventDetails::Training(TrainingDetails {
+                    training_title: "Tenancy deposit compliance".to_string(),
+                    location: Some("Training room".to_string()),
+                    lead_staff: Some(organiser),
+                    attendees: Some(vec![colleague]),
+                    additional_attendees: None,
+                    training_type: Some("internal".to_string()),
+                    training_status: Some("scheduled".to_string()),
+                    materials_url: None,
+                    prerequisites: None,
+                    attendance_confirmed: Some(false),
+                    certificates_issued: Some(false),
+                }),
+            ),
+            EventType::PublicHoliday => (
+                "Bank holiday".to_string(),
+                EventDetails::PublicHoliday(PublicHolidayDetails {
+                    holiday_name: "Bank holiday".to_string(),
+                    region: Some("England and Wales".to_string()),
+                    affects_all_staff: Some(true),
+                    affected_departments: None,
+                    is_bank_holiday: Some(true),
+                    office_status: Some("closed".to_string()),
+                    custom_working_hours: None,
+                }),
+            ),
+            EventType::Note => (
+                format!("Chase gas safety certificate for {}", property_address),
+                EventDetails::Note(NoteDetails {
+                    note_type: "reminder".to_string(),
+                    assigned_staff: Some(vec![organiser]),
+                    is_private: Some(false),
+                    category: Some("compliance".to_string()),
+                    priority: Some(self.pick(&["low", "medium", "high"]).to_string()),
+                    related_entity_type: Some("property".to_string()),
+                    related_entity_id: Some(property_id),
+                    status: Some("open".to_string()),
+                    completion_date: None,
+                    completed_by: None,
+                }),
+            ),
+        };
+
+        let start_time = NaiveTime::from_hms_opt(9 + 2 * slot as u32, 0, 0).unwrap_or_default();
+        let created_at = self.timestamp_before_start(7);
+        SeedEvent {
+            event: Event {
+                id: Some(self.uuid()),
+                external_id: format!("seed-{}-{}", self.options.seed, index),
+                event_type: kind,
+                date,
+                start_time,
+                end_time: start_time + Duration::hours(1),
+                title: Some(title),
+                description: Some("Generated by the development seeder".to_string()),
+                created_by: organiser,
+                created_at: Some(created_at),
+                updated_at: Some(created_at),
+                branch_id: None,
+            },
+            details,
+        }
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        items
+            .choose(&mut self.rng)
+            .expect("seed pools are never empty")
+    }
+
+    fn uuid(&mut self) -> Uuid {
+        uuid::Builder::from_random_bytes(self.rng.gen()).into_uuid()
+    }
+
+    fn mobile_number(&mut self) -> String {
+        format!("07{:09}", self.rng.gen_range(0..1_000_000_000u32))
+    }
+
+    fn postcode(&mut self, outward: &str) -> String {
+        const LETTERS: &[u8] = b"ABDEFGHJLNPQRSTUWXYZ";
+        format!(
+            "{} {}{}{}",
+            outward,
+            self.rng.gen_range(1..10),
+            *self.pick(LETTERS) as char,
+            *self.pick(LETTERS) as char
+        )
+    }
+
+    fn ni_number(&mut self) -> String {
+        const PREFIX: &[u8] = b"ABCEHJKLMNOPRSTWXYZ";
+        format!(
+            "{}{}{:06}{}",
+            *self.pick(PREFIX) as char,
+            *self.pick(PREFIX) as char,
+            self.rng.gen_range(0..1_000_000),
+            *self.pick(b"ABCD") as char
+        )
+    }
+
+    fn timestamp_before_start(&mut self, max_days: i64) -> DateTime<Utc> {
+        let start = self
+            .options
+            .start_date
+            .and_hms_opt(9, 0, 0)
+            .unwrap_or_default()
+            .and_utc();
+        start - Duration::minutes(self.rng.gen_range(0..max_days * 24 * 60))
+    }
+}
+
+fn staff_total() -> usize {
+    USER_LEVELS.iter().map(|(_, count)| count).sum()
+}
+
+/// Builds a UK IBAN with valid mod-97 check digits.
+fn uk_iban(bank: &str, sort_code: &str, account_number: &str) -> String {
+    let bban = format!("{}{}{}", bank, sort_code, account_number);
+    let rearranged = format!("{}GB00", bban);
+    let remainder = rearranged.chars().fold(0u32, |acc, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 {
+            (acc * 100 + value) % 97
+        } else {
+            (acc * 10 + value) % 97
+        }
+    });
+    format!("GB{:02}{}", 98 - remainder, bban)
+}
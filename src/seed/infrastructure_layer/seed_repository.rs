@@ -0,0 +1,297 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, SaltString},
+    Argon2, PasswordHasher,
+};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres, Transaction};
+
+use crate::{
+    diary::infrastructure_layer::diary_event_repo::EventRepository,
+    seed::domain_layer::seed_dataset::{SeedDataset, SeedEvent, SeedLandlord, SeedProperty},
+    shared::infrastructure_layer::field_cipher::{CipherError, KeyRing},
+    user::domain_layer::user::StaffUser,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Password hashing error: {}", _0)]
+    HashingError(String),
+    #[display(fmt = "Encryption error: {}", _0)]
+    Encryption(String),
+}
+
+impl From<CipherError> for CustomErrors {
+    fn from(e: CipherError) -> Self {
+        CustomErrors::Encryption(e.to_string())
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SeedSummary {
+    pub staff: u64,
+    pub landlords: u64,
+    pub properties: u64,
+    pub events: u64,
+}
+
+pub struct SeedRepository {}
+
+impl SeedRepository {
+    pub fn new() -> Self {
+        SeedRepository {}
+    }
+
+    /// Writes the dataset in a single transaction. Rows that already exist are
+    /// left untouched, so running the seeder twice with the same seed is a no-op.
+    pub async fn save(
+        &self,
+        pool: &Pool<Postgres>,
+        dataset: SeedDataset,
+        key_ring: &KeyRing,
+    ) -> Result<SeedSummary, CustomErrors> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        let mut summary = SeedSummary::default();
+
+        for user in dataset.staff {
+            summary.staff += Self::save_staff(&mut tx, user).await?;
+        }
+        for landlord in dataset.landlords {
+            summary.landlords += Self::save_landlord(&mut tx, landlord, key_ring).await?;
+        }
+        for property in dataset.properties {
+            summary.properties += Self::save_property(&mut tx, property).await?;
+        }
+        for event in dataset.events {
+            summary.events += Self::save_event(&mut tx, event).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(summary)
+    }
+
+    async fn save_staff(
+        tx: &mut Transaction<'_, Postgres>,
+        user: StaffUser,
+    ) -> Result<u64, CustomErrors> {
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(user.passwd.as_bytes(), &salt)
+            .map_err(|e| CustomErrors::HashingError(e.to_string()))?;
+
+        let result = sqlx::query(
+            "INSERT INTO staff_users (user_id, name, username, mob_phone, email, passwd, acc_level, status, a_created)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(user.user_id)
+        .bind(user.name)
+        .bind(user.username)
+        .bind(user.mob_phone)
+        .bind(user.email)
+        .bind(password_hash.to_string())
+        .bind(user.acc_level)
+        .bind(user.status)
+        .bind(user.a_created)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    async fn save_landlord(
+        tx: &mut Transaction<'_, Postgres>,
+        landlord: SeedLandlord,
+        key_ring: &KeyRing,
+    ) -> Result<u64, CustomErrors> {
+        let details = landlord.details;
+        let result = sqlx::query(
+            "INSERT INTO landlord_details (landlord_id, landlord_type, title, company_name, full_name, email, phone_nr, status, staff_assigned, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(details.landlord_id)
+        .bind(details.landlord_type)
+        .bind(details.title)
+        .bind(details.company_name)
+        .bind(details.full_name)
+        .bind(details.email)
+        .bind(details.phone_nr)
+        .bind(details.status)
+        .bind(details.staff_assigned)
+        .bind(details.created_at)
+        .bind(details.updated_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Ok(0);
+        }
+
+        let address = landlord.address;
+        sqlx::query(
+            "INSERT INTO landlords_address (id, landlord_id, address_line_1, address_line_2, city, county, postcode, country, is_primary)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(address.id)
+        .bind(address.landlord_id)
+        .bind(address.address_line_1)
+        .bind(address.address_line_2)
+        .bind(address.city)
+        .bind(address.county)
+        .bind(address.postcode)
+        .bind(address.country)
+        .bind(address.is_primary)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let bank = landlord.bank_details;
+        sqlx::query(
+            "INSERT INTO landlords_bank_details (id, landlord_id, account_name, account_number, sort_code, iban, bic, is_primary)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(bank.id)
+        .bind(bank.landlord_id)
+        .bind(bank.account_name)
+        .bind(key_ring.encrypt(&bank.account_number)?)
+        .bind(key_ring.encrypt(&bank.sort_code)?)
+        .bind(key_ring.encrypt_opt(bank.iban.as_deref())?)
+        .bind(bank.bic)
+        .bind(bank.is_primary)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let lettings = landlord.lettings;
+        sqlx::query(
+            "INSERT INTO landlords_lettings_management (id, landlord_id, payment_frequency, is_exempt_from_nrl_tax, nrl_exemption_reference, is_exempt_from_vat, vat_number, ni_number, unique_taxpayer_reference, statement_template_override, statement_email_subject_override, statement_payment_ref, accountant_email)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+        )
+        .bind(lettings.id)
+        .bind(lettings.landlord_id)
+        .bind(lettings.payment_frequency)
+        .bind(lettings.is_exempt_from_nrl_tax)
+        .bind(lettings.nrl_exemption_reference)
+        .bind(lettings.is_exempt_from_vat)
+        .bind(lettings.vat_number)
+        .bind(key_ring.encrypt_opt(lettings.ni_number.as_deref())?)
+        .bind(key_ring.encrypt_opt(lettings.unique_taxpayer_reference.as_deref())?)
+        .bind(lettings.statement_template_override)
+        .bind(lettings.statement_email_subject_override)
+        .bind(lettings.statement_payment_ref)
+        .bind(lettings.accountant_email)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        Ok(1)
+    }
+
+    async fn save_property(
+        tx: &mut Transaction<'_, Postgres>,
+        property: SeedProperty,
+    ) -> Result<u64, CustomErrors> {
+        let core = property.core;
+        let result = sqlx::query(
+            "INSERT INTO property_core (property_id, status, property_type, letting_classification, staff_assigned, landlord_id, date_available, created_at, updated_at, tenancy_end_date)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(core.property_id)
+        .bind(core.status.clone())
+        .bind(core.property_type)
+        .bind(core.letting_classification)
+        .bind(core.staff_assigned)
+        .bind(core.landlord_id)
+        .bind(core.date_available)
+        .bind(core.created_at)
+        .bind(core.updated_at)
+        .bind(core.tenancy_end_date)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Ok(0);
+        }
+
+        sqlx::query(
+            "INSERT INTO property_status_history (history_id, property_id, status, tenancy_end_date, changed_at)
+             VALUES (gen_random_uuid(), $1, $2, $3, $4)",
+        )
+        .bind(core.property_id)
+        .bind(core.status)
+        .bind(core.tenancy_end_date)
+        .bind(core.created_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let address = property.address;
+        sqlx::query(
+            "INSERT INTO property_address (address_id, property_id, display_address, address_line1, address_line2, town_city, county, postcode, country, searchable_area, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(address.address_id)
+        .bind(address.property_id)
+        .bind(address.display_address)
+        .bind(address.address_line1)
+        .bind(address.address_line2)
+        .bind(address.town_city)
+        .bind(address.county)
+        .bind(address.postcode)
+        .bind(address.country)
+        .bind(address.searchable_area)
+        .bind(address.created_at)
+        .bind(address.updated_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        Ok(1)
+    }
+
+    async fn save_event(
+        tx: &mut Transaction<'_, Postgres>,
+        seed_event: SeedEvent,
+    ) -> Result<u64, CustomErrors> {
+        let event = seed_event.event;
+        let result = sqlx::query(
+            "INSERT INTO events (id, external_id, event_type, date, start_time, end_time, created_by, title, description, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(event.id)
+        .bind(&event.external_id)
+        .bind(&event.event_type)
+        .bind(event.date)
+        .bind(event.start_time)
+        .bind(event.end_time)
+        .bind(event.created_by)
+        .bind(&event.title)
+        .bind(&event.description)
+        .bind(event.created_at)
+        .bind(event.updated_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Ok(0);
+        }
+
+        EventRepository::insert_details(tx, event.id.unwrap_or_default(), seed_event.details)
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(1)
+    }
+}
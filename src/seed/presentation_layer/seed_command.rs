@@ -0,0 +1,62 @@
+use chrono::NaiveDate;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use sqlx::{Pool, Postgres};
+
+use crate::seed::{application_layer::seed_service, domain_layer::seed_dataset::SeedOptions};
+use crate::shared::infrastructure_layer::field_cipher::KeyRing;
+
+pub fn seed_command() -> Command {
+    Command::new("seed")
+        .about("Populate the database with a deterministic demo dataset")
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_parser(value_parser!(u64))
+                .default_value("42")
+                .help("Random seed; the same seed always produces the same rows"),
+        )
+        .arg(
+            Arg::new("start-date")
+                .long("start-date")
+                .value_parser(parse_date)
+                .default_value("2024-11-01")
+                .help("First day of the generated diary month; fixed so every run matches"),
+        )
+        .arg(
+            Arg::new("password")
+                .long("password")
+                .default_value("password123")
+                .help("Password given to every generated staff user"),
+        )
+}
+
+pub async fn run(matches: &ArgMatches, pool: &Pool<Postgres>) -> std::io::Result<()> {
+    let options = SeedOptions {
+        seed: matches.get_one::<u64>("seed").copied().unwrap_or(42),
+        start_date: matches
+            .get_one::<NaiveDate>("start-date")
+            .copied()
+            .unwrap_or_default(),
+        staff_password: matches
+            .get_one::<String>("password")
+            .cloned()
+            .unwrap_or_default(),
+    };
+
+    let key_ring = KeyRing::from_env().map_err(|e| std::io::Error::other(e.to_string()))?;
+
+    match seed_service::run_seed(pool, options, &key_ring).await {
+        Ok(summary) => {
+            println!(
+                "Seeded {} staff, {} landlords, {} properties and {} diary events",
+                summary.staff, summary.landlords, summary.properties, summary.events
+            );
+            Ok(())
+        }
+        Err(e) => Err(std::io::Error::other(e.to_string())),
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| e.to_string())
+}
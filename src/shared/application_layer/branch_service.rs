@@ -0,0 +1,493 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::shared::{
+    domain_layer::{
+        api_schema::ErrorResponse,
+        branch::{
+            Branch, BranchRecord, BranchRequest, BranchTransferRequest, Organisation,
+            OrganisationCreated, OrganisationRequest, StaffBranch, MANAGE_ORGANISATIONS,
+        },
+    },
+    infrastructure_layer::branch_repository::{BranchRepository, CustomErrors},
+};
+use crate::user::{
+    application_layer::permission_service,
+    infrastructure_layer::{
+        jwt_repo, permission_repository::PermissionRepository, user_repository::UserRepository,
+    },
+};
+use crate::AppState;
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound(_) => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::ValidationError(_) => {
+            HttpResponse::BadRequest().json(json!({"error": e.to_string()}))
+        }
+        _ => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+fn validate_name(field: &str, value: &str) -> Result<(), CustomErrors> {
+    if value.trim().is_empty() {
+        return Err(CustomErrors::ValidationError(format!(
+            "{} must not be empty",
+            field
+        )));
+    }
+    Ok(())
+}
+
+async fn has_permission(state: &web::Data<AppState>, user_id: Uuid, permission: &str) -> bool {
+    PermissionRepository::new()
+        .has_permission(&state.db, user_id, permission)
+        .await
+        .unwrap_or(false)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organisations",
+    tag = "branches",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Organisations with a branch the caller can see", body = [Organisation]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub async fn get_organisations(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if jwt_repo::authenticated_user_id(&req, &state)
+        .await
+        .is_none()
+    {
+        return HttpResponse::Unauthorized().json(json!({"error": "Not logged in"}));
+    }
+    match BranchRepository::new().get_organisations(&state.db).await {
+        Ok(organisations) => HttpResponse::Ok().json(organisations),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/organisations",
+    tag = "branches",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 201, description = "The organisation and its first branch", body = OrganisationCreated),
+        (status = 400, description = "Missing name", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_organisations`", body = ErrorResponse),
+    )
+)]
+pub async fn create_organisation(
+    state: web::Data<AppState>,
+    body: web::Json<OrganisationRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id =
+        match permission_service::require_permission(&req, &state, MANAGE_ORGANISATIONS).await {
+            Ok(user_id) => user_id,
+            Err(response) => return response,
+        };
+    if let Err(e) = validate_name("name", &body.name)
+        .and_then(|_| validate_name("branch_name", &body.branch_name))
+    {
+        return error_response(e);
+    }
+    match BranchRepository::new()
+        .create_organisation(&state.db, body.name.trim(), body.branch_name.trim())
+        .await
+    {
+        Ok((organisation, branch)) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "organisation_created",
+                format!("Created organisation {}", organisation.id),
+            )
+            .await;
+            HttpResponse::Created().json(OrganisationCreated {
+                organisation,
+                branch,
+            })
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/branches",
+    tag = "branches",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Branches the caller can see", body = [Branch]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub async fn get_branches(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if jwt_repo::authenticated_user_id(&req, &state)
+        .await
+        .is_none()
+    {
+        return HttpResponse::Unauthorized().json(json!({"error": "Not logged in"}));
+    }
+    match BranchRepository::new().get_branches(&state.db).await {
+        Ok(branches) => HttpResponse::Ok().json(branches),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/branches",
+    tag = "branches",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 201, description = "The new branch", body = Branch),
+        (status = 400, description = "Missing or duplicate name", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required, or `manage_organisations` for another organisation", body = ErrorResponse),
+        (status = 404, description = "Organisation not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_branch(
+    state: web::Data<AppState>,
+    body: web::Json<BranchRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match permission_service::require_admin(&req, &state).await {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    if let Err(e) = validate_name("name", &body.name) {
+        return error_response(e);
+    }
+    let repo = BranchRepository::new();
+    let own_organisation = match repo.organisation_of(&state.db, admin_id).await {
+        Ok(organisation_id) => organisation_id,
+        Err(e) => return error_response(e),
+    };
+    let organisation_id = body.organisation_id.unwrap_or(own_organisation);
+    if organisation_id != own_organisation {
+        if !has_permission(&state, admin_id, MANAGE_ORGANISATIONS).await {
+            return HttpResponse::Forbidden()
+                .json(json!({"error": format!("Missing permission {}", MANAGE_ORGANISATIONS)}));
+        }
+        match repo.organisation_exists(&state.db, organisation_id).await {
+            Ok(true) => {}
+            Ok(false) => return error_response(CustomErrors::NotFound("Organisation")),
+            Err(e) => return error_response(e),
+        }
+    }
+    match repo
+        .create_branch(&state.db, organisation_id, body.name.trim())
+        .await
+    {
+        Ok(branch) => {
+            permission_service::audit(
+                &req,
+                &state,
+                admin_id,
+                "branch_created",
+                format!(
+                    "Created branch {} in organisation {}",
+                    branch.id, organisation_id
+                ),
+            )
+            .await;
+            HttpResponse::Created().json(branch)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// Checks the staff member and branch are both in the caller's scope.
+async fn visible_staff_and_branch(
+    state: &web::Data<AppState>,
+    user_id: Uuid,
+    branch_id: Uuid,
+) -> Result<(), HttpResponse> {
+    if UserRepository::new()
+        .get_by_id(state.clone().into_inner(), user_id)
+        .await
+        .is_err()
+    {
+        return Err(error_response(CustomErrors::NotFound("User")));
+    }
+    BranchRepository::new()
+        .get_branch(&state.db, branch_id)
+        .await
+        .map(|_| ())
+        .map_err(error_response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}/branches",
+    tag = "branches",
+    params(("user_id" = Uuid, Path, description = "Staff user id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Branches the user works for besides their own", body = [StaffBranch]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_staff_branches(
+    state: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(response) = permission_service::require_admin(&req, &state).await {
+        return response;
+    }
+    let user_id = user_id.into_inner();
+    if UserRepository::new()
+        .get_by_id(state.clone().into_inner(), user_id)
+        .await
+        .is_err()
+    {
+        return error_response(CustomErrors::NotFound("User"));
+    }
+    match BranchRepository::new()
+        .get_staff_branches(&state.db, user_id)
+        .await
+    {
+        Ok(branches) => HttpResponse::Ok().json(branches),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{user_id}/branches/{branch_id}",
+    tag = "branches",
+    params(
+        ("user_id" = Uuid, Path, description = "Staff user id"),
+        ("branch_id" = Uuid, Path, description = "Branch to give the user access to"),
+    ),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The grant", body = StaffBranch),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "User or branch not found", body = ErrorResponse),
+    )
+)]
+pub async fn grant_staff_branch(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match permission_service::require_admin(&req, &state).await {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    let (user_id, branch_id) = path.into_inner();
+    if let Err(response) = visible_staff_and_branch(&state, user_id, branch_id).await {
+        return response;
+    }
+    match BranchRepository::new()
+        .grant_staff_branch(&state.db, user_id, branch_id, admin_id)
+        .await
+    {
+        Ok(granted) => {
+            permission_service::audit(
+                &req,
+                &state,
+                admin_id,
+                "branch_access_granted",
+                format!("Gave user {} access to branch {}", user_id, branch_id),
+            )
+            .await;
+            HttpResponse::Ok().json(granted)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{user_id}/branches/{branch_id}",
+    tag = "branches",
+    params(
+        ("user_id" = Uuid, Path, description = "Staff user id"),
+        ("branch_id" = Uuid, Path, description = "Branch to take away"),
+    ),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 204, description = "Access removed"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "User, branch or grant not found", body = ErrorResponse),
+    )
+)]
+pub async fn revoke_staff_branch(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match permission_service::require_admin(&req, &state).await {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    let (user_id, branch_id) = path.into_inner();
+    if let Err(response) = visible_staff_and_branch(&state, user_id, branch_id).await {
+        return response;
+    }
+    match BranchRepository::new()
+        .revoke_staff_branch(&state.db, user_id, branch_id)
+        .await
+    {
+        Ok(()) => {
+            permission_service::audit(
+                &req,
+                &state,
+                admin_id,
+                "branch_access_revoked",
+                format!("Removed user {} from branch {}", user_id, branch_id),
+            )
+            .await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+async fn transfer(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    record: BranchRecord,
+    id: Uuid,
+    body: BranchTransferRequest,
+) -> HttpResponse {
+    let admin_id = match permission_service::require_admin(&req, &state).await {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    let repo = BranchRepository::new();
+    if let Err(e) = repo.get_branch(&state.db, body.branch_id).await {
+        return error_response(e);
+    }
+    match repo
+        .transfer(
+            &state.db,
+            record,
+            id,
+            body.branch_id,
+            body.include_properties,
+        )
+        .await
+    {
+        Ok(()) => {
+            permission_service::audit(
+                &req,
+                &state,
+                admin_id,
+                "branch_transfer",
+                format!(
+                    "Moved {} {} to branch {}",
+                    record.as_str(),
+                    id,
+                    body.branch_id
+                ),
+            )
+            .await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{user_id}/transfer",
+    tag = "branches",
+    params(("user_id" = Uuid, Path, description = "Staff user id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 204, description = "The user's own branch is now `branch_id`"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "User or branch not found", body = ErrorResponse),
+    )
+)]
+pub async fn transfer_user(
+    state: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    body: web::Json<BranchTransferRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    transfer(
+        state,
+        req,
+        BranchRecord::StaffUser,
+        user_id.into_inner(),
+        body.into_inner(),
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/landlords/{landlord_id}/transfer",
+    tag = "branches",
+    params(("landlord_id" = Uuid, Path, description = "Landlord id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 204, description = "The landlord, and their properties if asked, now belong to `branch_id`"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Landlord or branch not found", body = ErrorResponse),
+    )
+)]
+pub async fn transfer_landlord(
+    state: web::Data<AppState>,
+    landlord_id: web::Path<Uuid>,
+    body: web::Json<BranchTransferRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    transfer(
+        state,
+        req,
+        BranchRecord::Landlord,
+        landlord_id.into_inner(),
+        body.into_inner(),
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/transfer",
+    tag = "branches",
+    params(("property_id" = Uuid, Path, description = "Property id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 204, description = "The property now belongs to `branch_id`"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+        (status = 404, description = "Property or branch not found", body = ErrorResponse),
+    )
+)]
+pub async fn transfer_property(
+    state: web::Data<AppState>,
+    property_id: web::Path<Uuid>,
+    body: web::Json<BranchTransferRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    transfer(
+        state,
+        req,
+        BranchRecord::Property,
+        property_id.into_inner(),
+        body.into_inner(),
+    )
+    .await
+}
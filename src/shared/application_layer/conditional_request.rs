@@ -0,0 +1,84 @@
+use actix_web::http::header::{self, EntityTag, Header, IfMatch, IfNoneMatch};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+
+/// Strong entity tag for a row, derived from its `updated_at` timestamp.
+pub fn entity_tag(updated_at: &DateTime<Utc>) -> EntityTag {
+    EntityTag::new_strong(updated_at.timestamp_micros().to_string())
+}
+
+/// Responds to a GET with an `ETag` header, or with `304 Not Modified` when the
+/// client's `If-None-Match` already names the current version.
+pub fn conditional_get<T: Serialize>(
+    req: &HttpRequest,
+    updated_at: Option<DateTime<Utc>>,
+    body: &T,
+) -> HttpResponse {
+    let etag = match updated_at {
+        Some(updated_at) => entity_tag(&updated_at),
+        None => return HttpResponse::Ok().json(body),
+    };
+
+    let matches = match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        Err(_) => false,
+    };
+
+    if matches {
+        HttpResponse::NotModified()
+            .insert_header(header::ETag(etag))
+            .finish()
+    } else {
+        HttpResponse::Ok()
+            .insert_header(header::ETag(etag))
+            .json(body)
+    }
+}
+
+/// Reads the version a PUT/PATCH expects to overwrite from `If-Match`.
+///
+/// Returns `Ok(None)` for `If-Match: *`, `428` when the header is missing and
+/// `412` when none of the supplied tags could ever match.
+pub fn expected_version(req: &HttpRequest) -> Result<Option<DateTime<Utc>>, HttpResponse> {
+    if !req.headers().contains_key(header::IF_MATCH) {
+        return Err(HttpResponse::PreconditionRequired().json(json!({
+            "error": "Precondition required",
+            "message": "Send the resource's ETag in an If-Match header"
+        })));
+    }
+
+    match IfMatch::parse(req) {
+        Ok(IfMatch::Any) => Ok(None),
+        Ok(IfMatch::Items(tags)) => tags
+            .iter()
+            .filter(|tag| !tag.weak)
+            .find_map(|tag| {
+                tag.tag()
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(DateTime::from_timestamp_micros)
+            })
+            .map(Some)
+            .ok_or_else(precondition_failed),
+        Err(_) => Err(precondition_failed()),
+    }
+}
+
+pub fn precondition_failed() -> HttpResponse {
+    HttpResponse::PreconditionFailed().json(json!({
+        "error": "Precondition failed",
+        "message": "The resource was modified by someone else; reload it and try again"
+    }))
+}
+
+/// Successful PUT/PATCH response carrying the new `ETag`.
+pub fn updated<T: Serialize>(updated_at: Option<DateTime<Utc>>, body: &T) -> HttpResponse {
+    let mut response = HttpResponse::Ok();
+    if let Some(updated_at) = updated_at {
+        response.insert_header(header::ETag(entity_tag(&updated_at)));
+    }
+    response.json(body)
+}
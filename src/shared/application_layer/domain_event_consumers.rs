@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::shared::{
+    domain_layer::{
+        consumer_health::{ConsumerHealth, ConsumerState},
+        outbox_event::{
+            DomainEventEnvelope, DIARY_EVENTS_TOPIC, LANDLORD_EVENTS_TOPIC, PROPERTY_EVENTS_TOPIC,
+            USER_EVENTS_TOPIC,
+        },
+    },
+    infrastructure_layer::kafka_consumer::{
+        ConsumerConfig, KafkaConsumerSupervisor, MessageContext,
+    },
+};
+
+/// Starts the Kafka consumer with every domain event handler registered.
+/// Without `KAFKA_BROKERS` nothing is started and the consumer is reported
+/// as disabled.
+pub fn spawn_consumers(
+    health: Arc<ConsumerHealth>,
+    shutdown: watch::Receiver<bool>,
+) -> Option<JoinHandle<()>> {
+    let config = match ConsumerConfig::from_env() {
+        Some(config) => config,
+        None => {
+            health.set_state(ConsumerState::Disabled);
+            return None;
+        }
+    };
+
+    let supervisor = KafkaConsumerSupervisor::new(config, health)
+        .handle(PROPERTY_EVENTS_TOPIC, log_domain_event)
+        .handle(LANDLORD_EVENTS_TOPIC, log_domain_event)
+        .handle(DIARY_EVENTS_TOPIC, log_domain_event)
+        .handle(USER_EVENTS_TOPIC, log_domain_event);
+    Some(supervisor.spawn(shutdown))
+}
+
+async fn log_domain_event(
+    envelope: DomainEventEnvelope,
+    context: MessageContext,
+) -> Result<(), String> {
+    log::info!(
+        "{} {} for {} {} ({}[{}]@{})",
+        envelope.event_type,
+        envelope.event_id,
+        envelope.aggregate_type,
+        envelope.aggregate_id,
+        context.topic,
+        context.partition,
+        context.offset
+    );
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::shared::domain_layer::api_schema::{LivenessResponse, ReadinessResponse};
+use crate::AppState;
+
+/// The process is up; says nothing about its dependencies.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses((status = 200, description = "The process is running", body = LivenessResponse))
+)]
+pub async fn liveness() -> impl Responder {
+    HttpResponse::Ok().json(json!({ "status": "ok" }))
+}
+
+/// Ready to take traffic: the database answers and the Kafka consumer is
+/// either running or disabled.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready", body = ReadinessResponse),
+        (status = 503, description = "The database or Kafka consumer is not available", body = ReadinessResponse),
+    )
+)]
+pub async fn readiness(state: web::Data<AppState>) -> impl Responder {
+    let database_ready = sqlx::query("SELECT 1").execute(&state.db).await.is_ok();
+    let consumers_ready = state.consumer_health.is_ready();
+    let body = json!({
+        "status": if database_ready && consumers_ready { "ready" } else { "not_ready" },
+        "database": if database_ready { "ok" } else { "unavailable" },
+        "consumers": state.consumer_health.snapshot(),
+    });
+
+    if database_ready && consumers_ready {
+        HttpResponse::Ok().json(body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(body)
+    }
+}
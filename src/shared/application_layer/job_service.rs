@@ -0,0 +1,310 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::shared::{
+    domain_layer::{
+        api_schema::ErrorResponse,
+        job::{Job, JobQueryParams, JobSchedule, JobSchedulePatch, NewJob, MANAGE_JOBS},
+    },
+    infrastructure_layer::job_repository::{CustomErrors, JobRepository},
+};
+use crate::user::application_layer::permission_service;
+use crate::AppState;
+
+const DEFAULT_JOB_LIMIT: i64 = 50;
+const MAX_JOB_LIMIT: i64 = 500;
+const JOB_STATUSES: [&str; 5] = ["pending", "running", "succeeded", "failed", "cancelled"];
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::Conflict(_) => HttpResponse::Conflict().json(json!({"error": e.to_string()})),
+        _ => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs",
+    tag = "jobs",
+    params(JobQueryParams),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Most recent jobs first", body = [Job]),
+        (status = 400, description = "Unknown status", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_jobs`", body = ErrorResponse),
+    )
+)]
+pub async fn get_jobs(
+    state: web::Data<AppState>,
+    query: web::Query<JobQueryParams>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(response) = permission_service::require_permission(&req, &state, MANAGE_JOBS).await {
+        return response;
+    }
+    if let Some(status) = &query.status {
+        if !JOB_STATUSES.contains(&status.as_str()) {
+            return HttpResponse::BadRequest().json(json!({
+                "error": format!("status must be one of {}", JOB_STATUSES.join(", "))
+            }));
+        }
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_JOB_LIMIT)
+        .clamp(1, MAX_JOB_LIMIT);
+    let repo = JobRepository::new();
+    match repo
+        .get_all(
+            &state.db,
+            query.status.as_deref(),
+            query.kind.as_deref(),
+            limit,
+        )
+        .await
+    {
+        Ok(jobs) => HttpResponse::Ok().json(jobs),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{job_id}",
+    tag = "jobs",
+    params(("job_id" = Uuid, Path, description = "Job id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The job", body = Job),
+        (status = 404, description = "Job not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_jobs`", body = ErrorResponse),
+    )
+)]
+pub async fn get_job(
+    state: web::Data<AppState>,
+    job_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(response) = permission_service::require_permission(&req, &state, MANAGE_JOBS).await {
+        return response;
+    }
+    let repo = JobRepository::new();
+    match repo.get_by_id(&state.db, job_id.into_inner()).await {
+        Ok(job) => HttpResponse::Ok().json(job),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs/{job_id}/retry",
+    tag = "jobs",
+    params(("job_id" = Uuid, Path, description = "Job id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 202, description = "The job, queued to run again", body = Job),
+        (status = 404, description = "Job not found", body = ErrorResponse),
+        (status = 409, description = "Only failed or cancelled jobs can be retried", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_jobs`", body = ErrorResponse),
+    )
+)]
+pub async fn retry_job(
+    state: web::Data<AppState>,
+    job_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_JOBS).await {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let repo = JobRepository::new();
+    match repo.retry(&state.db, job_id.into_inner()).await {
+        Ok(job) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "job_retried",
+                format!("Retried {} job {}", job.kind, job.id),
+            )
+            .await;
+            HttpResponse::Accepted().json(job)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs/{job_id}/cancel",
+    tag = "jobs",
+    params(("job_id" = Uuid, Path, description = "Job id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The cancelled job", body = Job),
+        (status = 404, description = "Job not found", body = ErrorResponse),
+        (status = 409, description = "Only pending jobs can be cancelled", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_jobs`", body = ErrorResponse),
+    )
+)]
+pub async fn cancel_job(
+    state: web::Data<AppState>,
+    job_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_JOBS).await {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let repo = JobRepository::new();
+    match repo.cancel(&state.db, job_id.into_inner()).await {
+        Ok(job) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "job_cancelled",
+                format!("Cancelled {} job {}", job.kind, job.id),
+            )
+            .await;
+            HttpResponse::Ok().json(job)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/schedules",
+    tag = "jobs",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Every registered schedule", body = [JobSchedule]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_jobs`", body = ErrorResponse),
+    )
+)]
+pub async fn get_schedules(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(response) = permission_service::require_permission(&req, &state, MANAGE_JOBS).await {
+        return response;
+    }
+    let repo = JobRepository::new();
+    match repo.get_schedules(&state.db).await {
+        Ok(schedules) => HttpResponse::Ok().json(schedules),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Pauses or resumes a schedule on every instance.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/jobs/schedules/{name}",
+    tag = "jobs",
+    params(("name" = String, Path, description = "Schedule name, e.g. `retention_purge`")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The updated schedule", body = JobSchedule),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_jobs`", body = ErrorResponse),
+    )
+)]
+pub async fn update_schedule(
+    state: web::Data<AppState>,
+    name: web::Path<String>,
+    body: web::Json<JobSchedulePatch>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_JOBS).await {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let repo = JobRepository::new();
+    match repo
+        .set_schedule_enabled(&state.db, &name, body.enabled)
+        .await
+    {
+        Ok(schedule) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "job_schedule_updated",
+                format!(
+                    "{} the {} schedule",
+                    if schedule.enabled {
+                        "Enabled"
+                    } else {
+                        "Disabled"
+                    },
+                    schedule.name
+                ),
+            )
+            .await;
+            HttpResponse::Ok().json(schedule)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// Queues a scheduled job to run now, unless a run is already pending or running.
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs/schedules/{name}/run",
+    tag = "jobs",
+    params(("name" = String, Path, description = "Schedule name, e.g. `retention_purge`")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 202, description = "The queued job", body = Job),
+        (status = 404, description = "Schedule not found", body = ErrorResponse),
+        (status = 409, description = "A run is already pending or running", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_jobs`", body = ErrorResponse),
+    )
+)]
+pub async fn run_schedule_now(
+    state: web::Data<AppState>,
+    name: web::Path<String>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_JOBS).await {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let repo = JobRepository::new();
+    let schedule = match repo.get_schedule(&state.db, &name).await {
+        Ok(schedule) => schedule,
+        Err(e) => return error_response(e),
+    };
+    let mut job = NewJob::new(&schedule.kind, schedule.payload.clone())
+        .unique_key(format!("schedule:{}", schedule.name));
+    job.schedule_name = Some(schedule.name.clone());
+    let job_id = match JobRepository::enqueue(&state.db, &job).await {
+        Ok(Some(job_id)) => job_id,
+        Ok(None) => {
+            return error_response(CustomErrors::Conflict(format!(
+                "a {} run is already pending or running",
+                schedule.name
+            )))
+        }
+        Err(e) => return error_response(e.into()),
+    };
+    permission_service::audit(
+        &req,
+        &state,
+        user_id,
+        "job_schedule_run",
+        format!("Queued a run of the {} schedule", schedule.name),
+    )
+    .await;
+    match repo.get_by_id(&state.db, job_id).await {
+        Ok(job) => HttpResponse::Accepted().json(job),
+        Err(e) => error_response(e),
+    }
+}
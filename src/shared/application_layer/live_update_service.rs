@@ -0,0 +1,350 @@
+use actix_web::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use futures_channel::mpsc;
+use futures_util::SinkExt;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::diary::infrastructure_layer::diary_event_repo::EventRepository;
+use crate::shared::{
+    domain_layer::{
+        api_schema::ErrorResponse,
+        branch::BranchScope,
+        live_update::{Audience, LiveUpdate, StreamPosition, NOTIFICATION_EVENT},
+        webhook::WebhookEventType,
+    },
+    infrastructure_layer::{
+        branch_scope,
+        live_update_repository::{LiveEventRow, LiveUpdateRepository},
+    },
+};
+use crate::user::infrastructure_layer::jwt_repo;
+use crate::AppState;
+
+const HUB_CAPACITY: usize = 1024;
+const CATCH_UP_BATCH: i64 = 200;
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// How often the listener looks again for events that were held back while
+/// an older transaction was still running.
+const SETTLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+type Frame = Result<web::Bytes, actix_web::Error>;
+
+/// Fans live updates out to every open connection.
+pub struct LiveUpdateHub {
+    sender: broadcast::Sender<Arc<LiveUpdate>>,
+}
+
+impl LiveUpdateHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(HUB_CAPACITY);
+        LiveUpdateHub { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<LiveUpdate>> {
+        self.sender.subscribe()
+    }
+
+    fn publish(&self, update: LiveUpdate) {
+        // No receivers just means nobody is connected.
+        self.sender.send(Arc::new(update)).ok();
+    }
+}
+
+impl Default for LiveUpdateHub {
+    fn default() -> Self {
+        LiveUpdateHub::new()
+    }
+}
+
+/// Turns a committed outbox event into what connected staff should see.
+async fn build_updates(pool: &Pool<Postgres>, row: LiveEventRow) -> Vec<LiveUpdate> {
+    let event_type = match WebhookEventType::for_outbox_event(&row.event_type) {
+        Some(event_type) => event_type,
+        None => return Vec::new(),
+    };
+
+    match event_type {
+        WebhookEventType::PropertyStatusChanged => {
+            let branch_id = match LiveUpdateRepository::new()
+                .property_branch(pool, row.aggregate_id)
+                .await
+            {
+                Ok(Some(branch_id)) => branch_id,
+                Ok(None) => return Vec::new(),
+                Err(e) => {
+                    log::warn!(
+                        "Resolving the branch of property {} failed: {}",
+                        row.aggregate_id,
+                        e
+                    );
+                    return Vec::new();
+                }
+            };
+            vec![LiveUpdate {
+                position: row.position(),
+                event: event_type.as_str().to_string(),
+                data: row.data,
+                audience: Audience::Branch(branch_id),
+            }]
+        }
+        WebhookEventType::DiaryEventCreated
+        | WebhookEventType::DiaryEventUpdated
+        | WebhookEventType::DiaryEventDeleted => {
+            let audience = match EventRepository::live_audience(pool, row.aggregate_id).await {
+                Ok(Some(audience)) => audience,
+                Ok(None) => return Vec::new(),
+                Err(e) => {
+                    log::warn!(
+                        "Resolving who can see event {} failed: {}",
+                        row.aggregate_id,
+                        e
+                    );
+                    return Vec::new();
+                }
+            };
+
+            let mut updates = Vec::new();
+            let message = match event_type {
+                WebhookEventType::DiaryEventCreated => Some("New diary event"),
+                WebhookEventType::DiaryEventUpdated => Some("Diary event updated"),
+                _ => None,
+            };
+            if let (Some(message), false) = (message, audience.popup_recipients.is_empty()) {
+                updates.push(LiveUpdate {
+                    position: row.position(),
+                    event: NOTIFICATION_EVENT.to_string(),
+                    data: json!({
+                        "message": message,
+                        "type": event_type.as_str(),
+                        "event": row.data,
+                    }),
+                    audience: Audience::Users(audience.popup_recipients),
+                });
+            }
+            updates.insert(
+                0,
+                LiveUpdate {
+                    position: row.position(),
+                    event: event_type.as_str().to_string(),
+                    data: row.data,
+                    audience: if audience.is_private {
+                        Audience::Users(audience.involved)
+                    } else {
+                        Audience::Branch(audience.branch_id)
+                    },
+                },
+            );
+            updates
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Publishes the settled live events after `after` to the hub in stream
+/// order. Returns the position reached.
+async fn publish_since(
+    pool: &Pool<Postgres>,
+    hub: &LiveUpdateHub,
+    mut after: StreamPosition,
+) -> StreamPosition {
+    let repo = LiveUpdateRepository::new();
+    loop {
+        let rows = match repo.get_since(pool, after, CATCH_UP_BATCH).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                log::warn!("Reading live updates failed: {}", e);
+                return after;
+            }
+        };
+        let complete = (rows.len() as i64) < CATCH_UP_BATCH;
+        for row in rows {
+            after = row.position();
+            for update in build_updates(pool, row).await {
+                hub.publish(update);
+            }
+        }
+        if complete {
+            return after;
+        }
+    }
+}
+
+/// Listens for committed outbox events and publishes them to the hub for as
+/// long as the server is up. Events are published in stream order once they
+/// have settled, so one committed while an older transaction is still running
+/// waits for it. Notifications only say when to look again.
+pub fn spawn_live_update_listener(pool: Pool<Postgres>, hub: Arc<LiveUpdateHub>) {
+    actix_web::rt::spawn(async move {
+        let repo = LiveUpdateRepository::new();
+        let mut position = repo.latest_position(&pool).await.unwrap_or_default();
+
+        loop {
+            let mut listener = match repo.listen(&pool).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    log::error!("Live update listener could not connect: {}", e);
+                    actix_web::rt::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+
+            loop {
+                position = publish_since(&pool, &hub, position).await;
+                match actix_web::rt::time::timeout(SETTLE_POLL_INTERVAL, listener.recv()).await {
+                    Ok(Ok(_)) | Err(_) => {}
+                    Ok(Err(e)) => {
+                        log::warn!("Live update listener lost its connection: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            actix_web::rt::time::sleep(RECONNECT_DELAY).await;
+        }
+    });
+}
+
+/// Sends the settled events after `after` that `user_id` may see, straight
+/// from the outbox. Returns the last position covered, or `None` once the
+/// client has gone away.
+async fn catch_up(
+    pool: &Pool<Postgres>,
+    user_id: Uuid,
+    scope: &BranchScope,
+    mut after: StreamPosition,
+    frames: &mut mpsc::Sender<Frame>,
+) -> Option<StreamPosition> {
+    let repo = LiveUpdateRepository::new();
+    loop {
+        let rows = match repo.get_since(pool, after, CATCH_UP_BATCH).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                log::warn!("Live update catch-up failed: {}", e);
+                return Some(after);
+            }
+        };
+        let complete = (rows.len() as i64) < CATCH_UP_BATCH;
+        for row in rows {
+            after = row.position();
+            for update in build_updates(pool, row).await {
+                if update.audience.includes(user_id, scope) {
+                    frames
+                        .send(Ok(web::Bytes::from(update.to_frame())))
+                        .await
+                        .ok()?;
+                }
+            }
+        }
+        if complete {
+            return Some(after);
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LiveQueryParams {
+    /// For clients that cannot set the `Last-Event-ID` header.
+    pub last_event_id: Option<String>,
+}
+
+/// Server-sent events stream of diary and property changes. Reconnecting
+/// clients send `Last-Event-ID` and get everything they missed first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/live",
+    tag = "live updates",
+    params(LiveQueryParams, ("Last-Event-ID" = Option<String>, Header, description = "Id of the last event received, e.g. `1514-42`; everything after it is sent first")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Stream of diary, property and pop-up notification events; the SSE event name is the webhook event type or `notification`", content_type = "text/event-stream", body = String),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub async fn live_updates(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<LiveQueryParams>,
+) -> impl Responder {
+    let user_id = match jwt_repo::authenticated_user_id(&req, &state).await {
+        Some(user_id) => user_id,
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Not logged in"})),
+    };
+    let last_event_id = req
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .or(query.last_event_id.as_deref())
+        .and_then(StreamPosition::parse);
+
+    // The stream outlives the request, so keep hold of the caller's branches.
+    let scope = branch_scope::current();
+    // Subscribe before catching up so nothing falls between the two.
+    let mut updates = state.live_updates.subscribe();
+    let (mut frames, body) = mpsc::channel::<Frame>(64);
+    let pool = state.db.clone();
+
+    actix_web::rt::spawn(async move {
+        if frames
+            .send(Ok(web::Bytes::from_static(b"retry: 5000\n\n")))
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let mut caught_up_to = match last_event_id {
+            Some(last_event_id) => {
+                match catch_up(&pool, user_id, &scope, last_event_id, &mut frames).await {
+                    Some(position) => position,
+                    None => return,
+                }
+            }
+            None => StreamPosition::default(),
+        };
+        let mut last_sent = caught_up_to;
+        let mut heartbeat = actix_web::rt::time::interval(HEARTBEAT_INTERVAL);
+
+        loop {
+            let frame = tokio::select! {
+                _ = heartbeat.tick() => web::Bytes::from_static(b": keep-alive\n\n"),
+                received = updates.recv() => match received {
+                    Ok(update) if update.position <= caught_up_to => continue,
+                    Ok(update) if !update.audience.includes(user_id, &scope) => continue,
+                    Ok(update) => {
+                        last_sent = update.position;
+                        web::Bytes::from(update.to_frame())
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        // Too slow to keep up with the hub; resend from the outbox.
+                        match catch_up(&pool, user_id, &scope, last_sent, &mut frames).await {
+                            Some(position) => {
+                                caught_up_to = position;
+                                last_sent = position;
+                                continue;
+                            }
+                            None => return,
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+            };
+            if frames.send(Ok(frame)).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .insert_header((CONTENT_TYPE, "text/event-stream"))
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(body)
+}
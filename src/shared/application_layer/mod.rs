@@ -0,0 +1,13 @@
+pub mod conditional_request;
+pub mod retention_service;
+pub mod outbox_relay;
+pub mod domain_event_consumers;
+pub mod health_service;
+pub mod webhook_dispatcher;
+pub mod webhook_service;
+pub mod live_update_service;
+pub mod notification_service;
+pub mod scheduled_jobs;
+pub mod job_service;
+pub mod search_service;
+pub mod branch_service;
@@ -0,0 +1,151 @@
+use chrono::{Duration, Utc};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+
+use crate::shared::{
+    domain_layer::email::EmailTemplate,
+    infrastructure_layer::{
+        email_queue_repository::{DueEmail, EmailQueueRepository},
+        email_templates::EmailRenderer,
+        email_transport::EmailTransport,
+    },
+};
+
+const BATCH_SIZE: i64 = 50;
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+/// How long a claimed email is reserved for this sender.
+const LEASE_SECONDS: i64 = 120;
+/// Attempts after which an email is marked failed: 1m, 2m, 4m, ... up to 1h apart.
+const MAX_ATTEMPTS: i32 = 8;
+const BASE_BACKOFF_SECONDS: i64 = 60;
+const MAX_BACKOFF_SECONDS: i64 = 60 * 60;
+/// Sent, skipped and failed emails are kept this long for troubleshooting.
+const FINISHED_RETENTION_DAYS: i64 = 30;
+const CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Sends queued emails until the server stops.
+pub fn spawn_email_sender(pool: Pool<Postgres>, transport: Arc<dyn EmailTransport>) {
+    actix_web::rt::spawn(async move {
+        let renderer = match EmailRenderer::new() {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                log::error!("Email sender could not start: {}", e);
+                return;
+            }
+        };
+        let repo = EmailQueueRepository::new();
+        let mut last_cleanup = std::time::Instant::now();
+        loop {
+            let lease_until = Utc::now() + Duration::seconds(LEASE_SECONDS);
+            match repo.claim_due(&pool, BATCH_SIZE, lease_until).await {
+                Ok(emails) => {
+                    let full_batch = emails.len() as i64 == BATCH_SIZE;
+                    for email in emails {
+                        send(&pool, &repo, &renderer, transport.as_ref(), email).await;
+                    }
+                    if full_batch {
+                        continue;
+                    }
+                }
+                Err(e) => log::error!("Claiming queued emails failed: {}", e),
+            }
+
+            if last_cleanup.elapsed() >= CLEANUP_INTERVAL {
+                last_cleanup = std::time::Instant::now();
+                let cutoff = Utc::now() - Duration::days(FINISHED_RETENTION_DAYS);
+                match repo.delete_finished_before(&pool, cutoff).await {
+                    Ok(0) => {}
+                    Ok(deleted) => log::info!("Removed {} old emails from the queue", deleted),
+                    Err(e) => log::error!("Cleaning up the email queue failed: {}", e),
+                }
+            }
+            actix_web::rt::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+async fn send(
+    pool: &Pool<Postgres>,
+    repo: &EmailQueueRepository,
+    renderer: &EmailRenderer,
+    transport: &dyn EmailTransport,
+    email: DueEmail,
+) {
+    let address = match (&email.address, email.notifications_enabled) {
+        (_, false) => {
+            let reason = "Recipient has email notifications switched off";
+            log_result(email.id, repo.mark_skipped(pool, email.id, reason).await);
+            return;
+        }
+        (None, true) => {
+            let reason = "Recipient has no email address";
+            log_result(email.id, repo.mark_skipped(pool, email.id, reason).await);
+            return;
+        }
+        (Some(address), true) => address,
+    };
+    let template = match EmailTemplate::parse(&email.template) {
+        Some(template) => template,
+        None => {
+            let error = format!("Unknown template {}", email.template);
+            log_result(
+                email.id,
+                repo.record_failure(pool, email.id, &error, None).await,
+            );
+            return;
+        }
+    };
+
+    let mut context = email.context.clone();
+    if let (Some(name), Some(context)) = (&email.recipient_name, context.as_object_mut()) {
+        context
+            .entry("recipient_name")
+            .or_insert_with(|| name.clone().into());
+    }
+
+    let result = match renderer.render(template, address, &context) {
+        // A template that cannot render will not render on a retry either.
+        Err(e) => {
+            let error = e.to_string();
+            log_result(
+                email.id,
+                repo.record_failure(pool, email.id, &error, None).await,
+            );
+            return;
+        }
+        Ok(outgoing) => transport.send(&outgoing).await,
+    };
+
+    match result {
+        Ok(()) => log_result(email.id, repo.mark_sent(pool, email.id).await),
+        Err(e) => {
+            let attempt = email.attempts + 1;
+            let next_attempt_at = (attempt < MAX_ATTEMPTS).then(|| {
+                let backoff = BASE_BACKOFF_SECONDS
+                    .saturating_mul(2_i64.saturating_pow((attempt - 1) as u32))
+                    .min(MAX_BACKOFF_SECONDS);
+                Utc::now() + Duration::seconds(backoff)
+            });
+            if next_attempt_at.is_none() {
+                log::warn!(
+                    "Giving up on email {} after {} attempts: {}",
+                    email.id,
+                    attempt,
+                    e
+                );
+            }
+            let error = e.to_string();
+            log_result(
+                email.id,
+                repo.record_failure(pool, email.id, &error, next_attempt_at)
+                    .await,
+            );
+        }
+    }
+}
+
+fn log_result(email_id: uuid::Uuid, result: Result<(), sqlx::Error>) {
+    if let Err(e) = result {
+        log::error!("Recording the outcome of email {} failed: {}", email_id, e);
+    }
+}
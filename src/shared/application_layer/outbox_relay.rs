@@ -0,0 +1,99 @@
+use chrono::{Duration, Utc};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+
+use crate::shared::infrastructure_layer::{
+    event_publisher::EventPublisher, outbox_repository::OutboxRepository,
+};
+
+const BATCH_SIZE: i64 = 100;
+const DEFAULT_POLL_INTERVAL_MS: u64 = 1000;
+const MAX_BACKOFF_SECONDS: i64 = 300;
+/// How long a claimed batch is reserved for this relay.
+const LEASE_SECONDS: i64 = 120;
+/// Published rows are deleted after a week.
+const PUBLISHED_RETENTION_DAYS: i64 = 7;
+
+/// Publishes one batch of due outbox events. An event is only marked as
+/// published after the broker acknowledged it, so delivery is at least once;
+/// failures are retried with exponential backoff. Events are leased rather
+/// than locked, so no transaction stays open while the broker is waited on.
+/// Returns how many were sent.
+pub async fn relay_batch(
+    pool: &Pool<Postgres>,
+    publisher: &dyn EventPublisher,
+) -> Result<usize, sqlx::Error> {
+    let repo = OutboxRepository::new();
+    let lease_until = Utc::now() + Duration::seconds(LEASE_SECONDS);
+    let events = repo.claim_due(pool, BATCH_SIZE, lease_until).await?;
+    let mut published = 0;
+
+    for event in events {
+        // The rest are claimed again once the lease runs out, possibly by
+        // another relay.
+        if Utc::now() >= lease_until {
+            break;
+        }
+        let key = event.aggregate_id.to_string();
+        match publisher.publish(&event.topic, &key, &event.payload).await {
+            Ok(()) => {
+                repo.mark_published(pool, event.id).await?;
+                published += 1;
+            }
+            Err(e) => {
+                let backoff = 2_i64
+                    .saturating_pow(event.attempts.clamp(0, 16) as u32)
+                    .min(MAX_BACKOFF_SECONDS);
+                log::warn!(
+                    "Publishing outbox event {} to {} failed (attempt {}): {}",
+                    event.id,
+                    event.topic,
+                    event.attempts + 1,
+                    e
+                );
+                repo.mark_failed(
+                    pool,
+                    event.id,
+                    &e.to_string(),
+                    Utc::now() + Duration::seconds(backoff),
+                )
+                .await?;
+            }
+        }
+    }
+
+    Ok(published)
+}
+
+/// Runs the relay for as long as the server is up, polling every
+/// `OUTBOX_POLL_INTERVAL_MS` milliseconds when there is nothing to send.
+pub fn spawn_outbox_relay(pool: Pool<Postgres>, publisher: Arc<dyn EventPublisher>) {
+    let poll_interval = std::time::Duration::from_millis(
+        std::env::var("OUTBOX_POLL_INTERVAL_MS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_POLL_INTERVAL_MS),
+    );
+    actix_web::rt::spawn(async move {
+        let repo = OutboxRepository::new();
+        let mut last_cleanup = Utc::now();
+        loop {
+            match relay_batch(&pool, publisher.as_ref()).await {
+                // A full batch means there is probably more waiting.
+                Ok(published) if published as i64 == BATCH_SIZE => continue,
+                Ok(_) => {}
+                Err(e) => log::error!("Outbox relay failed: {}", e),
+            }
+
+            if Utc::now() - last_cleanup > Duration::hours(1) {
+                let cutoff = Utc::now() - Duration::days(PUBLISHED_RETENTION_DAYS);
+                if let Err(e) = repo.delete_published_before(&pool, cutoff).await {
+                    log::warn!("Outbox cleanup failed: {}", e);
+                }
+                last_cleanup = Utc::now();
+            }
+
+            actix_web::rt::time::sleep(poll_interval).await;
+        }
+    });
+}
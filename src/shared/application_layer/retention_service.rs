@@ -0,0 +1,46 @@
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+
+use crate::shared::{
+    domain_layer::retention_policy::{PurgeSummary, RetentionPolicy},
+    infrastructure_layer::{
+        document_storage::DocumentStorage,
+        job_runner::JobResult,
+        retention_repository::{CustomErrors, RetentionRepository},
+    },
+};
+
+pub async fn run_purge(
+    pool: &Pool<Postgres>,
+    storage: &dyn DocumentStorage,
+    policy: &RetentionPolicy,
+) -> Result<PurgeSummary, CustomErrors> {
+    let repo = RetentionRepository::new();
+    repo.purge(pool, storage, policy.cutoff(Utc::now())).await
+}
+
+/// Job form of the retention purge, scheduled once per `purge_interval`.
+pub async fn purge_job(
+    pool: Pool<Postgres>,
+    storage: Arc<dyn DocumentStorage>,
+    policy: RetentionPolicy,
+) -> JobResult {
+    match run_purge(&pool, storage.as_ref(), &policy).await {
+        Ok(summary) => {
+            log::info!(
+                "Retention purge removed {} events, {} properties, {} landlords, {} staff and {} files ({} skipped)",
+                summary.events,
+                summary.properties,
+                summary.landlords,
+                summary.staff,
+                summary.files,
+                summary.skipped
+            );
+            serde_json::to_value(summary)
+                .map(Some)
+                .map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
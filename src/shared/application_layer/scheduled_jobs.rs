@@ -0,0 +1,68 @@
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::diary::application_layer::diary_reminder_service;
+use crate::landlord::application_layer::registration_expiry_service;
+use crate::properties::application_layer::certificate_expiry_service;
+use crate::shared::{
+    application_layer::retention_service,
+    domain_layer::{cron_schedule::CronSchedule, job::Schedule, retention_policy::RetentionPolicy},
+    infrastructure_layer::{
+        document_storage::DocumentStorage,
+        job_runner::{JobRunner, JobRunnerConfig},
+    },
+};
+
+pub const RETENTION_PURGE: &str = "retention_purge";
+pub const CERTIFICATE_EXPIRY_CHECK: &str = "certificate_expiry_check";
+pub const DIARY_REMINDER_SCAN: &str = "diary_reminder_scan";
+pub const REGISTRATION_EXPIRY: &str = "registration_expiry";
+
+fn cron(expression: &str) -> Schedule {
+    Schedule::Cron(CronSchedule::parse(expression).expect("built-in cron expressions are valid"))
+}
+
+/// Starts the job runner with every background job registered and scheduled.
+pub fn spawn_job_runner(
+    pool: Pool<Postgres>,
+    storage: Arc<dyn DocumentStorage>,
+    policy: RetentionPolicy,
+    shutdown: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    let purge_interval = policy.purge_interval;
+    let (purge_pool, certificate_pool, reminder_pool, registration_pool) =
+        (pool.clone(), pool.clone(), pool.clone(), pool.clone());
+
+    JobRunner::new(pool, JobRunnerConfig::from_env())
+        .handle(RETENTION_PURGE, move |_| {
+            retention_service::purge_job(purge_pool.clone(), storage.clone(), policy.clone())
+        })
+        .handle(CERTIFICATE_EXPIRY_CHECK, move |_| {
+            certificate_expiry_service::check_expiring_certificates(certificate_pool.clone())
+        })
+        .handle(DIARY_REMINDER_SCAN, move |_| {
+            diary_reminder_service::reminder_scan_job(reminder_pool.clone())
+        })
+        .handle(REGISTRATION_EXPIRY, move |_| {
+            registration_expiry_service::expire_registrations(registration_pool.clone())
+        })
+        .schedule(
+            RETENTION_PURGE,
+            RETENTION_PURGE,
+            Schedule::every(purge_interval),
+        )
+        .schedule(
+            CERTIFICATE_EXPIRY_CHECK,
+            CERTIFICATE_EXPIRY_CHECK,
+            cron("0 * * * *"),
+        )
+        .schedule(DIARY_REMINDER_SCAN, DIARY_REMINDER_SCAN, cron("* * * * *"))
+        .schedule(
+            REGISTRATION_EXPIRY,
+            REGISTRATION_EXPIRY,
+            cron("*/15 * * * *"),
+        )
+        .spawn(shutdown)
+}
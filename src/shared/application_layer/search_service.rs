@@ -0,0 +1,83 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::shared::{
+    domain_layer::{
+        api_schema::ErrorResponse,
+        search::{
+            SearchQueryParams, SearchResponse, SearchResultKind, SearchTerms, DEFAULT_SEARCH_LIMIT,
+            MAX_SEARCH_LIMIT,
+        },
+    },
+    infrastructure_layer::search_repository::SearchRepository,
+};
+use crate::user::infrastructure_layer::jwt_repo;
+use crate::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    tag = "search",
+    params(SearchQueryParams),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Matches across properties, landlords, staff and diary events, best first", body = SearchResponse),
+        (status = 400, description = "Empty query or unknown type", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    )
+)]
+pub async fn search(
+    state: web::Data<AppState>,
+    query: web::Query<SearchQueryParams>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match jwt_repo::authenticated_user_id(&req, &state).await {
+        Some(user_id) => user_id,
+        None => return HttpResponse::Unauthorized().json(json!({"error": "Not logged in"})),
+    };
+    let q = query.q.as_deref().unwrap_or("").trim();
+    let terms = match SearchTerms::parse(q) {
+        Some(terms) => terms,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(json!({"error": "q must contain at least one letter or digit"}))
+        }
+    };
+
+    let kinds: Vec<&str> = match &query.types {
+        Some(types) => {
+            let mut kinds = Vec::new();
+            for value in types.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+                match SearchResultKind::parse(value) {
+                    Some(kind) => kinds.push(kind.as_str()),
+                    None => {
+                        return HttpResponse::BadRequest().json(json!({
+                            "error": format!(
+                                "Unknown type {}; use property, landlord, staff or event",
+                                value
+                            )
+                        }))
+                    }
+                }
+            }
+            kinds
+        }
+        None => SearchResultKind::ALL
+            .iter()
+            .map(|kind| kind.as_str())
+            .collect(),
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let repo = SearchRepository::new();
+    match repo.search(&state.db, &terms, &kinds, user_id, limit).await {
+        Ok(results) => HttpResponse::Ok().json(SearchResponse {
+            query: q.to_string(),
+            results,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
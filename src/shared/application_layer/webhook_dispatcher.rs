@@ -0,0 +1,124 @@
+use chrono::{Duration, Utc};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+
+use crate::shared::infrastructure_layer::{
+    field_cipher::KeyRing,
+    webhook_repository::{DueDelivery, WebhookRepository},
+    webhook_sender::{SendFailure, WebhookSender},
+};
+
+const BATCH_SIZE: i64 = 50;
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+/// How long a claimed delivery is reserved for this dispatcher.
+const LEASE_SECONDS: i64 = 120;
+/// Attempts after which a delivery is marked failed: 30s, 1m, 2m, ... up to 6h apart.
+const MAX_ATTEMPTS: i32 = 10;
+const BASE_BACKOFF_SECONDS: i64 = 30;
+const MAX_BACKOFF_SECONDS: i64 = 6 * 60 * 60;
+const DEFAULT_DISABLE_AFTER: i32 = 20;
+
+/// Sends due webhook deliveries until the server stops.
+pub fn spawn_webhook_dispatcher(pool: Pool<Postgres>, key_ring: Arc<KeyRing>) {
+    let disable_after = std::env::var("WEBHOOK_DISABLE_AFTER_FAILURES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_DISABLE_AFTER);
+
+    actix_web::rt::spawn(async move {
+        let sender = match WebhookSender::new() {
+            Ok(sender) => sender,
+            Err(e) => {
+                log::error!("Webhook dispatcher could not start: {}", e);
+                return;
+            }
+        };
+        let repo = WebhookRepository::new();
+        loop {
+            let lease_until = Utc::now() + Duration::seconds(LEASE_SECONDS);
+            match repo.claim_due(&pool, BATCH_SIZE, lease_until).await {
+                Ok(deliveries) => {
+                    let full_batch = deliveries.len() as i64 == BATCH_SIZE;
+                    for delivery in deliveries {
+                        deliver(&pool, &repo, &sender, &key_ring, delivery, disable_after).await;
+                    }
+                    if full_batch {
+                        continue;
+                    }
+                }
+                Err(e) => log::error!("Claiming webhook deliveries failed: {}", e),
+            }
+            actix_web::rt::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+async fn deliver(
+    pool: &Pool<Postgres>,
+    repo: &WebhookRepository,
+    sender: &WebhookSender,
+    key_ring: &KeyRing,
+    delivery: DueDelivery,
+    disable_after: i32,
+) {
+    let result = match key_ring.decrypt(&delivery.secret) {
+        Ok(secret) => {
+            sender
+                .send(
+                    &delivery.url,
+                    delivery.id,
+                    &delivery.event_type,
+                    &secret,
+                    delivery.payload.to_string(),
+                )
+                .await
+        }
+        Err(e) => Err(SendFailure {
+            status: None,
+            message: format!("Secret could not be decrypted: {}", e),
+        }),
+    };
+
+    let recorded = match result {
+        Ok(status) => {
+            repo.record_success(pool, &delivery, i32::from(status))
+                .await
+        }
+        Err(failure) => {
+            let attempt = delivery.attempts + 1;
+            let next_attempt_at = (attempt < MAX_ATTEMPTS).then(|| {
+                let backoff = BASE_BACKOFF_SECONDS
+                    .saturating_mul(2_i64.saturating_pow((attempt - 1) as u32))
+                    .min(MAX_BACKOFF_SECONDS);
+                Utc::now() + Duration::seconds(backoff)
+            });
+            let status = failure.status.map(i32::from);
+            match repo
+                .record_failure(
+                    pool,
+                    &delivery,
+                    status,
+                    &failure.message,
+                    next_attempt_at,
+                    disable_after,
+                )
+                .await
+            {
+                Ok(true) => {
+                    log::warn!(
+                        "Webhook subscription {} disabled after {} failed deliveries",
+                        delivery.subscription_id,
+                        disable_after
+                    );
+                    Ok(())
+                }
+                Ok(false) => Ok(()),
+                Err(e) => Err(e),
+            }
+        }
+    };
+
+    if let Err(e) = recorded {
+        log::error!("Recording webhook delivery {} failed: {}", delivery.id, e);
+    }
+}
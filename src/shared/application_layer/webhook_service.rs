@@ -0,0 +1,446 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use rand::RngCore;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::shared::{
+    domain_layer::{
+        api_schema::ErrorResponse,
+        webhook::{
+            is_internal_host, DeliveryQueryParams, WebhookDelivery, WebhookEventType,
+            WebhookSubscription, WebhookSubscriptionCreated, WebhookSubscriptionPatch,
+            WebhookSubscriptionRequest, MANAGE_WEBHOOKS,
+        },
+    },
+    infrastructure_layer::{
+        branch_repository::BranchRepository,
+        webhook_repository::{CustomErrors, WebhookRepository},
+    },
+};
+use crate::user::application_layer::permission_service;
+use crate::AppState;
+
+const MIN_SECRET_LENGTH: usize = 16;
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 500;
+
+fn error_response(e: CustomErrors) -> HttpResponse {
+    match e {
+        CustomErrors::NotFound => HttpResponse::NotFound().json(json!({"error": e.to_string()})),
+        CustomErrors::ValidationError(_) => {
+            HttpResponse::BadRequest().json(json!({"error": e.to_string()}))
+        }
+        _ => HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    }
+}
+
+fn validate_url(url: &str) -> Result<(), CustomErrors> {
+    let host = match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "https" | "http") => {
+            parsed.host_str().map(str::to_string)
+        }
+        _ => None,
+    };
+    match host {
+        Some(host) if is_internal_host(&host) => Err(CustomErrors::ValidationError(
+            "url must not point at a loopback, private or link-local address".to_string(),
+        )),
+        Some(_) => Ok(()),
+        None => Err(CustomErrors::ValidationError(
+            "url must be an absolute http or https URL".to_string(),
+        )),
+    }
+}
+
+fn validate_event_types(event_types: &[String]) -> Result<(), CustomErrors> {
+    if event_types.is_empty() {
+        return Err(CustomErrors::ValidationError(
+            "event_types must not be empty".to_string(),
+        ));
+    }
+    match event_types
+        .iter()
+        .find(|t| WebhookEventType::parse(t).is_none())
+    {
+        Some(unknown) => Err(CustomErrors::ValidationError(format!(
+            "Unknown event type {}",
+            unknown
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/event-types",
+    tag = "webhooks",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Event types a subscription can ask for", body = [WebhookEventType]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn get_event_types(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(response) =
+        permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        return response;
+    }
+    HttpResponse::Ok().json(WebhookEventType::ALL)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Every subscription of the organisations the caller can see", body = [WebhookSubscription]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn get_all_webhooks(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(response) =
+        permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        return response;
+    }
+    let repo = WebhookRepository::new();
+    match repo.get_all(&state.db).await {
+        Ok(subscriptions) => HttpResponse::Ok().json(subscriptions),
+        Err(e) => error_response(e),
+    }
+}
+
+/// The signing secret is only ever returned here and when rotated.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 201, description = "The subscription with its signing secret, shown only this once", body = WebhookSubscriptionCreated),
+        (status = 400, description = "Invalid URL, event type or secret", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn create_webhook(
+    state: web::Data<AppState>,
+    body: web::Json<WebhookSubscriptionRequest>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let request = body.into_inner();
+    if let Err(e) = validate_url(&request.url).and(validate_event_types(&request.event_types)) {
+        return error_response(e);
+    }
+    let secret = match request.secret.clone() {
+        Some(secret) if secret.len() < MIN_SECRET_LENGTH => {
+            return error_response(CustomErrors::ValidationError(format!(
+                "secret must be at least {} characters",
+                MIN_SECRET_LENGTH
+            )))
+        }
+        Some(secret) => secret,
+        None => generate_secret(),
+    };
+    let organisation_id = match BranchRepository::new()
+        .organisation_of(&state.db, user_id)
+        .await
+    {
+        Ok(organisation_id) => organisation_id,
+        Err(e) => return HttpResponse::InternalServerError().json(json!({"error": e.to_string()})),
+    };
+
+    let repo = WebhookRepository::new();
+    match repo
+        .create(
+            &state.db,
+            &state.key_ring,
+            &request,
+            &secret,
+            user_id,
+            organisation_id,
+        )
+        .await
+    {
+        Ok(subscription) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "webhook_created",
+                format!(
+                    "Created webhook {} for {}",
+                    subscription.id, subscription.url
+                ),
+            )
+            .await;
+            HttpResponse::Created().json(WebhookSubscriptionCreated {
+                subscription,
+                secret,
+            })
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(("webhook_id" = Uuid, Path, description = "Webhook subscription id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The subscription", body = WebhookSubscription),
+        (status = 404, description = "Subscription not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn get_webhook(
+    state: web::Data<AppState>,
+    webhook_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(response) =
+        permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        return response;
+    }
+    let repo = WebhookRepository::new();
+    match repo.get_by_id(&state.db, webhook_id.into_inner()).await {
+        Ok(subscription) => HttpResponse::Ok().json(subscription),
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(("webhook_id" = Uuid, Path, description = "Webhook subscription id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The updated subscription", body = WebhookSubscription),
+        (status = 400, description = "Invalid URL or event type", body = ErrorResponse),
+        (status = 404, description = "Subscription not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn update_webhook(
+    state: web::Data<AppState>,
+    webhook_id: web::Path<Uuid>,
+    body: web::Json<WebhookSubscriptionPatch>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let patch = body.into_inner();
+    if let Some(url) = &patch.url {
+        if let Err(e) = validate_url(url) {
+            return error_response(e);
+        }
+    }
+    if let Some(event_types) = &patch.event_types {
+        if let Err(e) = validate_event_types(event_types) {
+            return error_response(e);
+        }
+    }
+
+    let repo = WebhookRepository::new();
+    match repo.update(&state.db, webhook_id.into_inner(), patch).await {
+        Ok(subscription) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "webhook_updated",
+                format!("Updated webhook {}", subscription.id),
+            )
+            .await;
+            HttpResponse::Ok().json(subscription)
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/{webhook_id}/secret",
+    tag = "webhooks",
+    params(("webhook_id" = Uuid, Path, description = "Webhook subscription id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The subscription with its new signing secret", body = WebhookSubscriptionCreated),
+        (status = 404, description = "Subscription not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn rotate_webhook_secret(
+    state: web::Data<AppState>,
+    webhook_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let secret = generate_secret();
+    let repo = WebhookRepository::new();
+    match repo
+        .rotate_secret(&state.db, &state.key_ring, webhook_id.into_inner(), &secret)
+        .await
+    {
+        Ok(subscription) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "webhook_secret_rotated",
+                format!("Rotated the secret of webhook {}", subscription.id),
+            )
+            .await;
+            HttpResponse::Ok().json(WebhookSubscriptionCreated {
+                subscription,
+                secret,
+            })
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(("webhook_id" = Uuid, Path, description = "Webhook subscription id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 204, description = "Subscription deleted"),
+        (status = 404, description = "Subscription not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn delete_webhook(
+    state: web::Data<AppState>,
+    webhook_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let webhook_id = webhook_id.into_inner();
+    let repo = WebhookRepository::new();
+    match repo.delete(&state.db, webhook_id).await {
+        Ok(()) => {
+            permission_service::audit(
+                &req,
+                &state,
+                user_id,
+                "webhook_deleted",
+                format!("Deleted webhook {}", webhook_id),
+            )
+            .await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{webhook_id}/deliveries",
+    tag = "webhooks",
+    params(("webhook_id" = Uuid, Path, description = "Webhook subscription id"), DeliveryQueryParams),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Most recent deliveries first", body = [WebhookDelivery]),
+        (status = 404, description = "Subscription not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn get_deliveries(
+    state: web::Data<AppState>,
+    webhook_id: web::Path<Uuid>,
+    query: web::Query<DeliveryQueryParams>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(response) =
+        permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        return response;
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    let repo = WebhookRepository::new();
+    match repo
+        .get_deliveries(
+            &state.db,
+            webhook_id.into_inner(),
+            query.status.as_deref(),
+            limit,
+        )
+        .await
+    {
+        Ok(deliveries) => HttpResponse::Ok().json(deliveries),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Sends a logged delivery again as a new delivery.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/{webhook_id}/deliveries/{delivery_id}/replay",
+    tag = "webhooks",
+    params(("webhook_id" = Uuid, Path, description = "Webhook subscription id"), ("delivery_id" = Uuid, Path, description = "Delivery to send again")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 202, description = "The new delivery", body = WebhookDelivery),
+        (status = 404, description = "Delivery not found", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Missing permission `manage_webhooks`", body = ErrorResponse),
+    )
+)]
+pub async fn replay_delivery(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, Uuid)>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(response) =
+        permission_service::require_permission(&req, &state, MANAGE_WEBHOOKS).await
+    {
+        return response;
+    }
+    let (webhook_id, delivery_id) = path.into_inner();
+    let repo = WebhookRepository::new();
+    match repo.replay(&state.db, webhook_id, delivery_id).await {
+        Ok(delivery) => HttpResponse::Accepted().json(delivery),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::consumer_health::ConsumerStatus;
+use crate::landlord::domain_layer::landlord_data_export::RetentionHold;
+use crate::user::domain_layer::user::StaffUser;
+
+// Bodies the handlers build inline with `json!`, and headers read straight
+// off the request, spelled out for the API document. Keep them in step with
+// the handlers.
+
+#[derive(IntoParams)]
+#[into_params(names("Idempotency-Key"), parameter_in = Header)]
+pub struct IdempotencyHeader(
+    /// Repeats with the same key replay the first response instead of
+    /// running the request again.
+    Option<String>,
+);
+
+#[derive(IntoParams)]
+#[into_params(names("If-Match"), parameter_in = Header)]
+pub struct IfMatchHeader(
+    /// ETag of the version being replaced, or `*` to overwrite whatever is
+    /// there.
+    String,
+);
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// Longer explanation, where there is one.
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MessageResponse {
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct StatusMessageResponse {
+    pub status: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LoginResponse {
+    pub message: String,
+    pub user: StaffUser,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RefreshTokenRequest {
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: String,
+}
+
+#[derive(ToSchema)]
+pub struct PhotoUpload {
+    /// Up to ten PNG, JPEG, WebP, GIF or BMP files of up to 10 MB each,
+    /// under any field name. Fields that are not files are ignored.
+    #[schema(value_type = Vec<String>, format = Binary)]
+    pub images: Vec<Vec<u8>>,
+}
+
+#[derive(ToSchema)]
+pub struct CertificateDocumentUpload {
+    /// The certificate as a PDF, JPEG, PNG or WebP file of up to 10 MB.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    /// When renewing, the start date of the new certificate.
+    pub start_date: Option<chrono::NaiveDate>,
+    /// When renewing, the expiry date of the new certificate.
+    pub expiry_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RetentionHoldResponse {
+    pub error: String,
+    pub message: String,
+    pub hold: RetentionHold,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LivenessResponse {
+    /// Always `ok`.
+    pub status: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// `ready` or `not_ready`.
+    pub status: String,
+    /// `ok` or `unavailable`.
+    pub database: String,
+    pub consumers: ConsumerStatus,
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Permission to see and set up every organisation, not just the caller's own.
+pub const MANAGE_ORGANISATIONS: &str = "manage_organisations";
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Organisation {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Branch {
+    pub id: Uuid,
+    pub organisation_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A new organisation and its first branch.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct OrganisationRequest {
+    pub name: String,
+    pub branch_name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganisationCreated {
+    pub organisation: Organisation,
+    pub branch: Branch,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BranchRequest {
+    pub name: String,
+    /// Defaults to the caller's organisation. Another organisation needs
+    /// `manage_organisations`.
+    pub organisation_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BranchTransferRequest {
+    pub branch_id: Uuid,
+    /// Landlord transfers only: move the landlord's properties too.
+    #[serde(default)]
+    pub include_properties: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct StaffBranch {
+    pub staff_id: Uuid,
+    pub branch_id: Uuid,
+    pub granted_by: Option<Uuid>,
+    pub granted_at: DateTime<Utc>,
+}
+
+/// Records that belong to a branch and can be moved to another.
+#[derive(Clone, Copy, Debug)]
+pub enum BranchRecord {
+    StaffUser,
+    Landlord,
+    Property,
+}
+
+impl BranchRecord {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BranchRecord::StaffUser => "user",
+            BranchRecord::Landlord => "landlord",
+            BranchRecord::Property => "property",
+        }
+    }
+}
+
+/// The branches a caller's queries are limited to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BranchScope {
+    /// Where records the caller creates go; `None` for the default branch.
+    pub home_branch_id: Option<Uuid>,
+    /// `None` for every branch.
+    pub branch_ids: Option<Vec<Uuid>>,
+}
+
+impl BranchScope {
+    /// Background work, command line tools and sign-in lookups.
+    pub fn everything() -> Self {
+        BranchScope {
+            home_branch_id: None,
+            branch_ids: None,
+        }
+    }
+
+    /// Callers who are not logged in.
+    pub fn nothing() -> Self {
+        BranchScope {
+            home_branch_id: None,
+            branch_ids: Some(Vec::new()),
+        }
+    }
+
+    pub fn includes(&self, branch_id: Uuid) -> bool {
+        match &self.branch_ids {
+            Some(branch_ids) => branch_ids.contains(&branch_id),
+            None => true,
+        }
+    }
+
+    /// The value of the `app.branch_ids` setting the row-level security
+    /// policies read.
+    pub fn branch_ids_setting(&self) -> String {
+        match &self.branch_ids {
+            Some(branch_ids) => format!(
+                "{{{}}}",
+                branch_ids
+                    .iter()
+                    .map(Uuid::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            None => "*".to_string(),
+        }
+    }
+
+    pub fn home_branch_setting(&self) -> String {
+        self.home_branch_id
+            .map(|branch_id| branch_id.to_string())
+            .unwrap_or_default()
+    }
+}
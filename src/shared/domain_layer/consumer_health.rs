@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsumerState {
+    /// No brokers are configured, so there is nothing to consume.
+    Disabled,
+    Starting,
+    Running,
+    /// The consumer failed and is waiting to be recreated.
+    Restarting,
+    Stopped,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ConsumerStatus {
+    pub state: ConsumerState,
+    pub topics: Vec<String>,
+    pub last_error: Option<String>,
+    pub last_message_at: Option<DateTime<Utc>>,
+    pub restarts: u32,
+    pub dead_lettered: u64,
+}
+
+/// Shared view of the Kafka consumer, updated by the consumer task and read by
+/// the readiness check.
+pub struct ConsumerHealth {
+    status: RwLock<ConsumerStatus>,
+}
+
+impl ConsumerHealth {
+    pub fn new() -> Self {
+        ConsumerHealth {
+            status: RwLock::new(ConsumerStatus {
+                state: ConsumerState::Starting,
+                topics: Vec::new(),
+                last_error: None,
+                last_message_at: None,
+                restarts: 0,
+                dead_lettered: 0,
+            }),
+        }
+    }
+
+    pub fn snapshot(&self) -> ConsumerStatus {
+        match self.status.read() {
+            Ok(status) => status.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// A disabled consumer does not hold the service back.
+    pub fn is_ready(&self) -> bool {
+        matches!(
+            self.snapshot().state,
+            ConsumerState::Running | ConsumerState::Disabled
+        )
+    }
+
+    pub fn set_topics(&self, topics: Vec<String>) {
+        self.update(|status| status.topics = topics);
+    }
+
+    pub fn set_state(&self, state: ConsumerState) {
+        self.update(|status| status.state = state);
+    }
+
+    pub fn record_message(&self) {
+        self.update(|status| status.last_message_at = Some(Utc::now()));
+    }
+
+    pub fn record_error(&self, error: &str) {
+        self.update(|status| status.last_error = Some(error.to_string()));
+    }
+
+    pub fn record_restart(&self, error: &str) {
+        self.update(|status| {
+            status.state = ConsumerState::Restarting;
+            status.last_error = Some(error.to_string());
+            status.restarts += 1;
+        });
+    }
+
+    pub fn record_dead_letter(&self) {
+        self.update(|status| status.dead_lettered += 1);
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ConsumerStatus)) {
+        match self.status.write() {
+            Ok(mut status) => change(&mut status),
+            Err(poisoned) => change(&mut poisoned.into_inner()),
+        }
+    }
+}
+
+impl Default for ConsumerHealth {
+    fn default() -> Self {
+        ConsumerHealth::new()
+    }
+}
@@ -0,0 +1,229 @@
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
+use derive_more::Display;
+
+#[derive(Debug, Display, PartialEq)]
+#[display(fmt = "Invalid cron expression: {}", _0)]
+pub struct CronParseError(pub String);
+
+/// A five-field cron expression (`minute hour day-of-month month day-of-week`)
+/// evaluated in UTC. Fields accept `*`, numbers, ranges (`1-5`), steps
+/// (`*/15`, `0-30/10`) and comma-separated lists. Day of week runs from 0
+/// (Sunday) to 6; 7 is also Sunday.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Cron matches either day field when both are restricted. A field
+    /// starting with `*`, such as `*/2`, counts as unrestricted.
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+/// Give up looking for a match after this long, e.g. for `0 0 30 2 *`.
+const SEARCH_LIMIT_DAYS: i64 = 5 * 366;
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("bad step in {}", part))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    start.parse().map_err(|_| format!("bad range {}", range))?,
+                    end.parse().map_err(|_| format!("bad range {}", range))?,
+                ),
+                None => {
+                    let value = range.parse().map_err(|_| format!("bad value {}", range))?;
+                    // `5/10` means every 10 starting at 5.
+                    (value, if part.contains('/') { max } else { value })
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("{} is outside {}-{}", part, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, CronParseError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(CronParseError(format!(
+                "{} (expected 5 fields, got {})",
+                expression,
+                fields.len()
+            )));
+        }
+        let error = |e: String| CronParseError(format!("{} ({})", expression, e));
+        let mut days_of_week = parse_field(fields[4], 0, 7).map_err(error)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(CronSchedule {
+            expression: fields.join(" "),
+            minutes: parse_field(fields[0], 0, 59).map_err(error)?,
+            hours: parse_field(fields[1], 0, 23).map_err(error)?,
+            days_of_month: parse_field(fields[2], 1, 31).map_err(error)?,
+            months: parse_field(fields[3], 1, 12).map_err(error)?,
+            days_of_week,
+            any_day_of_month: fields[2].starts_with('*'),
+            any_day_of_week: fields[4].starts_with('*'),
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = self.days_of_month & (1 << time.day()) != 0;
+        let day_of_week = self.days_of_week & (1 << time.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+
+    /// The first matching minute strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = after.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let limit = after + Duration::days(SEARCH_LIMIT_DAYS);
+        while time <= limit {
+            if self.months & (1 << time.month()) == 0 || !self.day_matches(time) {
+                time = time.duration_trunc(Duration::days(1)).ok()? + Duration::days(1);
+            } else if self.hours & (1 << time.hour()) == 0 {
+                time = time.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+            } else if self.minutes & (1 << time.minute()) == 0 {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn next(expression: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        CronSchedule::parse(expression).unwrap().next_after(after)
+    }
+
+    #[test]
+    fn parse_rejects_malformed_expressions() {
+        for expression in [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "30-10 * * * *",
+            "a * * * *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{} should not parse",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn parse_normalises_whitespace() {
+        let schedule = CronSchedule::parse("  0  9 *\t* 1-5 ").unwrap();
+        assert_eq!(schedule.expression(), "0 9 * * 1-5");
+    }
+
+    #[test]
+    fn next_after_is_strictly_after() {
+        let start = at(2026, 10, 19, 9, 0);
+        assert_eq!(next("0 9 * * *", start), Some(at(2026, 10, 20, 9, 0)));
+        let mid_minute = start + Duration::seconds(30);
+        assert_eq!(next("* * * * *", mid_minute), Some(at(2026, 10, 19, 9, 1)));
+    }
+
+    #[test]
+    fn steps_and_ranges() {
+        let start = at(2026, 10, 19, 9, 7);
+        assert_eq!(next("*/15 * * * *", start), Some(at(2026, 10, 19, 9, 15)));
+        assert_eq!(
+            next("0-30/10 * * * *", start),
+            Some(at(2026, 10, 19, 9, 10))
+        );
+        assert_eq!(
+            next("0-30/10 * * * *", at(2026, 10, 19, 9, 31)),
+            Some(at(2026, 10, 19, 10, 0))
+        );
+        // Every 10 minutes starting at 5.
+        assert_eq!(next("5/10 * * * *", start), Some(at(2026, 10, 19, 9, 15)));
+        assert_eq!(next("0 8,17 * * *", start), Some(at(2026, 10, 19, 17, 0)));
+    }
+
+    #[test]
+    fn seven_is_sunday() {
+        // 2026-10-19 is a Monday.
+        let start = at(2026, 10, 19, 0, 0);
+        assert_eq!(next("0 0 * * 7", start), Some(at(2026, 10, 25, 0, 0)));
+        assert_eq!(next("0 0 * * 0", start), next("0 0 * * 7", start));
+        assert_eq!(next("0 0 * * 5-7", start), Some(at(2026, 10, 23, 0, 0)));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 1st of the month or a Monday.
+        let start = at(2026, 10, 19, 12, 0);
+        assert_eq!(next("0 0 1 * 1", start), Some(at(2026, 10, 26, 0, 0)));
+        assert_eq!(
+            next("0 0 1 * 1", at(2026, 10, 27, 0, 0)),
+            Some(at(2026, 11, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn starred_day_fields_match_both() {
+        // Odd days of the month that are also Mondays.
+        let start = at(2026, 10, 19, 12, 0);
+        assert_eq!(next("0 9 */2 * 1", start), Some(at(2026, 11, 9, 9, 0)));
+        // The 1st when it is a Sunday: `*/7` is 0 and 7.
+        assert_eq!(next("0 9 1 * */7", start), Some(at(2026, 11, 1, 9, 0)));
+    }
+
+    #[test]
+    fn leap_days_and_impossible_dates() {
+        let start = at(2026, 10, 19, 0, 0);
+        assert_eq!(next("0 0 29 2 *", start), Some(at(2028, 2, 29, 0, 0)));
+        assert_eq!(next("0 0 31 12 *", start), Some(at(2026, 12, 31, 0, 0)));
+        assert_eq!(next("0 0 30 2 *", start), None);
+        assert_eq!(next("0 0 31 4 *", start), None);
+    }
+}
@@ -0,0 +1,77 @@
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// The emails the notification service knows how to render. Each has a
+/// subject, plain text and HTML template under `templates/email`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmailTemplate {
+    EventReminder,
+    AssignmentChanged,
+    BookingConfirmation,
+}
+
+impl EmailTemplate {
+    pub const ALL: [EmailTemplate; 3] = [
+        EmailTemplate::EventReminder,
+        EmailTemplate::AssignmentChanged,
+        EmailTemplate::BookingConfirmation,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailTemplate::EventReminder => "event_reminder",
+            EmailTemplate::AssignmentChanged => "assignment_changed",
+            EmailTemplate::BookingConfirmation => "booking_confirmation",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<EmailTemplate> {
+        EmailTemplate::ALL
+            .into_iter()
+            .find(|template| template.as_str() == value)
+    }
+}
+
+/// Who an email goes to. Staff are looked up when the email is sent and
+/// skipped unless they have email notifications switched on; clients are
+/// emailed at the address they gave.
+#[derive(Clone, Debug)]
+pub enum EmailRecipient {
+    Staff(Uuid),
+    Address(String),
+}
+
+/// An email waiting to be queued.
+#[derive(Clone, Debug)]
+pub struct QueuedEmail {
+    pub template: EmailTemplate,
+    pub recipient: EmailRecipient,
+    pub context: JsonValue,
+    /// Emails with a key already in the queue are dropped.
+    pub dedupe_key: Option<String>,
+}
+
+impl QueuedEmail {
+    pub fn new(template: EmailTemplate, recipient: EmailRecipient, context: JsonValue) -> Self {
+        QueuedEmail {
+            template,
+            recipient,
+            context,
+            dedupe_key: None,
+        }
+    }
+
+    pub fn dedupe_key(mut self, key: impl Into<String>) -> Self {
+        self.dedupe_key = Some(key.into());
+        self
+    }
+}
+
+/// A rendered email ready for a transport.
+#[derive(Clone, Debug)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// How long a stored response is replayed before the key may be reused.
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+pub struct IdempotencyRecord {
+    pub idempotency_key: String,
+    pub request_method: String,
+    pub request_path: String,
+    pub principal: String,
+    pub request_hash: String,
+    pub status_code: Option<i16>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A key is only meaningful for the endpoint it was sent to, and only to
+/// whoever sent it.
+#[derive(Clone, Debug)]
+pub struct IdempotencyScope {
+    pub key: String,
+    pub method: String,
+    pub path: String,
+    /// `user:{id}` for a logged-in caller, otherwise `anonymous`.
+    pub principal: String,
+}
+
+/// Outcome of trying to claim a key before running the handler.
+pub enum IdempotencyClaim {
+    /// First time this key is seen; the request should be processed.
+    Claimed,
+    /// A finished response exists for the same request and should be replayed.
+    Replay(IdempotencyRecord),
+    /// The key was already used with a different request body.
+    Mismatch,
+    /// The key is in use by someone else.
+    OtherPrincipal,
+    /// Another request with the same key has not finished yet.
+    InProgress,
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::JsonValue;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::cron_schedule::{CronParseError, CronSchedule};
+use utoipa::{IntoParams, ToSchema};
+
+/// Permission needed to view and manage background jobs.
+pub const MANAGE_JOBS: &str = "manage_jobs";
+
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: JsonValue,
+    /// `pending`, `running`, `succeeded`, `failed` or `cancelled`.
+    pub status: String,
+    pub unique_key: Option<String>,
+    pub schedule_name: Option<String>,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_by: Option<String>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub result: Option<JsonValue>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// A job to be queued.
+#[derive(Clone, Debug)]
+pub struct NewJob {
+    pub kind: String,
+    pub payload: JsonValue,
+    /// While a pending or running job holds this key, jobs with the same key
+    /// are not queued.
+    pub unique_key: Option<String>,
+    pub schedule_name: Option<String>,
+    pub run_at: DateTime<Utc>,
+    pub max_attempts: i32,
+}
+
+impl NewJob {
+    pub fn new(kind: &str, payload: JsonValue) -> Self {
+        NewJob {
+            kind: kind.to_string(),
+            payload,
+            unique_key: None,
+            schedule_name: None,
+            run_at: Utc::now(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    pub fn unique_key(mut self, key: impl Into<String>) -> Self {
+        self.unique_key = Some(key.into());
+        self
+    }
+
+    pub fn run_at(mut self, run_at: DateTime<Utc>) -> Self {
+        self.run_at = run_at;
+        self
+    }
+
+    pub fn max_attempts(mut self, max_attempts: i32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+}
+
+/// When a scheduled job runs: a cron expression or a fixed interval.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Schedule {
+    Cron(CronSchedule),
+    Every(Duration),
+}
+
+impl Schedule {
+    /// Parses a cron expression or `every <seconds>s`.
+    pub fn parse(value: &str) -> Result<Self, CronParseError> {
+        match value.trim().strip_prefix("every ") {
+            Some(seconds) => seconds
+                .trim()
+                .trim_end_matches('s')
+                .parse::<i64>()
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .map(|seconds| Schedule::Every(Duration::seconds(seconds)))
+                .ok_or_else(|| CronParseError(format!("{} (expected every <seconds>s)", value))),
+            None => CronSchedule::parse(value).map(Schedule::Cron),
+        }
+    }
+
+    pub fn every(interval: std::time::Duration) -> Self {
+        Schedule::Every(Duration::seconds(interval.as_secs().max(1) as i64))
+    }
+
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Cron(cron) => cron.next_after(after),
+            Schedule::Every(interval) => Some(after + *interval),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        match self {
+            Schedule::Cron(cron) => cron.expression().to_string(),
+            Schedule::Every(interval) => format!("every {}s", interval.num_seconds()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+pub struct JobSchedule {
+    pub name: String,
+    pub kind: String,
+    pub schedule: String,
+    pub payload: JsonValue,
+    pub enabled: bool,
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobQueryParams {
+    pub status: Option<String>,
+    pub kind: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct JobSchedulePatch {
+    pub enabled: bool,
+}
@@ -0,0 +1,69 @@
+use serde_json::Value;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::shared::domain_layer::branch::BranchScope;
+
+/// SSE event name of the pop-up sent to staff involved in a diary event.
+pub const NOTIFICATION_EVENT: &str = "notification";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Audience {
+    /// Everyone who can see the branch.
+    Branch(Uuid),
+    Users(Vec<Uuid>),
+}
+
+impl Audience {
+    pub fn includes(&self, user_id: Uuid, scope: &BranchScope) -> bool {
+        match self {
+            Audience::Branch(branch_id) => scope.includes(*branch_id),
+            Audience::Users(users) => users.contains(&user_id),
+        }
+    }
+}
+
+/// Where an outbox event sits in the live stream: by writing transaction,
+/// then by sequence within it. Written as `<xact_id>-<sequence>`, the SSE
+/// event id clients resume from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StreamPosition {
+    pub xact_id: i64,
+    pub sequence: i64,
+}
+
+impl StreamPosition {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (xact_id, sequence) = value.split_once('-')?;
+        Some(StreamPosition {
+            xact_id: xact_id.parse().ok()?,
+            sequence: sequence.parse().ok()?,
+        })
+    }
+}
+
+impl fmt::Display for StreamPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.xact_id, self.sequence)
+    }
+}
+
+/// One message on the live channel, at the stream position of the outbox
+/// event it came from.
+#[derive(Clone, Debug)]
+pub struct LiveUpdate {
+    pub position: StreamPosition,
+    pub event: String,
+    pub data: Value,
+    pub audience: Audience,
+}
+
+impl LiveUpdate {
+    /// The update as a `text/event-stream` frame.
+    pub fn to_frame(&self) -> String {
+        format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            self.position, self.event, self.data
+        )
+    }
+}
@@ -0,0 +1,13 @@
+pub mod idempotency_key;
+pub mod retention_policy;
+pub mod sensitive_data;
+pub mod outbox_event;
+pub mod consumer_health;
+pub mod webhook;
+pub mod live_update;
+pub mod email;
+pub mod cron_schedule;
+pub mod job;
+pub mod search;
+pub mod api_schema;
+pub mod branch;
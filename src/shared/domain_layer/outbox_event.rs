@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use prost::Message;
+use serde::Serialize;
+use sqlx::types::JsonValue;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const PROPERTY_EVENTS_TOPIC: &str = "property-events";
+pub const LANDLORD_EVENTS_TOPIC: &str = "landlord-events";
+pub const DIARY_EVENTS_TOPIC: &str = "diary-events";
+pub const USER_EVENTS_TOPIC: &str = "user-events";
+
+/// Wire format of every message on the domain event topics. `payload` holds
+/// the encoded event named by `event_type`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DomainEventEnvelope {
+    #[prost(string, tag = "1")]
+    pub event_id: String,
+    #[prost(string, tag = "2")]
+    pub event_type: String,
+    #[prost(string, tag = "3")]
+    pub aggregate_type: String,
+    #[prost(string, tag = "4")]
+    pub aggregate_id: String,
+    #[prost(int64, tag = "5")]
+    pub occurred_at_micros: i64,
+    #[prost(bytes = "vec", tag = "6")]
+    pub payload: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct PropertyStatusChanged {
+    #[prost(string, tag = "1")]
+    pub property_id: String,
+    /// Empty when the property was just created.
+    #[prost(string, tag = "2")]
+    pub previous_status: String,
+    #[prost(string, tag = "3")]
+    pub status: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct LandlordRegistrationCompleted {
+    #[prost(string, tag = "1")]
+    pub landlord_id: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct DiaryEventCreated {
+    #[prost(string, tag = "1")]
+    pub event_id: String,
+    #[prost(string, tag = "2")]
+    pub event_type: String,
+    #[prost(string, tag = "3")]
+    pub date: String,
+    #[prost(string, tag = "4")]
+    pub created_by: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct UserSuspended {
+    #[prost(string, tag = "1")]
+    pub user_id: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct LandlordCreated {
+    #[prost(string, tag = "1")]
+    pub landlord_id: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct DiaryEventUpdated {
+    #[prost(string, tag = "1")]
+    pub event_id: String,
+    #[prost(string, tag = "2")]
+    pub event_type: String,
+    #[prost(string, tag = "3")]
+    pub date: String,
+    #[prost(string, tag = "4")]
+    pub created_by: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct DiaryEventDeleted {
+    #[prost(string, tag = "1")]
+    pub event_id: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize)]
+pub struct CertificateExpiring {
+    #[prost(string, tag = "1")]
+    pub certificate_id: String,
+    #[prost(string, tag = "2")]
+    pub property_id: String,
+    #[prost(string, tag = "3")]
+    pub certificate_type: String,
+    #[prost(string, tag = "4")]
+    pub expiry_date: String,
+    /// Negative once the certificate has expired.
+    #[prost(int64, tag = "5")]
+    pub days_remaining: i64,
+}
+
+/// A domain event waiting in the outbox to be published.
+#[derive(Clone, Debug, Serialize, FromRow)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub topic: String,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub payload: Vec<u8>,
+    /// The event as JSON, for consumers that do not speak protobuf.
+    pub data: JsonValue,
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+impl OutboxEvent {
+    /// Wraps `event` in an envelope ready to be stored and published as is.
+    pub fn new<M: Message + Serialize>(
+        topic: &str,
+        aggregate_type: &str,
+        aggregate_id: Uuid,
+        event_type: &str,
+        event: &M,
+    ) -> Self {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let envelope = DomainEventEnvelope {
+            event_id: id.to_string(),
+            event_type: event_type.to_string(),
+            aggregate_type: aggregate_type.to_string(),
+            aggregate_id: aggregate_id.to_string(),
+            occurred_at_micros: now.timestamp_micros(),
+            payload: event.encode_to_vec(),
+        };
+        OutboxEvent {
+            id,
+            topic: topic.to_string(),
+            aggregate_type: aggregate_type.to_string(),
+            aggregate_id,
+            event_type: event_type.to_string(),
+            payload: envelope.encode_to_vec(),
+            data: serde_json::to_value(event).unwrap_or_default(),
+            created_at: now,
+            published_at: None,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: now,
+        }
+    }
+}
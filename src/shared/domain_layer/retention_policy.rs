@@ -0,0 +1,52 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Six years, the longest period letting records have to be kept for HMRC.
+pub const DEFAULT_RETENTION_DAYS: i64 = 6 * 365;
+pub const DEFAULT_PURGE_INTERVAL_HOURS: u64 = 24;
+
+/// How long soft-deleted rows are kept before they are purged for good.
+#[derive(Clone, Debug)]
+pub struct RetentionPolicy {
+    pub retention_period: Duration,
+    pub purge_interval: std::time::Duration,
+}
+
+impl RetentionPolicy {
+    /// Reads `RETENTION_PERIOD_DAYS` and `RETENTION_PURGE_INTERVAL_HOURS`,
+    /// falling back to the defaults when unset or invalid.
+    pub fn from_env() -> Self {
+        let days = std::env::var("RETENTION_PERIOD_DAYS")
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|days| *days >= 0)
+            .unwrap_or(DEFAULT_RETENTION_DAYS);
+        let hours = std::env::var("RETENTION_PURGE_INTERVAL_HOURS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_PURGE_INTERVAL_HOURS);
+
+        RetentionPolicy {
+            retention_period: Duration::days(days),
+            purge_interval: std::time::Duration::from_secs(hours * 60 * 60),
+        }
+    }
+
+    /// Rows deleted before this instant are due for purging.
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - self.retention_period
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PurgeSummary {
+    pub events: u64,
+    pub properties: u64,
+    pub landlords: u64,
+    pub staff: u64,
+    /// Rows that were due but could not be removed, e.g. still referenced elsewhere.
+    pub skipped: u64,
+    /// Stored documents and photos deleted with the rows they belonged to.
+    pub files: u64,
+}
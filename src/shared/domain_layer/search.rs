@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchResultKind {
+    Property,
+    Landlord,
+    Staff,
+    Event,
+}
+
+impl SearchResultKind {
+    pub const ALL: [SearchResultKind; 4] = [
+        SearchResultKind::Property,
+        SearchResultKind::Landlord,
+        SearchResultKind::Staff,
+        SearchResultKind::Event,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchResultKind::Property => "property",
+            SearchResultKind::Landlord => "landlord",
+            SearchResultKind::Staff => "staff",
+            SearchResultKind::Event => "event",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+pub struct SearchResult {
+    /// `property`, `landlord`, `staff` or `event`.
+    pub kind: String,
+    pub id: Uuid,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub rank: f32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResponse {
+    pub query: String,
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQueryParams {
+    pub q: Option<String>,
+    /// Comma-separated kinds to search; all of them when left out.
+    pub types: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// A search box entry turned into what the search query needs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchTerms {
+    /// Every word as a prefix, all of which must match, e.g. `mill:* & lane:*`.
+    pub tsquery: String,
+    /// Set when the entry could be the start of a postcode, without spaces.
+    pub postcode_prefix: Option<String>,
+    /// Set when the entry looks like a phone number, digits only.
+    pub phone_prefix: Option<String>,
+}
+
+const MIN_PHONE_DIGITS: usize = 4;
+
+/// The upper bound of the strings starting with `prefix`: the prefix with its
+/// last character bumped, e.g. `07700` gives `07701`. Only for the
+/// non-empty ASCII prefixes of [`SearchTerms`].
+pub fn prefix_end(prefix: &str) -> String {
+    let mut end = prefix.to_string();
+    if let Some(last) = end.pop() {
+        end.push((last as u8 + 1) as char);
+    }
+    end
+}
+
+impl SearchTerms {
+    /// `None` when the entry has nothing to search for.
+    pub fn parse(query: &str) -> Option<Self> {
+        let words: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| format!("{}:*", word.to_lowercase()))
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+
+        let compact: String = query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_uppercase();
+        let postcode_prefix = (compact.len() >= 2
+            && compact.len() <= 7
+            && compact.chars().all(|c| c.is_ascii_alphanumeric())
+            && compact.starts_with(|c: char| c.is_ascii_alphabetic())
+            && compact.chars().any(|c| c.is_ascii_digit()))
+        .then_some(compact);
+
+        let digits: String = query.chars().filter(|c| c.is_ascii_digit()).collect();
+        let phone_prefix = (digits.len() >= MIN_PHONE_DIGITS
+            && query
+                .chars()
+                .all(|c| c.is_ascii_digit() || " +-()".contains(c)))
+        .then_some(digits);
+
+        Some(SearchTerms {
+            tsquery: words.join(" & "),
+            postcode_prefix,
+            phone_prefix,
+        })
+    }
+}
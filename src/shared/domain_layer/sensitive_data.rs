@@ -0,0 +1,15 @@
+/// Staff permission needed to see unmasked bank details and tax references.
+pub const REVEAL_SENSITIVE_DATA: &str = "reveal_sensitive_data";
+
+/// Masks all but the last `visible` characters, e.g. `****1234`. The mask is a
+/// fixed width so it does not give away the length of the value.
+pub fn mask(value: &str, visible: usize) -> String {
+    let chars: Vec<char> = value.chars().filter(|c| c.is_alphanumeric()).collect();
+    let shown = visible.min(chars.len() / 2);
+    let tail: String = chars[chars.len() - shown..].iter().collect();
+    format!("****{}", tail)
+}
+
+pub fn mask_opt(value: Option<&str>, visible: usize) -> Option<String> {
+    value.map(|value| mask(value, visible))
+}
@@ -0,0 +1,187 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::JsonValue;
+use sqlx::FromRow;
+use std::net::IpAddr;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Permission needed to manage webhook subscriptions.
+pub const MANAGE_WEBHOOKS: &str = "manage_webhooks";
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const DELIVERY_ID_HEADER: &str = "X-Webhook-Id";
+pub const EVENT_TYPE_HEADER: &str = "X-Webhook-Event";
+
+/// Whether `ip` is on this machine or a private network, where webhooks are
+/// never sent so subscriptions cannot reach internal services.
+pub fn is_internal_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                // "This network", 0.0.0.0/8.
+                || first == 0
+                // Carrier-grade NAT, 100.64.0.0/10.
+                || (first == 100 && second & 0xc0 == 64)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_internal_address(IpAddr::V4(ip));
+            }
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // Unique local, fc00::/7.
+                || first & 0xfe00 == 0xfc00
+                // Link local, fe80::/10.
+                || first & 0xffc0 == 0xfe80
+        }
+    }
+}
+
+/// Whether the host of a webhook URL names this machine or a private network
+/// outright. Names that only resolve there are caught when sending.
+pub fn is_internal_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<IpAddr>() {
+        Ok(ip) => is_internal_address(ip),
+        Err(_) => {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            host == "localhost" || host.ends_with(".localhost")
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEventType {
+    #[serde(rename = "property.status_changed")]
+    PropertyStatusChanged,
+    #[serde(rename = "landlord.created")]
+    LandlordCreated,
+    #[serde(rename = "diary_event.created")]
+    DiaryEventCreated,
+    #[serde(rename = "diary_event.updated")]
+    DiaryEventUpdated,
+    #[serde(rename = "diary_event.deleted")]
+    DiaryEventDeleted,
+    #[serde(rename = "certificate.expiring")]
+    CertificateExpiring,
+}
+
+impl WebhookEventType {
+    pub const ALL: [WebhookEventType; 6] = [
+        WebhookEventType::PropertyStatusChanged,
+        WebhookEventType::LandlordCreated,
+        WebhookEventType::DiaryEventCreated,
+        WebhookEventType::DiaryEventUpdated,
+        WebhookEventType::DiaryEventDeleted,
+        WebhookEventType::CertificateExpiring,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::PropertyStatusChanged => "property.status_changed",
+            WebhookEventType::LandlordCreated => "landlord.created",
+            WebhookEventType::DiaryEventCreated => "diary_event.created",
+            WebhookEventType::DiaryEventUpdated => "diary_event.updated",
+            WebhookEventType::DiaryEventDeleted => "diary_event.deleted",
+            WebhookEventType::CertificateExpiring => "certificate.expiring",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == value)
+    }
+
+    /// The webhook event raised for an outbox event, if integrators can
+    /// subscribe to it.
+    pub fn for_outbox_event(event_type: &str) -> Option<Self> {
+        match event_type {
+            "PropertyStatusChanged" => Some(WebhookEventType::PropertyStatusChanged),
+            "LandlordCreated" => Some(WebhookEventType::LandlordCreated),
+            "DiaryEventCreated" => Some(WebhookEventType::DiaryEventCreated),
+            "DiaryEventUpdated" => Some(WebhookEventType::DiaryEventUpdated),
+            "DiaryEventDeleted" => Some(WebhookEventType::DiaryEventDeleted),
+            "CertificateExpiring" => Some(WebhookEventType::CertificateExpiring),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub description: Option<String>,
+    pub active: bool,
+    pub consecutive_failures: i32,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub disabled_reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    /// The creator's organisation; only events about its records are sent.
+    pub organisation_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookSubscriptionRequest {
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub description: Option<String>,
+    /// Generated when left out.
+    pub secret: Option<String>,
+}
+
+/// Partial update; re-enabling a subscription clears its failure count.
+#[derive(Deserialize, ToSchema)]
+pub struct WebhookSubscriptionPatch {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// Returned once, when a subscription is created or its secret rotated.
+#[derive(Serialize, ToSchema)]
+pub struct WebhookSubscriptionCreated {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: JsonValue,
+    /// `pending`, `delivered` or `failed`.
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub replay_of: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeliveryQueryParams {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
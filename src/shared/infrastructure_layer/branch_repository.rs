@@ -0,0 +1,292 @@
+use actix_web::error::ResponseError;
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::shared::domain_layer::branch::{
+    Branch, BranchRecord, BranchScope, Organisation, StaffBranch, MANAGE_ORGANISATIONS,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "{} not found", _0)]
+    NotFound(&'static str),
+    #[display(fmt = "Validation error: {}", _0)]
+    ValidationError(String),
+}
+
+impl ResponseError for CustomErrors {}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                CustomErrors::ValidationError("A branch with that name already exists".to_string())
+            }
+            _ => CustomErrors::DatabaseError(e.to_string()),
+        }
+    }
+}
+
+pub struct BranchRepository {}
+
+impl BranchRepository {
+    pub fn new() -> Self {
+        BranchRepository {}
+    }
+
+    /// The branches `user_id` may see: their own, any they were given access
+    /// to, every branch of their organisation for admins and every branch
+    /// anywhere with `manage_organisations`.
+    pub async fn scope_for(
+        &self,
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+    ) -> Result<BranchScope, CustomErrors> {
+        let row = sqlx::query_as::<_, (Uuid, bool, Vec<Uuid>)>(
+            "SELECT s.branch_id,
+                    EXISTS (
+                        SELECT 1 FROM staff_permissions p
+                        WHERE p.user_id = s.user_id AND p.permission = $2
+                    ),
+                    ARRAY(
+                        SELECT b.id FROM branches b
+                        WHERE b.id = s.branch_id
+                           OR b.id IN (SELECT sb.branch_id FROM staff_branches sb WHERE sb.staff_id = s.user_id)
+                           OR (s.acc_level::text = 'admin'
+                               AND b.organisation_id = (SELECT hb.organisation_id FROM branches hb WHERE hb.id = s.branch_id))
+                    )
+             FROM staff_users s
+             WHERE s.user_id = $1 AND s.deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(MANAGE_ORGANISATIONS)
+        .fetch_optional(pool)
+        .await?;
+        Ok(match row {
+            Some((home_branch_id, all_branches, branch_ids)) => BranchScope {
+                home_branch_id: Some(home_branch_id),
+                branch_ids: if all_branches { None } else { Some(branch_ids) },
+            },
+            None => BranchScope::nothing(),
+        })
+    }
+
+    pub async fn get_organisations(
+        &self,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<Organisation>, CustomErrors> {
+        let organisations = sqlx::query_as::<_, Organisation>(
+            "SELECT * FROM organisations o
+             WHERE EXISTS (SELECT 1 FROM branches b WHERE b.organisation_id = o.id AND branch_visible(b.id))
+             ORDER BY o.name",
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(organisations)
+    }
+
+    pub async fn create_organisation(
+        &self,
+        pool: &Pool<Postgres>,
+        name: &str,
+        branch_name: &str,
+    ) -> Result<(Organisation, Branch), CustomErrors> {
+        let mut tx = pool.begin().await?;
+        let organisation = sqlx::query_as::<_, Organisation>(
+            "INSERT INTO organisations (id, name) VALUES ($1, $2) RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .fetch_one(&mut *tx)
+        .await?;
+        let branch = sqlx::query_as::<_, Branch>(
+            "INSERT INTO branches (id, organisation_id, name) VALUES ($1, $2, $3) RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(organisation.id)
+        .bind(branch_name)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok((organisation, branch))
+    }
+
+    /// Branches in the caller's scope.
+    pub async fn get_branches(&self, pool: &Pool<Postgres>) -> Result<Vec<Branch>, CustomErrors> {
+        let branches = sqlx::query_as::<_, Branch>(
+            "SELECT * FROM branches WHERE branch_visible(id) ORDER BY organisation_id, name",
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(branches)
+    }
+
+    /// A branch in the caller's scope.
+    pub async fn get_branch(
+        &self,
+        pool: &Pool<Postgres>,
+        branch_id: Uuid,
+    ) -> Result<Branch, CustomErrors> {
+        sqlx::query_as::<_, Branch>("SELECT * FROM branches WHERE id = $1 AND branch_visible(id)")
+            .bind(branch_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(CustomErrors::NotFound("Branch"))
+    }
+
+    pub async fn organisation_exists(
+        &self,
+        pool: &Pool<Postgres>,
+        organisation_id: Uuid,
+    ) -> Result<bool, CustomErrors> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM organisations WHERE id = $1)",
+        )
+        .bind(organisation_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(exists)
+    }
+
+    pub async fn create_branch(
+        &self,
+        pool: &Pool<Postgres>,
+        organisation_id: Uuid,
+        name: &str,
+    ) -> Result<Branch, CustomErrors> {
+        let branch = sqlx::query_as::<_, Branch>(
+            "INSERT INTO branches (id, organisation_id, name) VALUES ($1, $2, $3) RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(organisation_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await?;
+        Ok(branch)
+    }
+
+    /// The organisation of a staff member's own branch.
+    pub async fn organisation_of(
+        &self,
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+    ) -> Result<Uuid, CustomErrors> {
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT b.organisation_id FROM staff_users s
+             JOIN branches b ON b.id = s.branch_id
+             WHERE s.user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(CustomErrors::NotFound("User"))
+    }
+
+    pub async fn get_staff_branches(
+        &self,
+        pool: &Pool<Postgres>,
+        staff_id: Uuid,
+    ) -> Result<Vec<StaffBranch>, CustomErrors> {
+        let branches = sqlx::query_as::<_, StaffBranch>(
+            "SELECT * FROM staff_branches WHERE staff_id = $1 ORDER BY granted_at",
+        )
+        .bind(staff_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(branches)
+    }
+
+    pub async fn grant_staff_branch(
+        &self,
+        pool: &Pool<Postgres>,
+        staff_id: Uuid,
+        branch_id: Uuid,
+        granted_by: Uuid,
+    ) -> Result<StaffBranch, CustomErrors> {
+        let granted = sqlx::query_as::<_, StaffBranch>(
+            "INSERT INTO staff_branches (staff_id, branch_id, granted_by)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (staff_id, branch_id) DO UPDATE SET granted_by = $3, granted_at = CURRENT_TIMESTAMP
+             RETURNING *",
+        )
+        .bind(staff_id)
+        .bind(branch_id)
+        .bind(granted_by)
+        .fetch_one(pool)
+        .await?;
+        Ok(granted)
+    }
+
+    pub async fn revoke_staff_branch(
+        &self,
+        pool: &Pool<Postgres>,
+        staff_id: Uuid,
+        branch_id: Uuid,
+    ) -> Result<(), CustomErrors> {
+        let result =
+            sqlx::query("DELETE FROM staff_branches WHERE staff_id = $1 AND branch_id = $2")
+                .bind(staff_id)
+                .bind(branch_id)
+                .execute(pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound("Branch access"));
+        }
+        Ok(())
+    }
+
+    /// Moves a record the caller can see to `branch_id`. Moving a landlord
+    /// with `include_properties` moves their properties with them.
+    pub async fn transfer(
+        &self,
+        pool: &Pool<Postgres>,
+        record: BranchRecord,
+        id: Uuid,
+        branch_id: Uuid,
+        include_properties: bool,
+    ) -> Result<(), CustomErrors> {
+        let query = match record {
+            BranchRecord::StaffUser => {
+                "UPDATE staff_users SET branch_id = $2, updated_at = CURRENT_TIMESTAMP
+                 WHERE user_id = $1 AND deleted_at IS NULL"
+            }
+            BranchRecord::Landlord => {
+                "UPDATE landlord_details SET branch_id = $2, updated_at = CURRENT_TIMESTAMP
+                 WHERE landlord_id = $1 AND deleted_at IS NULL"
+            }
+            BranchRecord::Property => {
+                "UPDATE property_core SET branch_id = $2, updated_at = CURRENT_TIMESTAMP
+                 WHERE property_id = $1 AND deleted_at IS NULL"
+            }
+        };
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(query)
+            .bind(id)
+            .bind(branch_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound(match record {
+                BranchRecord::StaffUser => "User",
+                BranchRecord::Landlord => "Landlord",
+                BranchRecord::Property => "Property",
+            }));
+        }
+        if let (BranchRecord::Landlord, true) = (record, include_properties) {
+            sqlx::query(
+                "UPDATE property_core SET branch_id = $2, updated_at = CURRENT_TIMESTAMP
+                 WHERE landlord_id = $1 AND deleted_at IS NULL",
+            )
+            .bind(id)
+            .bind(branch_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{web, Error, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use serde_json::json;
+use sqlx::postgres::{PgConnection, PgPoolOptions};
+use std::future::{ready, Future, Ready};
+use std::rc::Rc;
+
+use crate::shared::domain_layer::branch::BranchScope;
+use crate::shared::infrastructure_layer::branch_repository::BranchRepository;
+use crate::user::infrastructure_layer::jwt_repo;
+use crate::AppState;
+
+tokio::task_local! {
+    static BRANCH_SCOPE: BranchScope;
+}
+
+/// The scope of the request being handled. Anything outside a request,
+/// including work a handler spawns onto its own task, sees every branch.
+pub fn current() -> BranchScope {
+    BRANCH_SCOPE
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| BranchScope::everything())
+}
+
+pub async fn scoped<F: Future>(scope: BranchScope, future: F) -> F::Output {
+    BRANCH_SCOPE.scope(scope, future).await
+}
+
+/// Runs `future` across every branch, e.g. to look a user up by name while
+/// signing in.
+pub async fn unrestricted<F: Future>(future: F) -> F::Output {
+    scoped(BranchScope::everything(), future).await
+}
+
+async fn apply_scope(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    let scope = current();
+    sqlx::query(
+        "SELECT set_config('app.branch_ids', $1, FALSE), set_config('app.home_branch_id', $2, FALSE)",
+    )
+    .bind(scope.branch_ids_setting())
+    .bind(scope.home_branch_setting())
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Pool options that hand every connection out with the current scope set,
+/// for the row-level security policies to read. This costs one round trip
+/// per acquire.
+pub fn pool_options() -> PgPoolOptions {
+    PgPoolOptions::new()
+        .after_connect(|conn, _| Box::pin(apply_scope(conn)))
+        .before_acquire(|conn, _| Box::pin(async move { apply_scope(conn).await.map(|_| true) }))
+}
+
+/// Limits each request to the caller's branches. Callers who are not logged
+/// in see no branch at all.
+pub struct BranchScoping;
+
+impl<S, B> Transform<S, ServiceRequest> for BranchScoping
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = BranchScopingMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(BranchScopingMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct BranchScopingMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for BranchScopingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let state = match request.app_data::<web::Data<AppState>>() {
+                Some(state) => state.clone(),
+                None => return Ok(service.call(request).await?.map_into_boxed_body()),
+            };
+            let scope = match jwt_repo::authenticated_user_id(request.request(), &state).await {
+                Some(user_id) => {
+                    match BranchRepository::new().scope_for(&state.db, user_id).await {
+                        Ok(scope) => scope,
+                        Err(e) => {
+                            return Ok(request.into_response(
+                                HttpResponse::InternalServerError()
+                                    .json(json!({"error": e.to_string()})),
+                            ))
+                        }
+                    }
+                }
+                None => BranchScope::nothing(),
+            };
+            let response = scoped(scope, service.call(request)).await?;
+            Ok(response.map_into_boxed_body())
+        })
+    }
+}
@@ -0,0 +1,225 @@
+use derive_more::Display;
+use futures_util::future::BoxFuture;
+use s3::{creds::Credentials, Bucket, Region};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DEFAULT_STORAGE_DIR: &str = "./uploads/documents";
+const DEFAULT_S3_REGION: &str = "eu-west-2";
+
+#[derive(Debug, Display)]
+pub enum StorageError {
+    #[display(fmt = "Document not found")]
+    NotFound,
+    #[display(fmt = "Document storage failed: {}", _0)]
+    Io(String),
+    #[display(fmt = "Document storage is misconfigured: {}", _0)]
+    Config(String),
+}
+
+/// Where uploaded documents are kept, by key. Services only depend on this
+/// trait, so local disk can be swapped for S3.
+pub trait DocumentStorage: Send + Sync {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        content_type: &'a str,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<(), StorageError>>;
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>, StorageError>>;
+
+    /// Removing a document that is already gone is not an error.
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), StorageError>>;
+
+    /// A link the storage itself serves the document from for `expires_in`
+    /// seconds, or `None` when documents have to be served by the API.
+    fn presigned_url<'a>(
+        &'a self,
+        key: &'a str,
+        expires_in: u32,
+    ) -> BoxFuture<'a, Result<Option<String>, StorageError>>;
+}
+
+/// Keeps documents as files under a directory.
+pub struct LocalStorage {
+    dir: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        LocalStorage { dir: dir.into() }
+    }
+
+    // Keys are generated by the server, but never let one leave the directory
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(StorageError::Io(format!("invalid key {}", key)));
+        }
+        Ok(self.dir.join(relative))
+    }
+}
+
+impl DocumentStorage for LocalStorage {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        _content_type: &'a str,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            let path = self.path(key)?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| StorageError::Io(e.to_string()))?;
+            }
+            // Write aside and rename, so a half-written file is never served.
+            let partial = path.with_extension(format!("{}.partial", Uuid::new_v4().simple()));
+            tokio::fs::write(&partial, bytes)
+                .await
+                .map_err(|e| StorageError::Io(e.to_string()))?;
+            tokio::fs::rename(&partial, &path)
+                .await
+                .map_err(|e| StorageError::Io(e.to_string()))
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>, StorageError>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.path(key)?).await {
+                Ok(bytes) => Ok(bytes),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(StorageError::NotFound),
+                Err(e) => Err(StorageError::Io(e.to_string())),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path(key)?).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(StorageError::Io(e.to_string())),
+            }
+        })
+    }
+
+    fn presigned_url<'a>(
+        &'a self,
+        _key: &'a str,
+        _expires_in: u32,
+    ) -> BoxFuture<'a, Result<Option<String>, StorageError>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Keeps documents in an S3 bucket, or any service speaking its API.
+pub struct S3Storage {
+    bucket: Box<Bucket>,
+}
+
+impl S3Storage {
+    pub fn new(bucket: Box<Bucket>) -> Self {
+        S3Storage { bucket }
+    }
+}
+
+impl DocumentStorage for S3Storage {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        content_type: &'a str,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            self.bucket
+                .put_object_with_content_type(key, &bytes, content_type)
+                .await
+                .map(|_| ())
+                .map_err(|e| StorageError::Io(e.to_string()))
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>, StorageError>> {
+        Box::pin(async move {
+            match self.bucket.get_object(key).await {
+                Ok(response) => Ok(response.bytes().to_vec()),
+                Err(s3::error::S3Error::HttpFailWithBody(404, _)) => Err(StorageError::NotFound),
+                Err(e) => Err(StorageError::Io(e.to_string())),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            self.bucket
+                .delete_object(key)
+                .await
+                .map(|_| ())
+                .map_err(|e| StorageError::Io(e.to_string()))
+        })
+    }
+
+    fn presigned_url<'a>(
+        &'a self,
+        key: &'a str,
+        expires_in: u32,
+    ) -> BoxFuture<'a, Result<Option<String>, StorageError>> {
+        Box::pin(async move {
+            self.bucket
+                .presign_get(key, expires_in, None)
+                .await
+                .map(Some)
+                .map_err(|e| StorageError::Io(e.to_string()))
+        })
+    }
+}
+
+/// S3 when `DOCUMENT_STORAGE=s3`, otherwise files under `DOCUMENT_STORAGE_DIR`.
+pub fn storage_from_env() -> Result<Arc<dyn DocumentStorage>, StorageError> {
+    match std::env::var("DOCUMENT_STORAGE").as_deref() {
+        Ok("s3") => {
+            let name = std::env::var("S3_BUCKET")
+                .map_err(|_| StorageError::Config("S3_BUCKET is not set".to_string()))?;
+            let region_name =
+                std::env::var("S3_REGION").unwrap_or_else(|_| DEFAULT_S3_REGION.to_string());
+            // A custom endpoint means an S3-compatible service such as MinIO,
+            // which expects path-style addressing.
+            let endpoint = std::env::var("S3_ENDPOINT").ok();
+            let region = match &endpoint {
+                Some(endpoint) => Region::Custom {
+                    region: region_name,
+                    endpoint: endpoint.clone(),
+                },
+                None => region_name
+                    .parse()
+                    .map_err(|e| StorageError::Config(format!("S3_REGION: {}", e)))?,
+            };
+            let credentials = Credentials::from_env()
+                .map_err(|e| StorageError::Config(format!("AWS credentials: {}", e)))?;
+            let bucket = Bucket::new(&name, region, credentials)
+                .map_err(|e| StorageError::Config(e.to_string()))?;
+            let bucket = if endpoint.is_some() {
+                bucket.with_path_style()
+            } else {
+                bucket
+            };
+            Ok(Arc::new(S3Storage::new(bucket)))
+        }
+        Ok("local") | Err(_) => {
+            let dir = std::env::var("DOCUMENT_STORAGE_DIR")
+                .unwrap_or_else(|_| DEFAULT_STORAGE_DIR.to_string());
+            Ok(Arc::new(LocalStorage::new(dir)))
+        }
+        Ok(other) => Err(StorageError::Config(format!(
+            "DOCUMENT_STORAGE must be s3 or local, not {}",
+            other
+        ))),
+    }
+}
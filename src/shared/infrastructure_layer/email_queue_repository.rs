@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::shared::domain_layer::email::{EmailRecipient, QueuedEmail};
+
+/// A queued email picked up by the sender, with its recipient resolved.
+#[derive(sqlx::FromRow)]
+pub struct DueEmail {
+    pub id: Uuid,
+    pub template: String,
+    pub context: serde_json::Value,
+    pub attempts: i32,
+    pub recipient_staff_id: Option<Uuid>,
+    pub recipient_name: Option<String>,
+    /// `None` when a staff recipient has no email address.
+    pub address: Option<String>,
+    /// `false` when a staff recipient has email notifications switched off
+    /// or has been deleted.
+    pub notifications_enabled: bool,
+}
+
+pub struct EmailQueueRepository {}
+
+impl EmailQueueRepository {
+    pub fn new() -> Self {
+        EmailQueueRepository {}
+    }
+
+    /// Queues an email inside the caller's transaction, so it is only sent if
+    /// the change that produced it commits. Returns `false` when an email
+    /// with the same dedupe key was already queued.
+    pub async fn enqueue(
+        tx: &mut Transaction<'_, Postgres>,
+        email: &QueuedEmail,
+    ) -> Result<bool, sqlx::Error> {
+        let (staff_id, address) = match &email.recipient {
+            EmailRecipient::Staff(staff_id) => (Some(*staff_id), None),
+            EmailRecipient::Address(address) => (None, Some(address.as_str())),
+        };
+        let result = sqlx::query(
+            "INSERT INTO email_queue (id, template, context, recipient_staff_id, recipient_email, dedupe_key)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (dedupe_key) DO NOTHING",
+        )
+        .bind(Uuid::new_v4())
+        .bind(email.template.as_str())
+        .bind(&email.context)
+        .bind(staff_id)
+        .bind(address)
+        .bind(&email.dedupe_key)
+        .execute(&mut **tx)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn enqueue_all(
+        tx: &mut Transaction<'_, Postgres>,
+        emails: &[QueuedEmail],
+    ) -> Result<(), sqlx::Error> {
+        for email in emails {
+            Self::enqueue(tx, email).await?;
+        }
+        Ok(())
+    }
+
+    /// Reserves up to `limit` due emails until `lease_until`, so several
+    /// senders can run without sending the same email twice.
+    pub async fn claim_due(
+        &self,
+        pool: &Pool<Postgres>,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<DueEmail>, sqlx::Error> {
+        sqlx::query_as::<_, DueEmail>(
+            "WITH due AS (
+                SELECT id FROM email_queue
+                WHERE status = 'pending' AND next_attempt_at <= CURRENT_TIMESTAMP
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+             ),
+             claimed AS (
+                UPDATE email_queue q SET next_attempt_at = $2
+                FROM due
+                WHERE q.id = due.id
+                RETURNING q.*
+             )
+             SELECT c.id, c.template, c.context, c.attempts, c.recipient_staff_id,
+                su.name AS recipient_name,
+                COALESCE(c.recipient_email, su.email) AS address,
+                c.recipient_staff_id IS NULL
+                    OR (su.deleted_at IS NULL AND COALESCE(ds.email_notifi_en, FALSE)) AS notifications_enabled
+             FROM claimed c
+             LEFT JOIN staff_users su ON su.user_id = c.recipient_staff_id
+             LEFT JOIN diary_settings ds ON ds.staff_id = c.recipient_staff_id",
+        )
+        .bind(limit)
+        .bind(lease_until)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_sent(&self, pool: &Pool<Postgres>, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE email_queue SET status = 'sent', attempts = attempts + 1,
+                sent_at = CURRENT_TIMESTAMP, last_error = NULL
+             WHERE id = $1",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Closes an email that will never be sent, e.g. because the recipient
+    /// switched email notifications off.
+    pub async fn mark_skipped(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        reason: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE email_queue SET status = 'skipped', last_error = $2 WHERE id = $1")
+            .bind(id)
+            .bind(reason)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt. Without `next_attempt_at` the email is
+    /// given up on.
+    pub async fn record_failure(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE email_queue SET attempts = attempts + 1, last_error = $2,
+                status = CASE WHEN $3::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+                next_attempt_at = COALESCE($3, next_attempt_at)
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes emails that are no longer pending and were queued before `cutoff`.
+    pub async fn delete_finished_before(
+        &self,
+        pool: &Pool<Postgres>,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM email_queue WHERE status <> 'pending' AND created_at < $1")
+                .bind(cutoff)
+                .execute(pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+}
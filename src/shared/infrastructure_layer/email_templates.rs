@@ -0,0 +1,79 @@
+use derive_more::Display;
+use handlebars::Handlebars;
+use serde_json::Value as JsonValue;
+
+use crate::shared::domain_layer::email::{EmailTemplate, OutgoingEmail};
+
+#[derive(Debug, Display)]
+pub enum RenderError {
+    #[display(fmt = "Template error: {}", _0)]
+    Template(String),
+}
+
+/// Subject, plain text and HTML sources of a template, compiled into the binary.
+fn sources(template: EmailTemplate) -> [&'static str; 3] {
+    match template {
+        EmailTemplate::EventReminder => [
+            include_str!("../../../templates/email/event_reminder.subject.hbs"),
+            include_str!("../../../templates/email/event_reminder.txt.hbs"),
+            include_str!("../../../templates/email/event_reminder.html.hbs"),
+        ],
+        EmailTemplate::AssignmentChanged => [
+            include_str!("../../../templates/email/assignment_changed.subject.hbs"),
+            include_str!("../../../templates/email/assignment_changed.txt.hbs"),
+            include_str!("../../../templates/email/assignment_changed.html.hbs"),
+        ],
+        EmailTemplate::BookingConfirmation => [
+            include_str!("../../../templates/email/booking_confirmation.subject.hbs"),
+            include_str!("../../../templates/email/booking_confirmation.txt.hbs"),
+            include_str!("../../../templates/email/booking_confirmation.html.hbs"),
+        ],
+    }
+}
+
+/// Renders queued emails. Plain text parts are not HTML-escaped.
+pub struct EmailRenderer {
+    text: Handlebars<'static>,
+    html: Handlebars<'static>,
+}
+
+impl EmailRenderer {
+    pub fn new() -> Result<Self, RenderError> {
+        let mut text = Handlebars::new();
+        text.register_escape_fn(handlebars::no_escape);
+        let mut html = Handlebars::new();
+        for template in EmailTemplate::ALL {
+            let [subject, body_text, body_html] = sources(template);
+            let name = template.as_str();
+            text.register_template_string(&format!("{}.subject", name), subject.trim())
+                .and_then(|_| text.register_template_string(&format!("{}.txt", name), body_text))
+                .and_then(|_| html.register_template_string(name, body_html))
+                .map_err(|e| RenderError::Template(e.to_string()))?;
+        }
+        Ok(EmailRenderer { text, html })
+    }
+
+    pub fn render(
+        &self,
+        template: EmailTemplate,
+        to: &str,
+        context: &JsonValue,
+    ) -> Result<OutgoingEmail, RenderError> {
+        let name = template.as_str();
+        let render_text = |part: &str| {
+            self.text
+                .render(&format!("{}.{}", name, part), context)
+                .map_err(|e| RenderError::Template(e.to_string()))
+        };
+        Ok(OutgoingEmail {
+            to: to.to_string(),
+            // Header values must stay on one line.
+            subject: render_text("subject")?.replace(['\r', '\n'], " "),
+            text: render_text("txt")?,
+            html: self
+                .html
+                .render(name, context)
+                .map_err(|e| RenderError::Template(e.to_string()))?,
+        })
+    }
+}
@@ -0,0 +1,184 @@
+use derive_more::Display;
+use futures_util::future::BoxFuture;
+use lettre::message::{header::ContentType, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::shared::domain_layer::email::OutgoingEmail;
+
+const DEFAULT_FROM: &str = "Real Estate <no-reply@localhost>";
+const DEFAULT_DROP_DIR: &str = "./mail";
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+#[derive(Debug, Display)]
+pub enum TransportError {
+    #[display(fmt = "Invalid email: {}", _0)]
+    Message(String),
+    #[display(fmt = "Email could not be sent: {}", _0)]
+    Send(String),
+    #[display(fmt = "Email transport is misconfigured: {}", _0)]
+    Config(String),
+}
+
+/// Where rendered emails go. The notification service only depends on this
+/// trait, so SMTP can be swapped for the file drop in local runs.
+pub trait EmailTransport: Send + Sync {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> BoxFuture<'a, Result<(), TransportError>>;
+}
+
+fn build_message(from: &Mailbox, email: &OutgoingEmail) -> Result<Message, TransportError> {
+    let to = email
+        .to
+        .parse::<Mailbox>()
+        .map_err(|e| TransportError::Message(format!("{}: {}", email.to, e)))?;
+    Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(email.subject.as_str())
+        .multipart(
+            MultiPart::alternative()
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_PLAIN)
+                        .body(email.text.clone()),
+                )
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_HTML)
+                        .body(email.html.clone()),
+                ),
+        )
+        .map_err(|e| TransportError::Message(e.to_string()))
+}
+
+pub struct SmtpTransport {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpTransport {
+    /// `tls` is `starttls` (the default), `tls` for implicit TLS or `none`
+    /// for local relays such as MailHog.
+    pub fn new(
+        host: &str,
+        port: u16,
+        tls: &str,
+        credentials: Option<(String, String)>,
+        from: Mailbox,
+    ) -> Result<Self, TransportError> {
+        let builder = match tls {
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+                .map_err(|e| TransportError::Config(e.to_string()))?,
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(|e| TransportError::Config(e.to_string()))?,
+            other => {
+                return Err(TransportError::Config(format!(
+                    "SMTP_TLS must be starttls, tls or none, not {}",
+                    other
+                )))
+            }
+        };
+        let builder = builder.port(port);
+        let builder = match credentials {
+            Some((username, password)) => builder.credentials(Credentials::new(username, password)),
+            None => builder,
+        };
+        Ok(SmtpTransport {
+            mailer: builder.build(),
+            from,
+        })
+    }
+}
+
+impl EmailTransport for SmtpTransport {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> BoxFuture<'a, Result<(), TransportError>> {
+        Box::pin(async move {
+            let message = build_message(&self.from, email)?;
+            self.mailer
+                .send(message)
+                .await
+                .map(|_| ())
+                .map_err(|e| TransportError::Send(e.to_string()))
+        })
+    }
+}
+
+/// Writes each email as an `.eml` file instead of sending it.
+pub struct FileDropTransport {
+    dir: PathBuf,
+    from: Mailbox,
+}
+
+impl FileDropTransport {
+    pub fn new(dir: impl Into<PathBuf>, from: Mailbox) -> Self {
+        FileDropTransport {
+            dir: dir.into(),
+            from,
+        }
+    }
+}
+
+impl EmailTransport for FileDropTransport {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> BoxFuture<'a, Result<(), TransportError>> {
+        Box::pin(async move {
+            let message = build_message(&self.from, email)?;
+            tokio::fs::create_dir_all(&self.dir)
+                .await
+                .map_err(|e| TransportError::Send(e.to_string()))?;
+            let file_name = format!(
+                "{}-{}.eml",
+                chrono::Utc::now().format("%Y%m%dT%H%M%S"),
+                Uuid::new_v4()
+            );
+            tokio::fs::write(self.dir.join(file_name), message.formatted())
+                .await
+                .map_err(|e| TransportError::Send(e.to_string()))
+        })
+    }
+}
+
+/// SMTP when `EMAIL_TRANSPORT=smtp`, otherwise the file drop in `EMAIL_DROP_DIR`.
+pub fn transport_from_env() -> Result<Arc<dyn EmailTransport>, TransportError> {
+    let from = std::env::var("EMAIL_FROM")
+        .unwrap_or_else(|_| DEFAULT_FROM.to_string())
+        .parse::<Mailbox>()
+        .map_err(|e| TransportError::Config(format!("EMAIL_FROM: {}", e)))?;
+
+    match std::env::var("EMAIL_TRANSPORT").as_deref() {
+        Ok("smtp") => {
+            let host = std::env::var("SMTP_HOST")
+                .map_err(|_| TransportError::Config("SMTP_HOST is not set".to_string()))?;
+            let port = match std::env::var("SMTP_PORT") {
+                Ok(port) => port
+                    .parse()
+                    .map_err(|_| TransportError::Config(format!("SMTP_PORT: {}", port)))?,
+                Err(_) => DEFAULT_SMTP_PORT,
+            };
+            let tls = std::env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string());
+            let credentials = std::env::var("SMTP_USERNAME")
+                .ok()
+                .map(|username| (username, std::env::var("SMTP_PASSWORD").unwrap_or_default()));
+            Ok(Arc::new(SmtpTransport::new(
+                &host,
+                port,
+                &tls,
+                credentials,
+                from,
+            )?))
+        }
+        Ok("file") | Err(_) => {
+            let dir =
+                std::env::var("EMAIL_DROP_DIR").unwrap_or_else(|_| DEFAULT_DROP_DIR.to_string());
+            log::info!("Emails are written to {} instead of being sent", dir);
+            Ok(Arc::new(FileDropTransport::new(dir, from)))
+        }
+        Ok(other) => Err(TransportError::Config(format!(
+            "EMAIL_TRANSPORT must be smtp or file, not {}",
+            other
+        ))),
+    }
+}
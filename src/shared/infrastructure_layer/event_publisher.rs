@@ -0,0 +1,117 @@
+use derive_more::Display;
+use futures_util::future::BoxFuture;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Display)]
+pub enum PublishError {
+    #[display(fmt = "Broker error: {}", _0)]
+    Broker(String),
+}
+
+/// Where outbox events are sent. The relay only depends on this trait, so
+/// Kafka can be swapped for the in-process broker in tests and local runs.
+pub trait EventPublisher: Send + Sync {
+    fn publish<'a>(
+        &'a self,
+        topic: &'a str,
+        key: &'a str,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<(), PublishError>>;
+}
+
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    pub fn new(brokers: &str) -> Result<Self, PublishError> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .set("message.timeout.ms", "30000")
+            .create()
+            .map_err(|e| PublishError::Broker(e.to_string()))?;
+        Ok(KafkaPublisher { producer })
+    }
+}
+
+impl EventPublisher for KafkaPublisher {
+    fn publish<'a>(
+        &'a self,
+        topic: &'a str,
+        key: &'a str,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<(), PublishError>> {
+        Box::pin(async move {
+            self.producer
+                .send(
+                    FutureRecord::to(topic).key(key).payload(payload),
+                    Timeout::After(Duration::from_secs(30)),
+                )
+                .await
+                .map(|_| ())
+                .map_err(|(e, _)| PublishError::Broker(e.to_string()))
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublishedMessage {
+    pub topic: String,
+    pub key: String,
+    pub payload: Vec<u8>,
+}
+
+/// Keeps published messages in memory instead of sending them anywhere.
+#[derive(Clone, Default)]
+pub struct InMemoryPublisher {
+    messages: Arc<Mutex<Vec<PublishedMessage>>>,
+}
+
+impl InMemoryPublisher {
+    pub fn new() -> Self {
+        InMemoryPublisher::default()
+    }
+
+    pub fn messages(&self) -> Vec<PublishedMessage> {
+        self.messages.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+}
+
+impl EventPublisher for InMemoryPublisher {
+    fn publish<'a>(
+        &'a self,
+        topic: &'a str,
+        key: &'a str,
+        payload: &'a [u8],
+    ) -> BoxFuture<'a, Result<(), PublishError>> {
+        let result = self
+            .messages
+            .lock()
+            .map(|mut messages| {
+                messages.push(PublishedMessage {
+                    topic: topic.to_string(),
+                    key: key.to_string(),
+                    payload: payload.to_vec(),
+                })
+            })
+            .map_err(|e| PublishError::Broker(e.to_string()));
+        Box::pin(async move { result })
+    }
+}
+
+/// Kafka when `KAFKA_BROKERS` is set, otherwise the in-memory stand-in.
+pub fn publisher_from_env() -> Result<Arc<dyn EventPublisher>, PublishError> {
+    match std::env::var("KAFKA_BROKERS") {
+        Ok(brokers) if !brokers.is_empty() => Ok(Arc::new(KafkaPublisher::new(&brokers)?)),
+        _ => {
+            log::warn!("KAFKA_BROKERS is not set; outbox events are kept in memory only");
+            Ok(Arc::new(InMemoryPublisher::new()))
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use derive_more::Display;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const PREFIX: &str = "enc:v";
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Display, Serialize)]
+pub enum CipherError {
+    #[display(fmt = "Invalid encryption key configuration: {}", _0)]
+    InvalidConfig(String),
+    #[display(fmt = "No encryption key with version {}", _0)]
+    UnknownKeyVersion(u32),
+    #[display(fmt = "Malformed encrypted value")]
+    Malformed,
+    #[display(fmt = "Encryption failed")]
+    EncryptionFailed,
+    #[display(fmt = "Decryption failed")]
+    DecryptionFailed,
+}
+
+/// Envelope encryption for individual columns. Every value gets its own random
+/// data key, which is wrapped with the newest configured master key. Stored
+/// values look like `enc:v<version>:<wrapped data key>:<ciphertext>`, so older
+/// values stay readable while keys are rotated.
+pub struct KeyRing {
+    keys: BTreeMap<u32, Key<Aes256Gcm>>,
+    active: u32,
+}
+
+impl KeyRing {
+    /// Reads `FIELD_ENCRYPTION_KEYS` as comma-separated `version:base64key`
+    /// pairs, each key 32 bytes. The highest version encrypts new values.
+    pub fn from_env() -> Result<Self, CipherError> {
+        let config = std::env::var("FIELD_ENCRYPTION_KEYS")
+            .map_err(|_| CipherError::InvalidConfig("FIELD_ENCRYPTION_KEYS must be set".into()))?;
+        Self::parse(&config)
+    }
+
+    pub fn parse(config: &str) -> Result<Self, CipherError> {
+        let mut keys = BTreeMap::new();
+        for entry in config.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (version, key) = entry.split_once(':').ok_or_else(|| {
+                CipherError::InvalidConfig(format!("expected version:key, got {}", entry))
+            })?;
+            let version: u32 = version
+                .trim_start_matches('v')
+                .parse()
+                .map_err(|_| CipherError::InvalidConfig(format!("bad key version {}", version)))?;
+            let bytes = STANDARD
+                .decode(key)
+                .map_err(|e| CipherError::InvalidConfig(e.to_string()))?;
+            if bytes.len() != 32 {
+                return Err(CipherError::InvalidConfig(format!(
+                    "key version {} must be 32 bytes",
+                    version
+                )));
+            }
+            keys.insert(version, *Key::<Aes256Gcm>::from_slice(&bytes));
+        }
+        let active = *keys
+            .keys()
+            .next_back()
+            .ok_or_else(|| CipherError::InvalidConfig("no keys configured".into()))?;
+        Ok(KeyRing { keys, active })
+    }
+
+    pub fn active_version(&self) -> u32 {
+        self.active
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, CipherError> {
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| CipherError::EncryptionFailed)?;
+
+        let master = Aes256Gcm::new(&self.keys[&self.active]);
+        let wrap_nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped_key = master
+            .encrypt(&wrap_nonce, data_key.as_slice())
+            .map_err(|_| CipherError::EncryptionFailed)?;
+
+        Ok(format!(
+            "{}{}:{}:{}",
+            PREFIX,
+            self.active,
+            STANDARD.encode([wrap_nonce.as_slice(), &wrapped_key].concat()),
+            STANDARD.encode([nonce.as_slice(), &ciphertext].concat()),
+        ))
+    }
+
+    pub fn encrypt_opt(&self, plaintext: Option<&str>) -> Result<Option<String>, CipherError> {
+        plaintext.map(|value| self.encrypt(value)).transpose()
+    }
+
+    /// Values written before encryption was introduced are returned unchanged.
+    pub fn decrypt(&self, stored: &str) -> Result<String, CipherError> {
+        let Some(rest) = stored.strip_prefix(PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let mut parts = rest.splitn(3, ':');
+        let (Some(version), Some(wrapped), Some(sealed)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(CipherError::Malformed);
+        };
+        let version: u32 = version.parse().map_err(|_| CipherError::Malformed)?;
+        let master_key = self
+            .keys
+            .get(&version)
+            .ok_or(CipherError::UnknownKeyVersion(version))?;
+
+        let wrapped = STANDARD
+            .decode(wrapped)
+            .map_err(|_| CipherError::Malformed)?;
+        let sealed = STANDARD
+            .decode(sealed)
+            .map_err(|_| CipherError::Malformed)?;
+        if wrapped.len() <= NONCE_LEN || sealed.len() <= NONCE_LEN {
+            return Err(CipherError::Malformed);
+        }
+
+        let (wrap_nonce, wrapped_key) = wrapped.split_at(NONCE_LEN);
+        let data_key = Aes256Gcm::new(master_key)
+            .decrypt(Nonce::from_slice(wrap_nonce), wrapped_key)
+            .map_err(|_| CipherError::DecryptionFailed)?;
+        if data_key.len() != 32 {
+            return Err(CipherError::Malformed);
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| CipherError::DecryptionFailed)?;
+        String::from_utf8(plaintext).map_err(|_| CipherError::Malformed)
+    }
+
+    pub fn decrypt_opt(&self, stored: Option<&str>) -> Result<Option<String>, CipherError> {
+        stored.map(|value| self.decrypt(value)).transpose()
+    }
+
+    /// True when the value is plaintext or wrapped with an older master key.
+    pub fn needs_reencryption(&self, stored: &str) -> bool {
+        match stored
+            .strip_prefix(PREFIX)
+            .and_then(|rest| rest.split(':').next())
+        {
+            Some(version) => version.parse::<u32>().ok() != Some(self.active),
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+
+use crate::shared::domain_layer::idempotency_key::{
+    IdempotencyClaim, IdempotencyRecord, IdempotencyScope, IDEMPOTENCY_KEY_TTL_HOURS,
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+}
+
+pub struct IdempotencyKeyRepository {}
+
+impl IdempotencyKeyRepository {
+    pub fn new() -> Self {
+        IdempotencyKeyRepository {}
+    }
+
+    /// Claims a key for a request with a single insert, so two requests with
+    /// the same key cannot both claim it. Expired keys are cleared first and
+    /// taken over as if they had never been used.
+    pub async fn claim(
+        &self,
+        pool: &Pool<Postgres>,
+        scope: &IdempotencyScope,
+        request_hash: &str,
+    ) -> Result<IdempotencyClaim, CustomErrors> {
+        sqlx::query(
+            "DELETE FROM idempotency_keys
+             WHERE idempotency_key = $1 AND request_method = $2 AND request_path = $3
+             AND created_at < CURRENT_TIMESTAMP - make_interval(hours => $4)",
+        )
+        .bind(&scope.key)
+        .bind(&scope.method)
+        .bind(&scope.path)
+        .bind(IDEMPOTENCY_KEY_TTL_HOURS as i32)
+        .execute(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let claimed = sqlx::query_as::<_, IdempotencyRecord>(
+            "INSERT INTO idempotency_keys
+                 (principal, idempotency_key, request_method, request_path, request_hash)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (principal, idempotency_key, request_method, request_path) DO NOTHING
+             RETURNING *",
+        )
+        .bind(&scope.principal)
+        .bind(&scope.key)
+        .bind(&scope.method)
+        .bind(&scope.path)
+        .bind(request_hash)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        if claimed.is_some() {
+            // Checked after our own row is in place: of two principals racing
+            // for a key, the later one always sees the earlier one.
+            if self.held_by_other_principal(pool, scope).await? {
+                self.release(pool, scope).await?;
+                return Ok(IdempotencyClaim::OtherPrincipal);
+            }
+            return Ok(IdempotencyClaim::Claimed);
+        }
+
+        let existing = sqlx::query_as::<_, IdempotencyRecord>(
+            "SELECT * FROM idempotency_keys
+             WHERE principal = $1 AND idempotency_key = $2 AND request_method = $3
+             AND request_path = $4",
+        )
+        .bind(&scope.principal)
+        .bind(&scope.key)
+        .bind(&scope.method)
+        .bind(&scope.path)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        match existing {
+            // The row was released between the two statements; let the caller proceed.
+            None => Ok(IdempotencyClaim::Claimed),
+            Some(record) if record.request_hash != request_hash => Ok(IdempotencyClaim::Mismatch),
+            Some(record) if record.status_code.is_none() => Ok(IdempotencyClaim::InProgress),
+            Some(record) => Ok(IdempotencyClaim::Replay(record)),
+        }
+    }
+
+    async fn held_by_other_principal(
+        &self,
+        pool: &Pool<Postgres>,
+        scope: &IdempotencyScope,
+    ) -> Result<bool, CustomErrors> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (
+                 SELECT 1 FROM idempotency_keys
+                 WHERE idempotency_key = $1 AND request_method = $2 AND request_path = $3
+                 AND principal <> $4
+             )",
+        )
+        .bind(&scope.key)
+        .bind(&scope.method)
+        .bind(&scope.path)
+        .bind(&scope.principal)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))
+    }
+
+    pub async fn complete(
+        &self,
+        pool: &Pool<Postgres>,
+        scope: &IdempotencyScope,
+        status_code: u16,
+        content_type: Option<String>,
+        response_body: &[u8],
+    ) -> Result<(), CustomErrors> {
+        sqlx::query(
+            "UPDATE idempotency_keys
+             SET status_code = $5, content_type = $6, response_body = $7, completed_at = CURRENT_TIMESTAMP
+             WHERE idempotency_key = $1 AND request_method = $2 AND request_path = $3
+             AND principal = $4",
+        )
+        .bind(&scope.key)
+        .bind(&scope.method)
+        .bind(&scope.path)
+        .bind(&scope.principal)
+        .bind(status_code as i16)
+        .bind(content_type)
+        .bind(response_body)
+        .execute(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Forgets a claimed key so the client can retry, used when processing failed.
+    pub async fn release(
+        &self,
+        pool: &Pool<Postgres>,
+        scope: &IdempotencyScope,
+    ) -> Result<(), CustomErrors> {
+        sqlx::query(
+            "DELETE FROM idempotency_keys
+             WHERE idempotency_key = $1 AND request_method = $2 AND request_path = $3
+             AND principal = $4 AND status_code IS NULL",
+        )
+        .bind(&scope.key)
+        .bind(&scope.method)
+        .bind(&scope.path)
+        .bind(&scope.principal)
+        .execute(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use actix_web::body::{self, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::{ErrorInternalServerError, PayloadError};
+use actix_web::http::header::{HeaderValue, CONTENT_TYPE};
+use actix_web::{web, Error, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use futures_util::stream::{self, Stream};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::future::{ready, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+
+use crate::shared::domain_layer::idempotency_key::{
+    IdempotencyClaim, IdempotencyScope, IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER,
+};
+use crate::shared::infrastructure_layer::idempotency_key_repository::IdempotencyKeyRepository;
+use crate::user::infrastructure_layer::jwt_repo;
+use crate::AppState;
+
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Makes a POST endpoint safe to retry. When the request carries an
+/// `Idempotency-Key` header, the first response is stored and replayed for
+/// repeats by the same caller; reusing the key with a different body, or
+/// from another caller, is rejected with 422.
+/// Requests without the header pass straight through.
+pub struct Idempotency;
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotency
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IdempotencyMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct IdempotencyMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut request: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let key = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+                Some(value) => value.to_str().map(str::to_string).unwrap_or_default(),
+                None => return Ok(service.call(request).await?.map_into_boxed_body()),
+            };
+            if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+                return Ok(request.into_response(HttpResponse::BadRequest().json(
+                    json!({ "error": "Idempotency-Key must be between 1 and 255 characters" }),
+                )));
+            }
+            let state = match request.app_data::<web::Data<AppState>>() {
+                Some(state) => state.clone(),
+                None => return Ok(service.call(request).await?.map_into_boxed_body()),
+            };
+
+            let body = request.extract::<web::Bytes>().await?;
+            let request_hash = hash_request(&request, &body);
+            request.set_payload(bytes_to_payload(body));
+
+            let principal = match jwt_repo::authenticated_user_id(request.request(), &state).await {
+                Some(user_id) => format!("user:{}", user_id),
+                None => "anonymous".to_string(),
+            };
+            let scope = IdempotencyScope {
+                key,
+                method: request.method().to_string(),
+                path: request.path().to_string(),
+                principal,
+            };
+            let repo = IdempotencyKeyRepository::new();
+
+            let claim = match repo.claim(&state.db, &scope, &request_hash).await {
+                Ok(claim) => claim,
+                Err(e) => {
+                    return Ok(request.into_response(
+                        HttpResponse::InternalServerError().json(json!({ "error": e.to_string() })),
+                    ))
+                }
+            };
+
+            match claim {
+                IdempotencyClaim::Mismatch => Ok(request.into_response(
+                    HttpResponse::UnprocessableEntity().json(json!({
+                        "error": "Idempotency key reused",
+                        "message": "This Idempotency-Key was already used with a different request body"
+                    })),
+                )),
+                IdempotencyClaim::OtherPrincipal => Ok(request.into_response(
+                    HttpResponse::UnprocessableEntity().json(json!({
+                        "error": "Idempotency key reused",
+                        "message": "This Idempotency-Key is already in use by another caller"
+                    })),
+                )),
+                IdempotencyClaim::InProgress => Ok(request.into_response(
+                    HttpResponse::Conflict().json(json!({
+                        "error": "Request in progress",
+                        "message": "A request with this Idempotency-Key is still being processed"
+                    })),
+                )),
+                IdempotencyClaim::Replay(record) => {
+                    let status = record
+                        .status_code
+                        .and_then(|code| u16::try_from(code).ok())
+                        .and_then(|code| actix_web::http::StatusCode::from_u16(code).ok())
+                        .unwrap_or(actix_web::http::StatusCode::OK);
+                    let mut response = HttpResponse::build(status);
+                    if let Some(content_type) = record.content_type {
+                        response.insert_header((CONTENT_TYPE, content_type));
+                    }
+                    response.insert_header((IDEMPOTENT_REPLAYED_HEADER, "true"));
+                    Ok(request.into_response(response.body(record.response_body.unwrap_or_default())))
+                }
+                IdempotencyClaim::Claimed => {
+                    let response = match service.call(request).await {
+                        Ok(response) => response,
+                        Err(e) => {
+                            repo.release(&state.db, &scope).await.ok();
+                            return Err(e);
+                        }
+                    };
+
+                    // Server errors are not remembered so that a retry gets another chance.
+                    if response.status().is_server_error() {
+                        repo.release(&state.db, &scope).await.ok();
+                        return Ok(response.map_into_boxed_body());
+                    }
+
+                    let status = response.status();
+                    let content_type = response
+                        .headers()
+                        .get(CONTENT_TYPE)
+                        .and_then(|value: &HeaderValue| value.to_str().ok())
+                        .map(str::to_string);
+                    let (http_request, http_response) = response.into_parts();
+                    let (http_response, response_body) = http_response.into_parts();
+                    let bytes = match body::to_bytes(response_body).await {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            repo.release(&state.db, &scope).await.ok();
+                            let e: Box<dyn std::error::Error> = e.into();
+                            return Err(ErrorInternalServerError(e.to_string()));
+                        }
+                    };
+
+                    if let Err(e) = repo
+                        .complete(&state.db, &scope, status.as_u16(), content_type, &bytes)
+                        .await
+                    {
+                        log::warn!("Failed to store idempotent response: {}", e);
+                    }
+
+                    Ok(ServiceResponse::new(
+                        http_request,
+                        http_response.set_body(BoxBody::new(bytes)),
+                    ))
+                }
+            }
+        })
+    }
+}
+
+fn hash_request(request: &ServiceRequest, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.method().as_str().as_bytes());
+    hasher.update(request.path().as_bytes());
+    hasher.update(request.query_string().as_bytes());
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn bytes_to_payload(body: web::Bytes) -> Payload {
+    let stream: Pin<Box<dyn Stream<Item = Result<web::Bytes, PayloadError>>>> =
+        Box::pin(stream::once(async move { Ok(body) }));
+    Payload::from(stream)
+}
@@ -0,0 +1,356 @@
+use actix_web::error::ResponseError;
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::types::JsonValue;
+use sqlx::{PgExecutor, Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::shared::domain_layer::job::{Job, JobSchedule, NewJob};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Job not found")]
+    NotFound,
+    #[display(fmt = "Conflict: {}", _0)]
+    Conflict(String),
+}
+
+impl ResponseError for CustomErrors {}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        match e.as_database_error() {
+            Some(db) if db.is_unique_violation() => CustomErrors::Conflict(
+                "a pending or running job already holds this unique key".to_string(),
+            ),
+            _ => CustomErrors::DatabaseError(e.to_string()),
+        }
+    }
+}
+
+pub struct JobRepository {}
+
+impl JobRepository {
+    pub fn new() -> Self {
+        JobRepository {}
+    }
+
+    /// Queues a job. Returns `None` when a pending or running job already
+    /// holds its unique key. Pass a transaction to queue it with other changes.
+    pub async fn enqueue<'e>(
+        executor: impl PgExecutor<'e>,
+        job: &NewJob,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO jobs (id, kind, payload, unique_key, schedule_name, run_at, max_attempts)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (unique_key)
+                WHERE unique_key IS NOT NULL AND status IN ('pending', 'running')
+                DO NOTHING
+             RETURNING id",
+        )
+        .bind(Uuid::new_v4())
+        .bind(&job.kind)
+        .bind(&job.payload)
+        .bind(&job.unique_key)
+        .bind(&job.schedule_name)
+        .bind(job.run_at)
+        .bind(job.max_attempts)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Claims up to `limit` due jobs of the given kinds for `worker` until
+    /// `lease_until`. Jobs whose previous claim ran out are claimed again.
+    pub async fn claim_due(
+        &self,
+        pool: &Pool<Postgres>,
+        kinds: &[String],
+        worker: &str,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<Job>, sqlx::Error> {
+        sqlx::query_as::<_, Job>(
+            "WITH due AS (
+                SELECT id FROM jobs
+                WHERE kind = ANY($1)
+                    AND ((status = 'pending' AND run_at <= CURRENT_TIMESTAMP)
+                        OR (status = 'running' AND locked_until < CURRENT_TIMESTAMP))
+                ORDER BY run_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+             )
+             UPDATE jobs j SET status = 'running', attempts = j.attempts + 1, locked_by = $3,
+                locked_until = $4, started_at = CURRENT_TIMESTAMP
+             FROM due
+             WHERE j.id = due.id
+             RETURNING j.*",
+        )
+        .bind(kinds)
+        .bind(limit)
+        .bind(worker)
+        .bind(lease_until)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Fails running jobs whose claim ran out on their last attempt, so a job
+    /// that brings its worker down is not picked up forever.
+    pub async fn fail_abandoned(&self, pool: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE jobs SET status = 'failed', finished_at = CURRENT_TIMESTAMP, locked_until = NULL,
+                last_error = COALESCE(last_error, 'Worker stopped while running the job')
+             WHERE status = 'running' AND locked_until < CURRENT_TIMESTAMP
+                AND attempts >= max_attempts",
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Records success, unless another worker has claimed the job since.
+    pub async fn mark_succeeded(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        worker: &str,
+        result: Option<JsonValue>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE jobs SET status = 'succeeded', result = $3, last_error = NULL,
+                finished_at = CURRENT_TIMESTAMP, locked_until = NULL
+             WHERE id = $1 AND locked_by = $2 AND status = 'running'",
+        )
+        .bind(id)
+        .bind(worker)
+        .bind(result)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt. Without `retry_at` the job is given up on.
+    pub async fn record_failure(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        worker: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE jobs SET last_error = $3, locked_until = NULL,
+                status = CASE WHEN $4::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+                run_at = COALESCE($4, run_at),
+                finished_at = CASE WHEN $4::timestamptz IS NULL THEN CURRENT_TIMESTAMP END
+             WHERE id = $1 AND locked_by = $2 AND status = 'running'",
+        )
+        .bind(id)
+        .bind(worker)
+        .bind(error)
+        .bind(retry_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_all(
+        &self,
+        pool: &Pool<Postgres>,
+        status: Option<&str>,
+        kind: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Job>, CustomErrors> {
+        Ok(sqlx::query_as::<_, Job>(
+            "SELECT * FROM jobs
+             WHERE ($1::text IS NULL OR status = $1) AND ($2::text IS NULL OR kind = $2)
+             ORDER BY created_at DESC
+             LIMIT $3",
+        )
+        .bind(status)
+        .bind(kind)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn get_by_id(&self, pool: &Pool<Postgres>, id: Uuid) -> Result<Job, CustomErrors> {
+        sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(CustomErrors::NotFound)
+    }
+
+    /// Queues a failed or cancelled job again with a fresh set of attempts.
+    pub async fn retry(&self, pool: &Pool<Postgres>, id: Uuid) -> Result<Job, CustomErrors> {
+        let job = sqlx::query_as::<_, Job>(
+            "UPDATE jobs SET status = 'pending', attempts = 0, run_at = CURRENT_TIMESTAMP,
+                locked_by = NULL, locked_until = NULL, finished_at = NULL
+             WHERE id = $1 AND status IN ('failed', 'cancelled')
+             RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+        match job {
+            Some(job) => Ok(job),
+            None => Err(self.state_conflict(pool, id, "failed or cancelled").await),
+        }
+    }
+
+    /// Cancels a job that has not started yet.
+    pub async fn cancel(&self, pool: &Pool<Postgres>, id: Uuid) -> Result<Job, CustomErrors> {
+        let job = sqlx::query_as::<_, Job>(
+            "UPDATE jobs SET status = 'cancelled', finished_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND status = 'pending'
+             RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+        match job {
+            Some(job) => Ok(job),
+            None => Err(self.state_conflict(pool, id, "pending").await),
+        }
+    }
+
+    async fn state_conflict(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        expected: &str,
+    ) -> CustomErrors {
+        match self.get_by_id(pool, id).await {
+            Ok(job) => {
+                CustomErrors::Conflict(format!("job is {}, it must be {}", job.status, expected))
+            }
+            Err(e) => e,
+        }
+    }
+
+    /// Removes finished jobs that ended before `cutoff`.
+    pub async fn delete_finished_before(
+        &self,
+        pool: &Pool<Postgres>,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM jobs
+             WHERE status IN ('succeeded', 'failed', 'cancelled') AND finished_at < $1",
+        )
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Creates or updates a schedule declared in code. A changed schedule
+    /// starts over from `next_run_at`; `enabled` is left as an admin set it.
+    pub async fn register_schedule(
+        &self,
+        pool: &Pool<Postgres>,
+        name: &str,
+        kind: &str,
+        schedule: &str,
+        payload: &JsonValue,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO job_schedules (name, kind, schedule, payload, next_run_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (name) DO UPDATE SET
+                kind = EXCLUDED.kind,
+                payload = EXCLUDED.payload,
+                next_run_at = CASE WHEN job_schedules.schedule = EXCLUDED.schedule
+                    THEN job_schedules.next_run_at ELSE EXCLUDED.next_run_at END,
+                schedule = EXCLUDED.schedule,
+                updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(name)
+        .bind(kind)
+        .bind(schedule)
+        .bind(payload)
+        .bind(next_run_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Locks the enabled schedules among `names` that are due. Only one
+    /// instance sees each due schedule.
+    pub async fn claim_due_schedules(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        names: &[String],
+    ) -> Result<Vec<JobSchedule>, sqlx::Error> {
+        sqlx::query_as::<_, JobSchedule>(
+            "SELECT * FROM job_schedules
+             WHERE name = ANY($1) AND enabled AND next_run_at <= CURRENT_TIMESTAMP
+             FOR UPDATE SKIP LOCKED",
+        )
+        .bind(names)
+        .fetch_all(&mut **tx)
+        .await
+    }
+
+    pub async fn advance_schedule(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+        last_run_at: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE job_schedules SET last_run_at = $2, next_run_at = $3 WHERE name = $1")
+            .bind(name)
+            .bind(last_run_at)
+            .bind(next_run_at)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_schedules(
+        &self,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<JobSchedule>, CustomErrors> {
+        Ok(
+            sqlx::query_as::<_, JobSchedule>("SELECT * FROM job_schedules ORDER BY name")
+                .fetch_all(pool)
+                .await?,
+        )
+    }
+
+    pub async fn get_schedule(
+        &self,
+        pool: &Pool<Postgres>,
+        name: &str,
+    ) -> Result<JobSchedule, CustomErrors> {
+        sqlx::query_as::<_, JobSchedule>("SELECT * FROM job_schedules WHERE name = $1")
+            .bind(name)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(CustomErrors::NotFound)
+    }
+
+    pub async fn set_schedule_enabled(
+        &self,
+        pool: &Pool<Postgres>,
+        name: &str,
+        enabled: bool,
+    ) -> Result<JobSchedule, CustomErrors> {
+        sqlx::query_as::<_, JobSchedule>(
+            "UPDATE job_schedules SET enabled = $2, updated_at = CURRENT_TIMESTAMP
+             WHERE name = $1
+             RETURNING *",
+        )
+        .bind(name)
+        .bind(enabled)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(CustomErrors::NotFound)
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::{Duration, Utc};
+use futures_util::future::BoxFuture;
+use sqlx::types::JsonValue;
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::shared::{
+    domain_layer::job::{Job, NewJob, Schedule},
+    infrastructure_layer::job_repository::JobRepository,
+};
+
+/// What a handler reports back: an optional result stored on the job, or an
+/// error that is retried with backoff.
+pub type JobResult = Result<Option<JsonValue>, String>;
+
+type ErasedHandler = Arc<dyn Fn(Job) -> BoxFuture<'static, JobResult> + Send + Sync>;
+
+const BASE_BACKOFF_SECONDS: i64 = 30;
+const MAX_BACKOFF_SECONDS: i64 = 60 * 60;
+/// Finished jobs are kept this long for the status API.
+const FINISHED_RETENTION_DAYS: i64 = 14;
+const CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+pub struct JobRunnerConfig {
+    pub poll_interval: std::time::Duration,
+    /// Jobs run at the same time by this instance.
+    pub concurrency: i64,
+    /// How long a claimed job is reserved before another instance may take it over.
+    pub lease: Duration,
+    /// Identifies this instance in `jobs.locked_by`.
+    pub worker_id: String,
+}
+
+impl JobRunnerConfig {
+    /// Reads `JOB_POLL_INTERVAL_MS` (default 1000), `JOB_CONCURRENCY`
+    /// (default 4) and `JOB_LEASE_SECONDS` (default 600).
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: i64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<i64>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(default)
+        };
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+        JobRunnerConfig {
+            poll_interval: std::time::Duration::from_millis(
+                read("JOB_POLL_INTERVAL_MS", 1000) as u64
+            ),
+            concurrency: read("JOB_CONCURRENCY", 4),
+            lease: Duration::seconds(read("JOB_LEASE_SECONDS", 600)),
+            worker_id: format!(
+                "{}-{}-{}",
+                host,
+                std::process::id(),
+                &Uuid::new_v4().simple().to_string()[..8]
+            ),
+        }
+    }
+}
+
+struct ScheduleEntry {
+    name: String,
+    kind: String,
+    schedule: Schedule,
+    payload: JsonValue,
+}
+
+/// Runs jobs from the `jobs` table. Every instance runs one; claims use
+/// `SKIP LOCKED` and leases, so each job runs on one instance at a time.
+pub struct JobRunner {
+    pool: Pool<Postgres>,
+    config: JobRunnerConfig,
+    handlers: HashMap<String, ErasedHandler>,
+    schedules: Vec<ScheduleEntry>,
+}
+
+impl JobRunner {
+    pub fn new(pool: Pool<Postgres>, config: JobRunnerConfig) -> Self {
+        JobRunner {
+            pool,
+            config,
+            handlers: HashMap::new(),
+            schedules: Vec::new(),
+        }
+    }
+
+    /// Registers `handler` for jobs of `kind`. This instance only claims
+    /// kinds it has a handler for.
+    pub fn handle<F, Fut>(mut self, kind: &str, handler: F) -> Self
+    where
+        F: Fn(Job) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = JobResult> + Send + 'static,
+    {
+        let erased: ErasedHandler = Arc::new(move |job: Job| Box::pin(handler(job)));
+        self.handlers.insert(kind.to_string(), erased);
+        self
+    }
+
+    /// Queues a `kind` job each time `schedule` comes round. A run is skipped
+    /// while the previous one is still pending or running.
+    pub fn schedule(mut self, name: &str, kind: &str, schedule: Schedule) -> Self {
+        self.schedules.push(ScheduleEntry {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            schedule,
+            payload: JsonValue::Object(Default::default()),
+        });
+        self
+    }
+
+    /// Starts the runner. It stops claiming once `shutdown` turns `true` and
+    /// returns when the jobs in hand have finished.
+    pub fn spawn(self, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        actix_web::rt::spawn(self.run(shutdown))
+    }
+
+    async fn run(self, mut shutdown: watch::Receiver<bool>) {
+        let repo = JobRepository::new();
+        let kinds: Vec<String> = self.handlers.keys().cloned().collect();
+        let schedule_names: Vec<String> = self.schedules.iter().map(|s| s.name.clone()).collect();
+        for entry in &self.schedules {
+            let next_run_at = entry
+                .schedule
+                .next_after(Utc::now())
+                .unwrap_or_else(Utc::now);
+            if let Err(e) = repo
+                .register_schedule(
+                    &self.pool,
+                    &entry.name,
+                    &entry.kind,
+                    &entry.schedule.to_text(),
+                    &entry.payload,
+                    next_run_at,
+                )
+                .await
+            {
+                log::error!("Registering job schedule {} failed: {}", entry.name, e);
+            }
+        }
+
+        let mut last_cleanup = std::time::Instant::now();
+        while !*shutdown.borrow() {
+            if let Err(e) = self.queue_scheduled(&repo, &schedule_names).await {
+                log::error!("Queueing scheduled jobs failed: {}", e);
+            }
+            match repo.fail_abandoned(&self.pool).await {
+                Ok(0) => {}
+                Ok(failed) => log::warn!("Failed {} jobs abandoned on their last attempt", failed),
+                Err(e) => log::error!("Failing abandoned jobs failed: {}", e),
+            }
+
+            let lease_until = Utc::now() + self.config.lease;
+            let claimed = match repo
+                .claim_due(
+                    &self.pool,
+                    &kinds,
+                    &self.config.worker_id,
+                    self.config.concurrency,
+                    lease_until,
+                )
+                .await
+            {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    log::error!("Claiming jobs failed: {}", e);
+                    Vec::new()
+                }
+            };
+            let full_batch = claimed.len() as i64 == self.config.concurrency;
+            self.run_batch(&repo, claimed).await;
+
+            if last_cleanup.elapsed() >= CLEANUP_INTERVAL {
+                last_cleanup = std::time::Instant::now();
+                let cutoff = Utc::now() - Duration::days(FINISHED_RETENTION_DAYS);
+                if let Err(e) = repo.delete_finished_before(&self.pool, cutoff).await {
+                    log::error!("Cleaning up finished jobs failed: {}", e);
+                }
+            }
+            if full_batch {
+                continue;
+            }
+            tokio::select! {
+                _ = actix_web::rt::time::sleep(self.config.poll_interval) => {}
+                _ = shutdown.changed() => {}
+            }
+        }
+        log::info!("Job runner {} stopped", self.config.worker_id);
+    }
+
+    /// Turns due schedules into jobs and moves each schedule on to its next run.
+    async fn queue_scheduled(
+        &self,
+        repo: &JobRepository,
+        names: &[String],
+    ) -> Result<(), sqlx::Error> {
+        if names.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
+        for due in repo.claim_due_schedules(&mut tx, names).await? {
+            let entry = match self.schedules.iter().find(|entry| entry.name == due.name) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let mut job = NewJob::new(&due.kind, due.payload.clone())
+                .unique_key(format!("schedule:{}", due.name));
+            job.schedule_name = Some(due.name.clone());
+            if JobRepository::enqueue(&mut *tx, &job).await?.is_none() {
+                log::warn!(
+                    "Skipped a run of {} because the previous one has not finished",
+                    due.name
+                );
+            }
+            // A missed run is not caught up on; the schedule continues from now.
+            let next_run_at = entry
+                .schedule
+                .next_after(now)
+                .unwrap_or(now + Duration::days(1));
+            repo.advance_schedule(&mut tx, &due.name, now, next_run_at)
+                .await?;
+        }
+        tx.commit().await
+    }
+
+    async fn run_batch(&self, repo: &JobRepository, jobs: Vec<Job>) {
+        let mut running = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            let handler = match self.handlers.get(&job.kind) {
+                Some(handler) => Arc::clone(handler),
+                None => continue,
+            };
+            let (id, kind, attempts, max_attempts) =
+                (job.id, job.kind.clone(), job.attempts, job.max_attempts);
+            // Each job runs in its own task so a panic only fails that job.
+            let task = actix_web::rt::spawn(handler(job));
+            running.push((id, kind, attempts, max_attempts, task));
+        }
+
+        for (id, kind, attempts, max_attempts, task) in running {
+            let outcome = match task.await {
+                Ok(outcome) => outcome,
+                Err(e) => Err(format!("Job panicked: {}", e)),
+            };
+            let recorded = match outcome {
+                Ok(result) => {
+                    repo.mark_succeeded(&self.pool, id, &self.config.worker_id, result)
+                        .await
+                }
+                Err(error) => {
+                    let retry_at = (attempts < max_attempts).then(|| {
+                        let backoff = BASE_BACKOFF_SECONDS
+                            .saturating_mul(2_i64.saturating_pow((attempts - 1).max(0) as u32))
+                            .min(MAX_BACKOFF_SECONDS);
+                        Utc::now() + Duration::seconds(backoff)
+                    });
+                    if retry_at.is_none() {
+                        log::warn!(
+                            "Job {} ({}) failed after {} attempts: {}",
+                            id,
+                            kind,
+                            attempts,
+                            error
+                        );
+                    }
+                    repo.record_failure(&self.pool, id, &self.config.worker_id, &error, retry_at)
+                        .await
+                }
+            };
+            if let Err(e) = recorded {
+                log::error!("Recording the outcome of job {} failed: {}", id, e);
+            }
+        }
+    }
+}
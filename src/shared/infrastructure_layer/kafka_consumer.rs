@@ -0,0 +1,358 @@
+use derive_more::Display;
+use futures_util::future::BoxFuture;
+use log::{error, info, warn};
+use prost::Message as ProstMessage;
+use rdkafka::client::ClientContext;
+use rdkafka::config::{ClientConfig, RDKafkaLogLevel};
+use rdkafka::consumer::stream_consumer::StreamConsumer;
+use rdkafka::consumer::{CommitMode, Consumer, ConsumerContext, Rebalance};
+use rdkafka::error::KafkaResult;
+use rdkafka::message::{BorrowedMessage, Header, Message, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::topic_partition_list::TopicPartitionList;
+use rdkafka::util::Timeout;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::shared::domain_layer::consumer_health::{ConsumerHealth, ConsumerState};
+
+pub const DEAD_LETTER_SUFFIX: &str = ".dlq";
+const DEFAULT_GROUP_ID: &str = "real-estate-backend";
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+/// Consecutive receive errors after which the client is recreated.
+const MAX_CONSECUTIVE_ERRORS: u32 = 10;
+
+struct CustomContext;
+
+impl ClientContext for CustomContext {
+    const ENABLE_REFRESH_OAUTH_TOKEN: bool = false;
+}
+
+impl ConsumerContext for CustomContext {
+    fn pre_rebalance(&self, rebalance: &Rebalance) {
+        info!("Pre rebalance {:?}", rebalance);
+    }
+
+    fn post_rebalance(&self, rebalance: &Rebalance) {
+        info!("Post rebalance {:?}", rebalance);
+    }
+
+    fn commit_callback(&self, result: KafkaResult<()>, _offsets: &TopicPartitionList) {
+        if let Err(e) = result {
+            warn!("Committing offsets failed: {}", e);
+        }
+    }
+}
+
+type LoggingConsumer = StreamConsumer<CustomContext>;
+
+#[derive(Debug, Display)]
+pub enum HandlerError {
+    /// The payload is not the message the topic is registered with; retrying
+    /// cannot help, so it goes straight to the dead-letter topic.
+    #[display(fmt = "Could not decode message: {}", _0)]
+    Decode(String),
+    #[display(fmt = "Handler failed: {}", _0)]
+    Failed(String),
+}
+
+#[derive(Debug, Display)]
+enum ConsumerError {
+    #[display(fmt = "Kafka error: {}", _0)]
+    Kafka(String),
+    #[display(fmt = "Shutting down")]
+    ShuttingDown,
+}
+
+/// Where a message came from, passed to handlers next to the decoded message.
+#[derive(Clone, Debug)]
+pub struct MessageContext {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub key: Option<Vec<u8>>,
+}
+
+type ErasedHandler = Arc<
+    dyn Fn(&[u8], MessageContext) -> BoxFuture<'static, Result<(), HandlerError>> + Send + Sync,
+>;
+
+#[derive(Clone, Debug)]
+pub struct ConsumerConfig {
+    pub brokers: String,
+    pub group_id: String,
+    pub max_retries: u32,
+}
+
+impl ConsumerConfig {
+    /// Reads `KAFKA_BROKERS`, `KAFKA_CONSUMER_GROUP` and
+    /// `KAFKA_CONSUMER_MAX_RETRIES`. Returns `None` when no brokers are set.
+    pub fn from_env() -> Option<Self> {
+        let brokers = std::env::var("KAFKA_BROKERS")
+            .ok()
+            .filter(|b| !b.is_empty())?;
+        Some(ConsumerConfig {
+            brokers,
+            group_id: std::env::var("KAFKA_CONSUMER_GROUP")
+                .unwrap_or_else(|_| DEFAULT_GROUP_ID.to_string()),
+            max_retries: std::env::var("KAFKA_CONSUMER_MAX_RETRIES")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_MAX_RETRIES),
+        })
+    }
+}
+
+/// Runs one consumer for every registered topic and keeps it running: if the
+/// client fails it is recreated with backoff. A message's offset is committed
+/// only once its handler succeeded or it was written to `<topic>.dlq`.
+pub struct KafkaConsumerSupervisor {
+    config: ConsumerConfig,
+    handlers: HashMap<String, ErasedHandler>,
+    health: Arc<ConsumerHealth>,
+}
+
+impl KafkaConsumerSupervisor {
+    pub fn new(config: ConsumerConfig, health: Arc<ConsumerHealth>) -> Self {
+        KafkaConsumerSupervisor {
+            config,
+            handlers: HashMap::new(),
+            health,
+        }
+    }
+
+    /// Registers `handler` for `topic`. Payloads are decoded as `M` before the
+    /// handler sees them; an `Err` from the handler is retried.
+    pub fn handle<M, F, Fut>(mut self, topic: &str, handler: F) -> Self
+    where
+        M: ProstMessage + Default + 'static,
+        F: Fn(M, MessageContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let erased: ErasedHandler = Arc::new(move |payload: &[u8], context: MessageContext| {
+            let decoded = M::decode(payload);
+            let handler = Arc::clone(&handler);
+            Box::pin(async move {
+                let message = decoded.map_err(|e| HandlerError::Decode(e.to_string()))?;
+                handler(message, context)
+                    .await
+                    .map_err(HandlerError::Failed)
+            })
+        });
+        self.handlers.insert(topic.to_string(), erased);
+        self
+    }
+
+    /// Starts the consumer task. It stops after the message in hand once
+    /// `shutdown` turns `true`.
+    pub fn spawn(self, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        actix_web::rt::spawn(self.supervise(shutdown))
+    }
+
+    async fn supervise(self, mut shutdown: watch::Receiver<bool>) {
+        let mut topics: Vec<String> = self.handlers.keys().cloned().collect();
+        topics.sort();
+        self.health.set_topics(topics.clone());
+        let mut backoff = Duration::from_secs(1);
+
+        while !*shutdown.borrow() {
+            self.health.set_state(ConsumerState::Starting);
+            match self.run(&topics, &mut shutdown).await {
+                Ok(()) | Err(ConsumerError::ShuttingDown) => break,
+                Err(e) => {
+                    error!("Kafka consumer failed, restarting in {:?}: {}", backoff, e);
+                    self.health.record_restart(&e.to_string());
+                    tokio::select! {
+                        _ = shutdown.changed() => {}
+                        _ = actix_web::rt::time::sleep(backoff) => {}
+                    }
+                    backoff = (backoff * 2).min(MAX_RESTART_BACKOFF);
+                }
+            }
+        }
+
+        self.health.set_state(ConsumerState::Stopped);
+        info!("Kafka consumer stopped");
+    }
+
+    async fn run(
+        &self,
+        topics: &[String],
+        shutdown: &mut watch::Receiver<bool>,
+    ) -> Result<(), ConsumerError> {
+        let consumer: LoggingConsumer = ClientConfig::new()
+            .set("group.id", &self.config.group_id)
+            .set("bootstrap.servers", &self.config.brokers)
+            .set("enable.partition.eof", "false")
+            .set("session.timeout.ms", "6000")
+            .set("enable.auto.commit", "false")
+            .set("auto.offset.reset", "earliest")
+            .set_log_level(RDKafkaLogLevel::Warning)
+            .create_with_context(CustomContext)
+            .map_err(|e| ConsumerError::Kafka(e.to_string()))?;
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &self.config.brokers)
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .create()
+            .map_err(|e| ConsumerError::Kafka(e.to_string()))?;
+
+        let topic_names: Vec<&str> = topics.iter().map(String::as_str).collect();
+        consumer
+            .subscribe(&topic_names)
+            .map_err(|e| ConsumerError::Kafka(e.to_string()))?;
+        self.health.set_state(ConsumerState::Running);
+        info!("Kafka consumer subscribed to {}", topic_names.join(", "));
+
+        let mut consecutive_errors = 0;
+        loop {
+            let received = tokio::select! {
+                _ = shutdown.changed() => return Ok(()),
+                received = consumer.recv() => received,
+            };
+
+            match received {
+                Err(e) => {
+                    warn!("Kafka error: {}", e);
+                    self.health.record_error(&e.to_string());
+                    consecutive_errors += 1;
+                    if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+                        return Err(ConsumerError::Kafka(e.to_string()));
+                    }
+                }
+                Ok(message) => {
+                    consecutive_errors = 0;
+                    self.process(&message, &producer, shutdown).await?;
+                    consumer
+                        .commit_message(&message, CommitMode::Async)
+                        .map_err(|e| ConsumerError::Kafka(e.to_string()))?;
+                }
+            }
+        }
+    }
+
+    async fn process(
+        &self,
+        message: &BorrowedMessage<'_>,
+        producer: &FutureProducer,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Result<(), ConsumerError> {
+        let handler = match self.handlers.get(message.topic()) {
+            Some(handler) => handler,
+            None => return Ok(()),
+        };
+        let payload = message.payload().unwrap_or_default();
+        let context = MessageContext {
+            topic: message.topic().to_string(),
+            partition: message.partition(),
+            offset: message.offset(),
+            key: message.key().map(<[u8]>::to_vec),
+        };
+
+        let mut attempts = 0;
+        let failure = loop {
+            attempts += 1;
+            match handler(payload, context.clone()).await {
+                Ok(()) => {
+                    self.health.record_message();
+                    return Ok(());
+                }
+                Err(e @ HandlerError::Decode(_)) => break e,
+                Err(e) if attempts > self.config.max_retries => break e,
+                Err(e) => {
+                    warn!(
+                        "Handling {}[{}]@{} failed (attempt {}): {}",
+                        context.topic, context.partition, context.offset, attempts, e
+                    );
+                    actix_web::rt::time::sleep(RETRY_BACKOFF * 2_u32.pow(attempts - 1)).await;
+                }
+            }
+        };
+
+        self.dead_letter(message, &failure, attempts, producer, shutdown)
+            .await
+    }
+
+    /// Keeps trying until the message is on the dead-letter topic, because
+    /// committing its offset before that would lose it.
+    async fn dead_letter(
+        &self,
+        message: &BorrowedMessage<'_>,
+        failure: &HandlerError,
+        attempts: u32,
+        producer: &FutureProducer,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Result<(), ConsumerError> {
+        let topic = format!("{}{}", message.topic(), DEAD_LETTER_SUFFIX);
+        let partition = message.partition().to_string();
+        let offset = message.offset().to_string();
+        let error_text = failure.to_string();
+        let attempts_text = attempts.to_string();
+        let key = message.key().unwrap_or_default();
+        let payload = message.payload().unwrap_or_default();
+        let mut backoff = RETRY_BACKOFF;
+
+        loop {
+            let headers = OwnedHeaders::new()
+                .insert(Header {
+                    key: "x-original-topic",
+                    value: Some(message.topic()),
+                })
+                .insert(Header {
+                    key: "x-original-partition",
+                    value: Some(&partition),
+                })
+                .insert(Header {
+                    key: "x-original-offset",
+                    value: Some(&offset),
+                })
+                .insert(Header {
+                    key: "x-error",
+                    value: Some(&error_text),
+                })
+                .insert(Header {
+                    key: "x-attempts",
+                    value: Some(&attempts_text),
+                });
+            let record = FutureRecord::to(&topic)
+                .key(key)
+                .payload(payload)
+                .headers(headers);
+
+            match producer
+                .send(record, Timeout::After(Duration::from_secs(30)))
+                .await
+            {
+                Ok(_) => {
+                    warn!(
+                        "Moved {}[{}]@{} to {} after {} attempt(s): {}",
+                        message.topic(),
+                        partition,
+                        offset,
+                        topic,
+                        attempts,
+                        error_text
+                    );
+                    self.health.record_dead_letter();
+                    return Ok(());
+                }
+                Err((e, _)) => {
+                    error!("Writing to {} failed: {}", topic, e);
+                    self.health.record_error(&e.to_string());
+                    if *shutdown.borrow() {
+                        return Err(ConsumerError::ShuttingDown);
+                    }
+                    actix_web::rt::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RESTART_BACKOFF);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use serde_json::Value;
+use sqlx::postgres::PgListener;
+use sqlx::{FromRow, Pool, Postgres};
+use uuid::Uuid;
+
+use crate::shared::domain_layer::live_update::StreamPosition;
+
+/// Notification channel raised by the `outbox_events_notify` trigger.
+pub const OUTBOX_CHANNEL: &str = "outbox_events";
+
+/// Only the event types shown on the live channel are read back.
+const LIVE_EVENT_TYPES: [&str; 4] = [
+    "PropertyStatusChanged",
+    "DiaryEventCreated",
+    "DiaryEventUpdated",
+    "DiaryEventDeleted",
+];
+
+/// Only events of transactions older than every running one are read, so
+/// nothing can later commit at an earlier stream position.
+const SETTLED: &str = "xact_id < pg_snapshot_xmin(pg_current_snapshot())::text::bigint";
+
+#[derive(Clone, Debug, FromRow)]
+pub struct LiveEventRow {
+    pub xact_id: i64,
+    pub sequence: i64,
+    pub event_type: String,
+    pub aggregate_id: Uuid,
+    pub data: Value,
+}
+
+impl LiveEventRow {
+    pub fn position(&self) -> StreamPosition {
+        StreamPosition {
+            xact_id: self.xact_id,
+            sequence: self.sequence,
+        }
+    }
+}
+
+pub struct LiveUpdateRepository {}
+
+impl LiveUpdateRepository {
+    pub fn new() -> Self {
+        LiveUpdateRepository {}
+    }
+
+    pub async fn listen(&self, pool: &Pool<Postgres>) -> Result<PgListener, sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(OUTBOX_CHANNEL).await?;
+        Ok(listener)
+    }
+
+    /// Settled live events after `after`, in stream order.
+    pub async fn get_since(
+        &self,
+        pool: &Pool<Postgres>,
+        after: StreamPosition,
+        limit: i64,
+    ) -> Result<Vec<LiveEventRow>, sqlx::Error> {
+        sqlx::query_as::<_, LiveEventRow>(&format!(
+            "SELECT xact_id, sequence, event_type, aggregate_id, data FROM outbox_events
+             WHERE (xact_id, sequence) > ($1, $2) AND {} AND event_type = ANY($3)
+             ORDER BY xact_id, sequence
+             LIMIT $4",
+            SETTLED
+        ))
+        .bind(after.xact_id)
+        .bind(after.sequence)
+        .bind(&LIVE_EVENT_TYPES[..])
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Branch of a property, including soft-deleted ones.
+    pub async fn property_branch(
+        &self,
+        pool: &Pool<Postgres>,
+        property_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>("SELECT branch_id FROM property_core WHERE property_id = $1")
+            .bind(property_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// The position of the last settled event, where a new stream starts.
+    pub async fn latest_position(
+        &self,
+        pool: &Pool<Postgres>,
+    ) -> Result<StreamPosition, sqlx::Error> {
+        sqlx::query_as::<_, (i64, i64)>(&format!(
+            "SELECT xact_id, sequence FROM outbox_events WHERE {}
+             ORDER BY xact_id DESC, sequence DESC
+             LIMIT 1",
+            SETTLED
+        ))
+        .fetch_optional(pool)
+        .await
+        .map(|row| {
+            row.map(|(xact_id, sequence)| StreamPosition { xact_id, sequence })
+                .unwrap_or_default()
+        })
+    }
+}
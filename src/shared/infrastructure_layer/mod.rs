@@ -0,0 +1,19 @@
+pub mod field_cipher;
+pub mod idempotency_key_repository;
+pub mod idempotency_repo;
+pub mod retention_repository;
+pub mod event_publisher;
+pub mod outbox_repository;
+pub mod kafka_consumer;
+pub mod webhook_repository;
+pub mod webhook_sender;
+pub mod live_update_repository;
+pub mod email_queue_repository;
+pub mod email_templates;
+pub mod document_storage;
+pub mod email_transport;
+pub mod job_repository;
+pub mod job_runner;
+pub mod search_repository;
+pub mod branch_scope;
+pub mod branch_repository;
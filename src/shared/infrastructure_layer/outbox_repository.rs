@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::shared::domain_layer::outbox_event::OutboxEvent;
+use crate::shared::infrastructure_layer::webhook_repository::WebhookRepository;
+
+pub struct OutboxRepository {}
+
+impl OutboxRepository {
+    pub fn new() -> Self {
+        OutboxRepository {}
+    }
+
+    /// Stores an event inside the caller's transaction, so it is only
+    /// published if the change that produced it commits. Webhook deliveries
+    /// for the event are queued in the same transaction.
+    pub async fn enqueue(
+        tx: &mut Transaction<'_, Postgres>,
+        event: &OutboxEvent,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO outbox_events (id, topic, aggregate_type, aggregate_id, event_type, payload, data, created_at, next_attempt_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)",
+        )
+        .bind(event.id)
+        .bind(&event.topic)
+        .bind(&event.aggregate_type)
+        .bind(event.aggregate_id)
+        .bind(&event.event_type)
+        .bind(&event.payload)
+        .bind(&event.data)
+        .bind(event.created_at)
+        .execute(&mut **tx)
+        .await?;
+        WebhookRepository::fan_out(tx, event).await
+    }
+
+    /// Leases up to `limit` due events by pushing their next attempt past
+    /// `lease_until`, so several relays can run without publishing the same
+    /// row at once, and a crashed relay's events are picked up again once the
+    /// lease runs out.
+    pub async fn claim_due(
+        &self,
+        pool: &Pool<Postgres>,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<OutboxEvent>, sqlx::Error> {
+        sqlx::query_as::<_, OutboxEvent>(
+            "WITH due AS (
+                SELECT id FROM outbox_events
+                WHERE published_at IS NULL AND next_attempt_at <= CURRENT_TIMESTAMP
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+             )
+             UPDATE outbox_events e SET next_attempt_at = $2
+             FROM due
+             WHERE e.id = due.id
+             RETURNING e.*",
+        )
+        .bind(limit)
+        .bind(lease_until)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_published(&self, pool: &Pool<Postgres>, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbox_events SET published_at = CURRENT_TIMESTAMP, attempts = attempts + 1, last_error = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_failed(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbox_events SET attempts = attempts + 1, last_error = $2, next_attempt_at = $3 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Published rows are only kept for troubleshooting.
+    pub async fn delete_published_before(
+        &self,
+        pool: &Pool<Postgres>,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM outbox_events WHERE published_at < $1")
+            .bind(cutoff)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::diary::infrastructure_layer::diary_event_repo::DETAIL_TABLES;
+use crate::shared::domain_layer::retention_policy::PurgeSummary;
+use crate::shared::infrastructure_layer::document_storage::DocumentStorage;
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+}
+
+/// A soft-deletable table together with the rows that have to go with it.
+struct PurgeTarget {
+    table: &'static str,
+    key: &'static str,
+    /// Child tables removed first, all keyed by `child_key`.
+    children: &'static [&'static str],
+    child_key: &'static str,
+    /// Selects the ids due for purging; `$1` is the retention cutoff.
+    due: &'static str,
+    /// Selects the storage keys of files belonging to a row; `$1` is its id.
+    files: Option<&'static str>,
+}
+
+const EVENTS: PurgeTarget = PurgeTarget {
+    table: "events",
+    key: "id",
+    children: &DETAIL_TABLES,
+    child_key: "event_id",
+    due: "SELECT id FROM events WHERE deleted_at < $1",
+    files: None,
+};
+
+const PROPERTIES: PurgeTarget = PurgeTarget {
+    table: "property_core",
+    key: "property_id",
+    children: &[
+        "property_address",
+        "property_images",
+        "property_photos",
+        "property_specifications",
+        "property_rental_info",
+        "property_services",
+        "property_keys_security",
+        "certificate_documents",
+        "property_certificates",
+        "property_status_history",
+        "compliance_reminders",
+    ],
+    child_key: "property_id",
+    due: "SELECT p.property_id FROM property_core p
+          WHERE p.deleted_at < $1
+            AND NOT EXISTS (
+                SELECT 1 FROM landlord_general g
+                WHERE g.landlord_id = p.landlord_id AND g.do_not_delete_before > CURRENT_DATE
+            )",
+    // Photo variants go with their photos.
+    files: Some(
+        "SELECT storage_key FROM certificate_documents WHERE property_id = $1
+         UNION ALL
+         SELECT v.storage_key FROM property_photo_variants v
+         JOIN property_photos p ON p.photo_id = v.photo_id
+         WHERE p.property_id = $1",
+    ),
+};
+
+const LANDLORDS: PurgeTarget = PurgeTarget {
+    table: "landlord_details",
+    key: "landlord_id",
+    children: &[
+        "landlords_address",
+        "landlords_bank_details",
+        "landlords_lettings_management",
+        "landlord_general",
+    ],
+    child_key: "landlord_id",
+    due: "SELECT d.landlord_id FROM landlord_details d
+          WHERE d.deleted_at < $1
+            AND NOT EXISTS (
+                SELECT 1 FROM landlord_general g
+                WHERE g.landlord_id = d.landlord_id AND g.do_not_delete_before > CURRENT_DATE
+            )",
+    files: None,
+};
+
+const STAFF: PurgeTarget = PurgeTarget {
+    table: "staff_users",
+    key: "user_id",
+    children: &[],
+    child_key: "user_id",
+    due: "SELECT user_id FROM staff_users WHERE deleted_at < $1",
+    files: None,
+};
+
+pub struct RetentionRepository {}
+
+impl RetentionRepository {
+    pub fn new() -> Self {
+        RetentionRepository {}
+    }
+
+    /// Hard-deletes rows soft-deleted before `cutoff`. Landlords with a
+    /// `do_not_delete_before` in the future, and their properties, are kept.
+    /// Each row is removed in its own transaction so one row that is still
+    /// referenced elsewhere does not block the rest. Files in `storage` that
+    /// belong to a removed row are deleted once its transaction commits.
+    pub async fn purge(
+        &self,
+        pool: &Pool<Postgres>,
+        storage: &dyn DocumentStorage,
+        cutoff: DateTime<Utc>,
+    ) -> Result<PurgeSummary, CustomErrors> {
+        let mut summary = PurgeSummary::default();
+        // Children before parents: events point at staff, properties at landlords.
+        summary.events = Self::purge_target(pool, storage, &EVENTS, cutoff, &mut summary).await?;
+        summary.properties =
+            Self::purge_target(pool, storage, &PROPERTIES, cutoff, &mut summary).await?;
+        summary.landlords =
+            Self::purge_target(pool, storage, &LANDLORDS, cutoff, &mut summary).await?;
+        summary.staff = Self::purge_target(pool, storage, &STAFF, cutoff, &mut summary).await?;
+        Ok(summary)
+    }
+
+    async fn purge_target(
+        pool: &Pool<Postgres>,
+        storage: &dyn DocumentStorage,
+        target: &PurgeTarget,
+        cutoff: DateTime<Utc>,
+        summary: &mut PurgeSummary,
+    ) -> Result<u64, CustomErrors> {
+        let ids: Vec<Uuid> = sqlx::query_scalar(target.due)
+            .bind(cutoff)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        let mut purged = 0;
+        for id in ids {
+            match Self::purge_row(pool, target, id, cutoff).await {
+                Ok((rows, storage_keys)) => {
+                    purged += rows;
+                    for storage_key in storage_keys {
+                        match storage.delete(&storage_key).await {
+                            Ok(()) => summary.files += 1,
+                            Err(e) => log::warn!(
+                                "Could not delete {} of purged {} {}: {}",
+                                storage_key,
+                                target.table,
+                                id,
+                                e
+                            ),
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Could not purge {} {}: {}", target.table, id, e);
+                    summary.skipped += 1;
+                }
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Removes one row and its children. Returns how many rows were removed
+    /// and the storage keys of the files that belonged to them.
+    async fn purge_row(
+        pool: &Pool<Postgres>,
+        target: &PurgeTarget,
+        id: Uuid,
+        cutoff: DateTime<Utc>,
+    ) -> Result<(u64, Vec<String>), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let storage_keys: Vec<String> = match target.files {
+            Some(files) => {
+                sqlx::query_scalar(files)
+                    .bind(id)
+                    .fetch_all(&mut *tx)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+        for child in target.children {
+            sqlx::query(&format!(
+                "DELETE FROM {} WHERE {} = $1",
+                child, target.child_key
+            ))
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        // Re-check deleted_at in case the row was restored since it was selected.
+        let result = sqlx::query(&format!(
+            "DELETE FROM {} WHERE {} = $1 AND deleted_at < $2",
+            target.table, target.key
+        ))
+        .bind(id)
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok((0, Vec::new()));
+        }
+        tx.commit().await?;
+        Ok((result.rows_affected(), storage_keys))
+    }
+}
@@ -0,0 +1,136 @@
+use actix_web::error::ResponseError;
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::shared::domain_layer::search::{prefix_end, SearchResult, SearchTerms};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+}
+
+impl ResponseError for CustomErrors {}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+pub struct SearchRepository {}
+
+impl SearchRepository {
+    pub fn new() -> Self {
+        SearchRepository {}
+    }
+
+    /// Searches the kinds in `kinds`, best matches first. Postcode and phone
+    /// prefix matches rank above word matches. They are written as ranges
+    /// rather than `LIKE $n || '%'` so the prefix indexes can serve them in a
+    /// generic plan too. Landlords are also found through the addresses of
+    /// their properties, and private events are only returned to the staff
+    /// involved in them.
+    pub async fn search(
+        &self,
+        pool: &Pool<Postgres>,
+        terms: &SearchTerms,
+        kinds: &[&str],
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>, CustomErrors> {
+        Ok(sqlx::query_as::<_, SearchResult>(
+            "WITH params AS (
+        SELECT to_tsquery('simple', $1) AS q, to_tsquery('english', $1) AS qe
+    ),
+    landlord_hits AS (
+        SELECT ld.landlord_id, ts_rank(ld.search_vector, p.q) AS rank, NULL::text AS via
+        FROM params p, landlord_details ld
+        WHERE ld.search_vector @@ p.q
+        UNION ALL
+        SELECT ld.landlord_id, 1.0 AS rank, NULL::text AS via
+        FROM landlord_details ld
+        WHERE $3::text IS NOT NULL AND regexp_replace(ld.phone_nr, '\\D', '', 'g') ~>=~ $3
+                    AND regexp_replace(ld.phone_nr, '\\D', '', 'g') ~<~ $8
+        UNION ALL
+        SELECT pc.landlord_id, ts_rank(pa.search_vector, p.q) * 0.9 AS rank, pa.display_address AS via
+        FROM params p, property_address pa
+        JOIN property_core pc ON pc.property_id = pa.property_id AND pc.deleted_at IS NULL
+        WHERE pc.landlord_id IS NOT NULL
+            AND (pa.search_vector @@ p.q
+                OR ($2::text IS NOT NULL AND upper(replace(pa.postcode, ' ', '')) ~>=~ $2
+                    AND upper(replace(pa.postcode, ' ', '')) ~<~ $7))
+    )
+    SELECT * FROM (
+        SELECT 'property' AS kind, pc.property_id AS id,
+            COALESCE(pa.display_address, concat_ws(', ', pa.address_line1, pa.town_city, pa.postcode)) AS title,
+            COALESCE(ld.company_name, ld.full_name) AS subtitle,
+            (ts_rank(pa.search_vector, p.q)
+                + CASE WHEN $2::text IS NOT NULL AND upper(replace(pa.postcode, ' ', '')) ~>=~ $2
+                    AND upper(replace(pa.postcode, ' ', '')) ~<~ $7
+                    THEN 1 ELSE 0 END)::real AS rank
+        FROM params p, property_address pa
+        JOIN property_core pc ON pc.property_id = pa.property_id AND pc.deleted_at IS NULL
+        LEFT JOIN landlord_details ld ON ld.landlord_id = pc.landlord_id AND ld.deleted_at IS NULL
+        WHERE 'property' = ANY($4)
+            AND (pa.search_vector @@ p.q
+                OR ($2::text IS NOT NULL AND upper(replace(pa.postcode, ' ', '')) ~>=~ $2
+                    AND upper(replace(pa.postcode, ' ', '')) ~<~ $7))
+
+        UNION ALL
+
+        SELECT 'landlord', ld.landlord_id,
+            COALESCE(ld.company_name, ld.full_name, ld.phone_nr),
+            concat_ws(' · ', ld.email, ld.phone_nr, string_agg(DISTINCT h.via, '; ')),
+            MAX(h.rank)::real
+        FROM landlord_hits h
+        JOIN landlord_details ld ON ld.landlord_id = h.landlord_id AND ld.deleted_at IS NULL
+        WHERE 'landlord' = ANY($4)
+        GROUP BY ld.landlord_id, ld.company_name, ld.full_name, ld.email, ld.phone_nr
+
+        UNION ALL
+
+        SELECT 'staff', su.user_id, COALESCE(su.name, su.username),
+            concat_ws(' · ', su.username, su.email),
+            (ts_rank(su.search_vector, p.q)
+                + CASE WHEN $3::text IS NOT NULL
+                    AND regexp_replace(coalesce(su.mob_phone, ''), '\\D', '', 'g') ~>=~ $3
+                    AND regexp_replace(coalesce(su.mob_phone, ''), '\\D', '', 'g') ~<~ $8
+                    THEN 1 ELSE 0 END)::real
+        FROM params p, staff_users su
+        WHERE 'staff' = ANY($4) AND su.deleted_at IS NULL
+            AND (su.search_vector @@ p.q
+                OR ($3::text IS NOT NULL
+                    AND regexp_replace(coalesce(su.mob_phone, ''), '\\D', '', 'g') ~>=~ $3
+                    AND regexp_replace(coalesce(su.mob_phone, ''), '\\D', '', 'g') ~<~ $8))
+
+        UNION ALL
+
+        SELECT 'event', e.id, COALESCE(e.title, initcap(e.event_type::text)),
+            concat_ws(' ', initcap(e.event_type::text), to_char(e.date, 'DD/MM/YYYY'), to_char(e.start_time, 'HH24:MI')),
+            ts_rank(e.search_vector, p.qe)::real
+        FROM params p, events e
+        LEFT JOIN appointment_details ad ON ad.event_id = e.id
+        LEFT JOIN note_details nd ON nd.event_id = e.id
+        WHERE 'event' = ANY($4) AND e.deleted_at IS NULL AND e.search_vector @@ p.qe
+            AND (NOT (COALESCE(ad.is_private, FALSE) OR COALESCE(nd.is_private, FALSE))
+                OR e.created_by = $5
+                OR $5 = ANY(COALESCE(nd.assigned_staff, '{}'::uuid[])))
+    ) results
+    ORDER BY rank DESC, title
+    LIMIT $6",
+        )
+        .bind(&terms.tsquery)
+        .bind(&terms.postcode_prefix)
+        .bind(&terms.phone_prefix)
+        .bind(kinds)
+        .bind(user_id)
+        .bind(limit)
+        .bind(terms.postcode_prefix.as_deref().map(prefix_end))
+        .bind(terms.phone_prefix.as_deref().map(prefix_end))
+        .fetch_all(pool)
+        .await?)
+    }
+}
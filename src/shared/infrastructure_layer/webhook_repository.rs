@@ -0,0 +1,393 @@
+use actix_web::error::ResponseError;
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::shared::{
+    domain_layer::{
+        outbox_event::OutboxEvent,
+        webhook::{
+            WebhookDelivery, WebhookEventType, WebhookSubscription, WebhookSubscriptionPatch,
+            WebhookSubscriptionRequest,
+        },
+    },
+    infrastructure_layer::field_cipher::{CipherError, KeyRing},
+};
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Webhook not found")]
+    NotFound,
+    #[display(fmt = "Validation error: {}", _0)]
+    ValidationError(String),
+    #[display(fmt = "Encryption error: {}", _0)]
+    Encryption(String),
+}
+
+impl ResponseError for CustomErrors {}
+
+impl From<sqlx::Error> for CustomErrors {
+    fn from(e: sqlx::Error) -> Self {
+        CustomErrors::DatabaseError(e.to_string())
+    }
+}
+
+impl From<CipherError> for CustomErrors {
+    fn from(e: CipherError) -> Self {
+        CustomErrors::Encryption(e.to_string())
+    }
+}
+
+/// A delivery picked up by the dispatcher, with what is needed to send it.
+#[derive(sqlx::FromRow)]
+pub struct DueDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+pub struct WebhookRepository {}
+
+impl WebhookRepository {
+    pub fn new() -> Self {
+        WebhookRepository {}
+    }
+
+    pub async fn create(
+        &self,
+        pool: &Pool<Postgres>,
+        key_ring: &KeyRing,
+        request: &WebhookSubscriptionRequest,
+        secret: &str,
+        created_by: Uuid,
+        organisation_id: Uuid,
+    ) -> Result<WebhookSubscription, CustomErrors> {
+        let subscription = sqlx::query_as::<_, WebhookSubscription>(
+            "INSERT INTO webhook_subscriptions
+                 (id, url, event_types, secret, description, created_by, organisation_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(&request.url)
+        .bind(&request.event_types)
+        .bind(key_ring.encrypt(secret)?)
+        .bind(&request.description)
+        .bind(created_by)
+        .bind(organisation_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(subscription)
+    }
+
+    pub async fn get_all(
+        &self,
+        pool: &Pool<Postgres>,
+    ) -> Result<Vec<WebhookSubscription>, CustomErrors> {
+        let subscriptions = sqlx::query_as::<_, WebhookSubscription>(
+            "SELECT * FROM webhook_subscriptions ORDER BY created_at",
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(subscriptions)
+    }
+
+    pub async fn get_by_id(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+    ) -> Result<WebhookSubscription, CustomErrors> {
+        sqlx::query_as::<_, WebhookSubscription>(
+            "SELECT * FROM webhook_subscriptions WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(CustomErrors::NotFound)
+    }
+
+    pub async fn update(
+        &self,
+        pool: &Pool<Postgres>,
+        id: Uuid,
+        patch: WebhookSubscriptionPatch,
+    ) -> Result<WebhookSubscription, CustomErrors> {
+        sqlx::query_as::<_, WebhookSubscription>(
+            "UPDATE webhook_subscriptions SET
+                url = COALESCE($2, url),
+                event_types = COALESCE($3, event_types),
+                description = COALESCE($4, description),
+                active = COALESCE($5, active),
+                consecutive_failures = CASE WHEN $5 THEN 0 ELSE consecutive_failures END,
+                disabled_at = CASE WHEN $5 THEN NULL WHEN $5 = FALSE THEN CURRENT_TIMESTAMP ELSE disabled_at END,
+                disabled_reason = CASE WHEN $5 THEN NULL WHEN $5 = FALSE THEN 'Disabled by user' ELSE disabled_reason END,
+                updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(patch.url)
+        .bind(patch.event_types)
+        .bind(patch.description)
+        .bind(patch.active)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(CustomErrors::NotFound)
+    }
+
+    pub async fn rotate_secret(
+        &self,
+        pool: &Pool<Postgres>,
+        key_ring: &KeyRing,
+        id: Uuid,
+        secret: &str,
+    ) -> Result<WebhookSubscription, CustomErrors> {
+        sqlx::query_as::<_, WebhookSubscription>(
+            "UPDATE webhook_subscriptions SET secret = $2, updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(key_ring.encrypt(secret)?)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(CustomErrors::NotFound)
+    }
+
+    /// Deletes the subscription together with its delivery log.
+    pub async fn delete(&self, pool: &Pool<Postgres>, id: Uuid) -> Result<(), CustomErrors> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Queues a delivery of `event` for every active subscription to its type
+    /// in the organisation whose branch holds the event's record. Runs inside
+    /// the outbox transaction, so deliveries exist exactly when the change
+    /// that raised the event committed.
+    pub async fn fan_out(
+        tx: &mut Transaction<'_, Postgres>,
+        event: &OutboxEvent,
+    ) -> Result<(), sqlx::Error> {
+        let event_type = match WebhookEventType::for_outbox_event(&event.event_type) {
+            Some(event_type) => event_type.as_str(),
+            None => return Ok(()),
+        };
+        let payload = json!({
+            "id": event.id,
+            "type": event_type,
+            "created_at": event.created_at,
+            "data": event.data,
+        });
+
+        // An event whose record cannot be found reaches no one.
+        sqlx::query(
+            "WITH event_branch AS (
+                 SELECT CASE $4
+                     WHEN 'property' THEN (SELECT branch_id FROM property_core WHERE property_id = $5)
+                     WHEN 'landlord' THEN (SELECT branch_id FROM landlord_details WHERE landlord_id = $5)
+                     WHEN 'diary_event' THEN (SELECT branch_id FROM events WHERE id = $5)
+                     WHEN 'staff_user' THEN (SELECT branch_id FROM staff_users WHERE user_id = $5)
+                 END AS branch_id
+             )
+             INSERT INTO webhook_deliveries (id, subscription_id, event_id, event_type, payload)
+             SELECT gen_random_uuid(), s.id, $1, $2, $3
+             FROM webhook_subscriptions s
+             JOIN branches b ON b.organisation_id = s.organisation_id
+             JOIN event_branch e ON e.branch_id = b.id
+             WHERE s.active AND $2 = ANY(s.event_types)",
+        )
+        .bind(event.id)
+        .bind(event_type)
+        .bind(payload)
+        .bind(&event.aggregate_type)
+        .bind(event.aggregate_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_deliveries(
+        &self,
+        pool: &Pool<Postgres>,
+        subscription_id: Uuid,
+        status: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, CustomErrors> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            "SELECT * FROM webhook_deliveries
+             WHERE subscription_id = $1 AND ($2::text IS NULL OR status = $2)
+             AND EXISTS (SELECT 1 FROM webhook_subscriptions s WHERE s.id = $1)
+             ORDER BY created_at DESC
+             LIMIT $3",
+        )
+        .bind(subscription_id)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+        Ok(deliveries)
+    }
+
+    /// Queues a fresh copy of a delivery; the original stays in the log as is.
+    pub async fn replay(
+        &self,
+        pool: &Pool<Postgres>,
+        subscription_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<WebhookDelivery, CustomErrors> {
+        sqlx::query_as::<_, WebhookDelivery>(
+            "INSERT INTO webhook_deliveries (id, subscription_id, event_id, event_type, payload, replay_of)
+             SELECT $3, subscription_id, event_id, event_type, payload, id
+             FROM webhook_deliveries
+             WHERE id = $2 AND subscription_id = $1
+             AND EXISTS (SELECT 1 FROM webhook_subscriptions s WHERE s.id = $1)
+             RETURNING *",
+        )
+        .bind(subscription_id)
+        .bind(delivery_id)
+        .bind(Uuid::new_v4())
+        .fetch_optional(pool)
+        .await?
+        .ok_or(CustomErrors::NotFound)
+    }
+
+    /// Leases up to `limit` due deliveries of active subscriptions by pushing
+    /// their next attempt past `lease_until`, so a crashed dispatcher's work is
+    /// picked up again once the lease runs out.
+    pub async fn claim_due(
+        &self,
+        pool: &Pool<Postgres>,
+        limit: i64,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<DueDelivery>, sqlx::Error> {
+        sqlx::query_as::<_, DueDelivery>(
+            "WITH due AS (
+                SELECT d.id FROM webhook_deliveries d
+                JOIN webhook_subscriptions s ON s.id = d.subscription_id
+                WHERE d.status = 'pending' AND d.next_attempt_at <= CURRENT_TIMESTAMP AND s.active
+                ORDER BY d.next_attempt_at
+                LIMIT $1
+                FOR UPDATE OF d SKIP LOCKED
+             )
+             UPDATE webhook_deliveries d SET next_attempt_at = $2
+             FROM due, webhook_subscriptions s
+             WHERE d.id = due.id AND s.id = d.subscription_id
+             RETURNING d.id, d.subscription_id, d.event_type, d.payload, d.attempts, s.url, s.secret",
+        )
+        .bind(limit)
+        .bind(lease_until)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn record_success(
+        &self,
+        pool: &Pool<Postgres>,
+        delivery: &DueDelivery,
+        status_code: i32,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = 'delivered', attempts = attempts + 1,
+                last_attempt_at = CURRENT_TIMESTAMP, delivered_at = CURRENT_TIMESTAMP,
+                last_status_code = $2, last_error = NULL
+             WHERE id = $1",
+        )
+        .bind(delivery.id)
+        .bind(status_code)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE webhook_subscriptions SET consecutive_failures = 0 WHERE id = $1")
+            .bind(delivery.subscription_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    /// Records a failed attempt. With `next_attempt_at` of `None` the delivery
+    /// is given up on. The subscription is disabled once `disable_after`
+    /// attempts in a row have failed.
+    pub async fn record_failure(
+        &self,
+        pool: &Pool<Postgres>,
+        delivery: &DueDelivery,
+        status_code: Option<i32>,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+        disable_after: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE webhook_deliveries SET
+                status = CASE WHEN $4::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                attempts = attempts + 1, last_attempt_at = CURRENT_TIMESTAMP,
+                last_status_code = $2, last_error = $3
+             WHERE id = $1",
+        )
+        .bind(delivery.id)
+        .bind(status_code)
+        .bind(error)
+        .bind(next_attempt_at)
+        .execute(&mut *tx)
+        .await?;
+        let disabled = sqlx::query_scalar::<_, bool>(
+            "UPDATE webhook_subscriptions SET
+                consecutive_failures = consecutive_failures + 1,
+                active = consecutive_failures + 1 < $2,
+                disabled_at = CASE WHEN consecutive_failures + 1 >= $2 THEN CURRENT_TIMESTAMP ELSE disabled_at END,
+                disabled_reason = CASE WHEN consecutive_failures + 1 >= $2
+                    THEN 'Disabled after ' || $2 || ' failed deliveries in a row' ELSE disabled_reason END
+             WHERE id = $1 AND active
+             RETURNING NOT active",
+        )
+        .bind(delivery.subscription_id)
+        .bind(disable_after)
+        .fetch_optional(&mut *tx)
+        .await?
+        .unwrap_or(false);
+        tx.commit().await?;
+        Ok(disabled)
+    }
+
+    /// Re-encrypts secrets still under an older key version.
+    pub async fn reencrypt_secrets(
+        &self,
+        pool: &Pool<Postgres>,
+        key_ring: &KeyRing,
+    ) -> Result<u64, CustomErrors> {
+        let rows =
+            sqlx::query_as::<_, (Uuid, String)>("SELECT id, secret FROM webhook_subscriptions")
+                .fetch_all(pool)
+                .await?;
+        let mut updated = 0;
+        for (id, secret) in rows {
+            if !key_ring.needs_reencryption(&secret) {
+                continue;
+            }
+            let plaintext = key_ring.decrypt(&secret)?;
+            sqlx::query("UPDATE webhook_subscriptions SET secret = $2 WHERE id = $1")
+                .bind(id)
+                .bind(key_ring.encrypt(&plaintext)?)
+                .execute(pool)
+                .await?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+}
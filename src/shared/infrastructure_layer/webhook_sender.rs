@@ -0,0 +1,132 @@
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::Sha256;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::shared::domain_layer::webhook::{
+    is_internal_address, is_internal_host, DELIVERY_ID_HEADER, EVENT_TYPE_HEADER, SIGNATURE_HEADER,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a delivery attempt failed; `status` is set when the endpoint answered.
+pub struct SendFailure {
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+/// Resolves webhook hosts, refusing any that resolve to this machine or a
+/// private network. Connections use the addresses checked here, so a name
+/// that changes what it resolves to after the subscription was saved cannot
+/// reach internal services either.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| is_internal_address(addr.ip())) {
+                return Err(format!(
+                    "{} resolves to internal address {}",
+                    name.as_str(),
+                    addr.ip()
+                )
+                .into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+pub struct WebhookSender {
+    client: reqwest::Client,
+}
+
+impl WebhookSender {
+    pub fn new() -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()?;
+        Ok(WebhookSender { client })
+    }
+
+    /// POSTs `body` to `url`. Any 2xx answer counts as delivered.
+    pub async fn send(
+        &self,
+        url: &str,
+        delivery_id: Uuid,
+        event_type: &str,
+        secret: &str,
+        body: String,
+    ) -> Result<u16, SendFailure> {
+        // Addresses written out in the URL are never resolved, so are checked
+        // here.
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|parsed| parsed.host_str().map(str::to_string));
+        if let Some(host) = host.filter(|host| is_internal_host(host)) {
+            return Err(SendFailure {
+                status: None,
+                message: format!("Refusing to send to internal host {}", host),
+            });
+        }
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = sign(secret, timestamp, &body);
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(DELIVERY_ID_HEADER, delivery_id.to_string())
+            .header(EVENT_TYPE_HEADER, event_type)
+            .header(
+                SIGNATURE_HEADER,
+                format!("t={},v1={}", timestamp, signature),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SendFailure {
+                status: None,
+                message: describe(&e),
+            })?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err(SendFailure {
+                status: Some(status.as_u16()),
+                message: format!("Endpoint answered {}", status),
+            })
+        }
+    }
+}
+
+/// The error with its causes, which say why the request could not be sent.
+fn describe(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message = format!("{}: {}", message, cause);
+        source = cause.source();
+    }
+    message
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}.{body}"`. Receivers recompute it with
+/// their secret and should reject old timestamps to prevent replays.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
@@ -0,0 +1,17 @@
+use crate::shared::application_layer::branch_service;
+use actix_web::web;
+
+/// Organisations and branches. Staff access and transfers are routed from
+/// the users, landlords and properties scopes.
+pub fn branch_configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/organisations")
+            .route("", web::get().to(branch_service::get_organisations))
+            .route("", web::post().to(branch_service::create_organisation)),
+    )
+    .service(
+        web::scope("/api/v1/branches")
+            .route("", web::get().to(branch_service::get_branches))
+            .route("", web::post().to(branch_service::create_branch)),
+    );
+}
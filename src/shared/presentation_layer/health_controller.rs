@@ -0,0 +1,10 @@
+use crate::shared::application_layer::health_service;
+use actix_web::web;
+
+pub fn health_configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/health")
+            .route("/live", web::get().to(health_service::liveness))
+            .route("/ready", web::get().to(health_service::readiness)),
+    );
+}
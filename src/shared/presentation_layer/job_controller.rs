@@ -0,0 +1,21 @@
+use crate::shared::application_layer::job_service;
+use actix_web::web;
+
+pub fn job_configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/jobs")
+            .route("", web::get().to(job_service::get_jobs))
+            .route("/schedules", web::get().to(job_service::get_schedules))
+            .route(
+                "/schedules/{name}",
+                web::patch().to(job_service::update_schedule),
+            )
+            .route(
+                "/schedules/{name}/run",
+                web::post().to(job_service::run_schedule_now),
+            )
+            .route("/{job_id}", web::get().to(job_service::get_job))
+            .route("/{job_id}/retry", web::post().to(job_service::retry_job))
+            .route("/{job_id}/cancel", web::post().to(job_service::cancel_job)),
+    );
+}
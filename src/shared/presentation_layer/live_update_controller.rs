@@ -0,0 +1,9 @@
+use crate::shared::application_layer::live_update_service;
+use actix_web::web;
+
+pub fn live_update_configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        "/api/v1/live",
+        web::get().to(live_update_service::live_updates),
+    );
+}
@@ -0,0 +1,9 @@
+pub mod retention_command;
+pub mod health_controller;
+pub mod webhook_controller;
+pub mod live_update_controller;
+pub mod job_controller;
+pub mod search_controller;
+pub mod openapi_controller;
+pub mod openapi_command;
+pub mod branch_controller;
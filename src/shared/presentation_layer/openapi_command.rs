@@ -0,0 +1,41 @@
+use clap::{Arg, ArgMatches, Command};
+use utoipa::OpenApi;
+
+use crate::shared::presentation_layer::openapi_controller::ApiDoc;
+
+pub fn openapi_command() -> Command {
+    Command::new("openapi")
+        .about("Print the OpenAPI document, or check a committed copy is up to date")
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .value_name("FILE")
+                .help("Fail if FILE differs from the document the code generates"),
+        )
+}
+
+pub fn run(matches: &ArgMatches) -> std::io::Result<()> {
+    let document = ApiDoc::openapi()
+        .to_pretty_json()
+        .map_err(std::io::Error::other)?
+        + "\n";
+
+    match matches.get_one::<String>("check") {
+        Some(path) => {
+            let committed = std::fs::read_to_string(path)?;
+            if committed.replace("\r\n", "\n") == document {
+                println!("{} is up to date", path);
+                Ok(())
+            } else {
+                Err(std::io::Error::other(format!(
+                    "{} is out of date; regenerate it with `cargo run -- openapi > {}`",
+                    path, path
+                )))
+            }
+        }
+        None => {
+            print!("{}", document);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use actix_web::web;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::diary::application_layer::{diary_event_service, diary_settings_service};
+use crate::landlord::application_layer::{
+    landlord_data_service, landlord_financial_service, landlord_service,
+};
+use crate::properties::application_layer::{
+    certificate_documents_service, properties_service, property_address_service,
+    property_certificates_service, property_compliance_service, property_keys_service,
+    property_photos_service, property_rental_info_service, property_services_service,
+    property_specifications_service,
+};
+use crate::shared::application_layer::{
+    branch_service, health_service, job_service, live_update_service, search_service,
+    webhook_service,
+};
+use crate::user::application_layer::{permission_service, user_service};
+
+pub const OPENAPI_PATH: &str = "/api/openapi.json";
+
+/// Every routed handler. A route added to a `*_configure_routes` function
+/// needs a `#[utoipa::path]` on its handler and an entry here.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Real Estate Backend API",
+        description = "Staff-facing API for properties, landlords and the diary."
+    ),
+    paths(
+        health_service::liveness,
+        health_service::readiness,
+        user_service::get_all_users,
+        user_service::get_user_by_id,
+        user_service::register_user,
+        user_service::update_user,
+        user_service::delete_user,
+        user_service::restore_user,
+        user_service::login_user,
+        user_service::refresh_token,
+        user_service::logout_user,
+        user_service::get_user_full_names,
+        permission_service::get_user_permissions,
+        permission_service::grant_permission,
+        permission_service::revoke_permission,
+        property_photos_service::upload_images,
+        property_photos_service::get_upload_status,
+        property_photos_service::get_photos,
+        property_photos_service::get_photo_file,
+        properties_service::get_all,
+        properties_service::add,
+        properties_service::search,
+        properties_service::get_one,
+        properties_service::transition_status,
+        properties_service::get_status_history,
+        properties_service::get_full,
+        properties_service::update,
+        properties_service::patch,
+        properties_service::get_by_landlord,
+        properties_service::get_by_staff,
+        properties_service::delete,
+        properties_service::restore,
+        property_address_service::get_by_property_id,
+        property_address_service::add,
+        property_address_service::update,
+        property_specifications_service::get_specifications,
+        property_specifications_service::add_specifications,
+        property_specifications_service::update_specifications,
+        property_specifications_service::delete_specifications,
+        property_rental_info_service::get_rental_info,
+        property_rental_info_service::add_rental_info,
+        property_rental_info_service::update_rental_info,
+        property_rental_info_service::delete_rental_info,
+        property_services_service::get_services,
+        property_services_service::get_service,
+        property_services_service::add_service,
+        property_services_service::update_service,
+        property_services_service::delete_service,
+        property_keys_service::get_keys,
+        property_keys_service::get_key,
+        property_keys_service::add_key,
+        property_keys_service::update_key,
+        property_keys_service::delete_key,
+        property_certificates_service::get_certificates,
+        property_certificates_service::get_certificate,
+        property_certificates_service::add_certificate,
+        property_certificates_service::update_certificate,
+        property_certificates_service::delete_certificate,
+        certificate_documents_service::get_documents,
+        certificate_documents_service::get_document,
+        certificate_documents_service::upload_document,
+        certificate_documents_service::verify_document,
+        certificate_documents_service::download_document,
+        property_compliance_service::get_compliance,
+        property_compliance_service::get_dashboard,
+        diary_settings_service::get_all_diary_settings,
+        diary_settings_service::get_diary_settings_by_id,
+        diary_settings_service::create_diary_settings,
+        diary_settings_service::update_diary_settings,
+        diary_settings_service::delete_diary_settings,
+        diary_event_service::get_all_events,
+        diary_event_service::create_event,
+        diary_event_service::get_event_by_id,
+        diary_event_service::update_event,
+        diary_event_service::delete_event,
+        diary_event_service::restore_event,
+        diary_event_service::get_event_by_user_id,
+        diary_event_service::get_event_by_user_id_with_dates,
+        landlord_service::get_all_landlords,
+        landlord_service::register_landlord,
+        landlord_service::delete_landlord,
+        landlord_service::complete_registration,
+        landlord_service::restore_landlord,
+        landlord_data_service::export_landlord_data,
+        landlord_data_service::export_landlord_archive,
+        landlord_data_service::erase_landlord_data,
+        landlord_data_service::erase_contact_data,
+        landlord_financial_service::get_bank_details,
+        landlord_financial_service::reveal_bank_details,
+        landlord_financial_service::get_lettings_management,
+        landlord_financial_service::reveal_lettings_management,
+        webhook_service::get_all_webhooks,
+        webhook_service::create_webhook,
+        webhook_service::get_event_types,
+        webhook_service::get_webhook,
+        webhook_service::update_webhook,
+        webhook_service::delete_webhook,
+        webhook_service::rotate_webhook_secret,
+        webhook_service::get_deliveries,
+        webhook_service::replay_delivery,
+        live_update_service::live_updates,
+        job_service::get_jobs,
+        job_service::get_schedules,
+        job_service::update_schedule,
+        job_service::run_schedule_now,
+        job_service::get_job,
+        job_service::retry_job,
+        job_service::cancel_job,
+        search_service::search,
+        branch_service::get_organisations,
+        branch_service::create_organisation,
+        branch_service::get_branches,
+        branch_service::create_branch,
+        branch_service::get_staff_branches,
+        branch_service::grant_staff_branch,
+        branch_service::revoke_staff_branch,
+        branch_service::transfer_user,
+        branch_service::transfer_landlord,
+        branch_service::transfer_property,
+    ),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "health"),
+        (name = "users"),
+        (name = "properties"),
+        (name = "diary settings"),
+        (name = "diary events"),
+        (name = "landlords"),
+        (name = "webhooks"),
+        (name = "live updates"),
+        (name = "jobs"),
+        (name = "search"),
+        (name = "branches"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Login sets an `access_token` cookie; API clients may send the same token
+/// as a bearer token instead.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "cookie_auth",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("access_token"))),
+        );
+    }
+}
+
+/// Serves the document at `/api/openapi.json` and Swagger UI at `/api/docs/`.
+pub fn openapi_configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(SwaggerUi::new("/api/docs/{_:.*}").url(OPENAPI_PATH, ApiDoc::openapi()));
+}
@@ -0,0 +1,33 @@
+use clap::{ArgMatches, Command};
+use sqlx::{Pool, Postgres};
+
+use crate::shared::{
+    application_layer::retention_service, domain_layer::retention_policy::RetentionPolicy,
+    infrastructure_layer::document_storage,
+};
+
+pub fn purge_command() -> Command {
+    Command::new("purge")
+        .about("Hard-delete soft-deleted records that are past the retention period")
+}
+
+pub async fn run(_matches: &ArgMatches, pool: &Pool<Postgres>) -> std::io::Result<()> {
+    let policy = RetentionPolicy::from_env();
+    let storage =
+        document_storage::storage_from_env().map_err(|e| std::io::Error::other(e.to_string()))?;
+    match retention_service::run_purge(pool, storage.as_ref(), &policy).await {
+        Ok(summary) => {
+            println!(
+                "Purged {} events, {} properties, {} landlords, {} staff and {} files ({} skipped)",
+                summary.events,
+                summary.properties,
+                summary.landlords,
+                summary.staff,
+                summary.files,
+                summary.skipped
+            );
+            Ok(())
+        }
+        Err(e) => Err(std::io::Error::other(e.to_string())),
+    }
+}
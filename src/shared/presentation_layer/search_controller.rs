@@ -0,0 +1,6 @@
+use crate::shared::application_layer::search_service;
+use actix_web::web;
+
+pub fn search_configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/v1/search", web::get().to(search_service::search));
+}
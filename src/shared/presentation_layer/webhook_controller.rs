@@ -0,0 +1,35 @@
+use crate::shared::application_layer::webhook_service;
+use actix_web::web;
+
+pub fn webhook_configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/webhooks")
+            .route("", web::get().to(webhook_service::get_all_webhooks))
+            .route("", web::post().to(webhook_service::create_webhook))
+            .route(
+                "/event-types",
+                web::get().to(webhook_service::get_event_types),
+            )
+            .route("/{webhook_id}", web::get().to(webhook_service::get_webhook))
+            .route(
+                "/{webhook_id}",
+                web::patch().to(webhook_service::update_webhook),
+            )
+            .route(
+                "/{webhook_id}",
+                web::delete().to(webhook_service::delete_webhook),
+            )
+            .route(
+                "/{webhook_id}/secret",
+                web::post().to(webhook_service::rotate_webhook_secret),
+            )
+            .route(
+                "/{webhook_id}/deliveries",
+                web::get().to(webhook_service::get_deliveries),
+            )
+            .route(
+                "/{webhook_id}/deliveries/{delivery_id}/replay",
+                web::post().to(webhook_service::replay_delivery),
+            ),
+    );
+}
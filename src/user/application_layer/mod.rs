@@ -1 +1,2 @@
+pub mod permission_service;
 pub mod user_service;
@@ -0,0 +1,252 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::shared::domain_layer::{api_schema::ErrorResponse, branch::MANAGE_ORGANISATIONS};
+use crate::user::{
+    domain_layer::{user::UserLevel, user_permission::StaffPermission},
+    infrastructure_layer::{
+        audit_trail_repository::AuditTrailRepository,
+        jwt_repo,
+        permission_repository::{CustomErrors, PermissionRepository},
+        user_repository::UserRepository,
+    },
+};
+use crate::AppState;
+
+/// Resolves the calling user and checks they hold `permission`. The error is
+/// the response to send back: 401 when not logged in, 403 without the grant.
+pub async fn require_permission(
+    req: &HttpRequest,
+    state: &web::Data<AppState>,
+    permission: &str,
+) -> Result<Uuid, HttpResponse> {
+    let user_id = jwt_repo::authenticated_user_id(req, state)
+        .await
+        .ok_or_else(|| HttpResponse::Unauthorized().json(json!({"error": "Not logged in"})))?;
+    let repo = PermissionRepository::new();
+    match repo.has_permission(&state.db, user_id, permission).await {
+        Ok(true) => Ok(user_id),
+        Ok(false) => Err(HttpResponse::Forbidden()
+            .json(json!({"error": format!("Missing permission {}", permission)}))),
+        Err(e) => Err(HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))),
+    }
+}
+
+/// Writes an entry to the audit trail for the calling user. Failures are
+/// logged rather than returned so they never hide the result of the action.
+pub async fn audit(
+    req: &HttpRequest,
+    state: &web::Data<AppState>,
+    user_id: Uuid,
+    action_type: &str,
+    description: String,
+) {
+    // require_audit has already logged the failure.
+    let _ = require_audit(req, state, user_id, action_type, description).await;
+}
+
+/// Writes an entry to the audit trail before an action that must not happen
+/// unaudited, such as revealing sensitive data. The error is the response to
+/// send back instead of going ahead: 500 when the entry could not be written.
+pub async fn require_audit(
+    req: &HttpRequest,
+    state: &web::Data<AppState>,
+    user_id: Uuid,
+    action_type: &str,
+    description: String,
+) -> Result<(), HttpResponse> {
+    let ip_address = req
+        .connection_info()
+        .realip_remote_addr()
+        .map(str::to_string);
+    let repo = AuditTrailRepository::new();
+    repo.record(&state.db, user_id, action_type, description, ip_address)
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            log::error!("Failed to write audit trail entry: {}", e);
+            HttpResponse::InternalServerError()
+                .json(json!({"error": "Could not write the audit trail"}))
+        })
+}
+
+/// Resolves the calling user and checks they are an admin. The error is the
+/// response to send back, as for `require_permission`.
+pub async fn require_admin(
+    req: &HttpRequest,
+    state: &web::Data<AppState>,
+) -> Result<Uuid, HttpResponse> {
+    let user_id = jwt_repo::authenticated_user_id(req, state)
+        .await
+        .ok_or_else(|| HttpResponse::Unauthorized().json(json!({"error": "Not logged in"})))?;
+    let repo = UserRepository::new();
+    match repo.get_by_id(state.clone().into_inner(), user_id).await {
+        Ok(user) if matches!(user.acc_level, Some(UserLevel::Admin)) => Ok(user_id),
+        Ok(_) => Err(HttpResponse::Forbidden().json(json!({"error": "Admin access required"}))),
+        Err(_) => Err(HttpResponse::Unauthorized().json(json!({"error": "Not logged in"}))),
+    }
+}
+
+/// Staff in other branches are invisible to the caller, so their permissions
+/// cannot be changed either.
+async fn require_visible_user(
+    state: &web::Data<AppState>,
+    user_id: Uuid,
+) -> Result<(), HttpResponse> {
+    UserRepository::new()
+        .get_by_id(state.clone().into_inner(), user_id)
+        .await
+        .map(|_| ())
+        .map_err(|_| HttpResponse::NotFound().json(json!({"error": "User not found"})))
+}
+
+/// Admins only run their own organisation, so only staff who can already
+/// reach every organisation may hand that on or take it away.
+async fn require_grantable(
+    state: &web::Data<AppState>,
+    admin_id: Uuid,
+    permission: &str,
+) -> Result<(), HttpResponse> {
+    if permission != MANAGE_ORGANISATIONS {
+        return Ok(());
+    }
+    let repo = PermissionRepository::new();
+    match repo
+        .has_permission(&state.db, admin_id, MANAGE_ORGANISATIONS)
+        .await
+    {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(HttpResponse::Forbidden()
+            .json(json!({"error": format!("Missing permission {}", MANAGE_ORGANISATIONS)}))),
+        Err(e) => Err(HttpResponse::InternalServerError().json(json!({"error": e.to_string()}))),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}/permissions",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "Staff user id")),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "Permissions granted to the user", body = [StaffPermission]),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required", body = ErrorResponse),
+    )
+)]
+pub async fn get_user_permissions(
+    state: web::Data<AppState>,
+    user_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req, &state).await {
+        return response;
+    }
+    let repo = PermissionRepository::new();
+    match repo.list(&state.db, user_id.into_inner()).await {
+        Ok(permissions) => HttpResponse::Ok().json(permissions),
+        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{user_id}/permissions/{permission}",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "Staff user id"),
+        ("permission" = String, Path, description = "Permission name, e.g. `manage_webhooks`"),
+    ),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 200, description = "The grant", body = StaffPermission),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required; `manage_organisations` also needs the caller to hold it", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
+pub async fn grant_permission(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, String)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match require_admin(&req, &state).await {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    let (user_id, permission) = path.into_inner();
+    if let Err(response) = require_visible_user(&state, user_id).await {
+        return response;
+    }
+    if let Err(response) = require_grantable(&state, admin_id, &permission).await {
+        return response;
+    }
+    let repo = PermissionRepository::new();
+    match repo.grant(&state.db, user_id, &permission, admin_id).await {
+        Ok(granted) => {
+            audit(
+                &req,
+                &state,
+                admin_id,
+                "permission_granted",
+                format!("Granted {} to user {}", permission, user_id),
+            )
+            .await;
+            HttpResponse::Ok().json(granted)
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{user_id}/permissions/{permission}",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "Staff user id"),
+        ("permission" = String, Path, description = "Permission name"),
+    ),
+    security(("bearer_auth" = []), ("cookie_auth" = [])),
+    responses(
+        (status = 204, description = "Permission revoked"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Admin access required; `manage_organisations` also needs the caller to hold it", body = ErrorResponse),
+        (status = 404, description = "User or permission not found", body = ErrorResponse),
+    )
+)]
+pub async fn revoke_permission(
+    state: web::Data<AppState>,
+    path: web::Path<(Uuid, String)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match require_admin(&req, &state).await {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    let (user_id, permission) = path.into_inner();
+    if let Err(response) = require_visible_user(&state, user_id).await {
+        return response;
+    }
+    if let Err(response) = require_grantable(&state, admin_id, &permission).await {
+        return response;
+    }
+    let repo = PermissionRepository::new();
+    match repo.revoke(&state.db, user_id, &permission).await {
+        Ok(()) => {
+            audit(
+                &req,
+                &state,
+                admin_id,
+                "permission_revoked",
+                format!("Revoked {} from user {}", permission, user_id),
+            )
+            .await;
+            HttpResponse::NoContent().finish()
+        }
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Permission not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
+    }
+}
@@ -1,21 +1,28 @@
+use crate::shared::application_layer::conditional_request;
+use crate::shared::domain_layer::api_schema::{
+    ErrorResponse, IdempotencyHeader, IfMatchHeader, LoginResponse, MessageResponse,
+    RefreshTokenRequest,
+};
+use crate::shared::infrastructure_layer::branch_scope;
 use crate::user::{
-    domain_layer::user::StaffUser,
-    infrastructure_layer::{jwt_repo, user_repository::UserRepository},
+    domain_layer::user::{StaffUser, StaffUserFullNames},
+    infrastructure_layer::{
+        jwt_repo,
+        user_repository::{CustomErrors, UserRepository},
+    },
 };
 use crate::AppState;
 use actix_web::HttpRequest;
-use actix_web::{
-    cookie::Cookie,
-    http::{
-        self,
-        header::{self, HeaderMap},
-    },
-    web, HttpResponse, Responder,
-};
-use chrono::{Duration, Utc};
+use actix_web::{cookie::Cookie, web, HttpResponse, Responder};
 use serde_json::json;
 use uuid::Uuid;
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    tag = "users",
+    responses((status = 200, description = "All staff users", body = [StaffUser]))
+)]
 pub async fn get_all_users(state: web::Data<AppState>) -> impl Responder {
     let repo = UserRepository::new();
     match repo.get_all(state.into_inner()).await {
@@ -24,20 +31,42 @@ pub async fn get_all_users(state: web::Data<AppState>) -> impl Responder {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/byuserid/{user_id}",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "Staff user id")),
+    responses(
+        (status = 200, description = "The staff user", body = StaffUser, headers(("ETag" = String))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+    )
+)]
 pub async fn get_user_by_id(
     state: web::Data<AppState>,
     user_id: web::Path<Uuid>,
+    req: HttpRequest,
 ) -> impl Responder {
     let repo = UserRepository::new();
     match repo
         .get_by_id(state.into_inner(), user_id.into_inner())
         .await
     {
-        Ok(user) => HttpResponse::Ok().json(user),
+        Ok(user) => conditional_request::conditional_get(&req, user.updated_at, &user),
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/users",
+    tag = "users",
+    params(IdempotencyHeader),
+    responses(
+        (status = 200, description = "The registered staff user", body = StaffUser),
+        (status = 409, description = "A request with this `Idempotency-Key` is still in progress", body = ErrorResponse),
+        (status = 422, description = "The `Idempotency-Key` was used with a different body", body = ErrorResponse),
+    )
+)]
 pub async fn register_user(
     state: web::Data<AppState>,
     user: web::Json<StaffUser>,
@@ -49,6 +78,12 @@ pub async fn register_user(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/staff",
+    tag = "users",
+    responses((status = 200, description = "Id and name of every staff user", body = [StaffUserFullNames]))
+)]
 pub async fn get_user_full_names(state: web::Data<AppState>) -> impl Responder {
     let repo = UserRepository::new();
     match repo.get_all_user_full_names(state.into_inner()).await {
@@ -57,22 +92,94 @@ pub async fn get_user_full_names(state: web::Data<AppState>) -> impl Responder {
     }
 }
 
-pub async fn update_user(state: web::Data<AppState>, user: web::Json<StaffUser>) -> impl Responder {
+#[utoipa::path(
+    put,
+    path = "/api/v1/users",
+    tag = "users",
+    params(IfMatchHeader),
+    responses(
+        (status = 200, description = "The updated staff user", body = StaffUser, headers(("ETag" = String))),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 412, description = "The user changed since the `If-Match` ETag", body = ErrorResponse),
+        (status = 428, description = "`If-Match` header missing", body = ErrorResponse),
+    )
+)]
+pub async fn update_user(
+    state: web::Data<AppState>,
+    user: web::Json<StaffUser>,
+    req: HttpRequest,
+) -> impl Responder {
+    let expected_updated_at = match conditional_request::expected_version(&req) {
+        Ok(expected_updated_at) => expected_updated_at,
+        Err(response) => return response,
+    };
     let repo = UserRepository::new();
-    match repo.update(state.into_inner(), user.into_inner()).await {
-        Ok(updated_user) => HttpResponse::Ok().json(updated_user),
+    match repo
+        .update(state.into_inner(), user.into_inner(), expected_updated_at)
+        .await
+    {
+        Ok(updated_user) => conditional_request::updated(updated_user.updated_at, &updated_user),
+        Err(CustomErrors::PreconditionFailed) => conditional_request::precondition_failed(),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "User not found"}))
+        }
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{user_id}",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "Staff user id")),
+    responses(
+        (status = 200, description = "User soft-deleted"),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    )
+)]
 pub async fn delete_user(state: web::Data<AppState>, user_id: web::Path<Uuid>) -> impl Responder {
     let repo = UserRepository::new();
     match repo.delete(state.into_inner(), user_id.into_inner()).await {
         Ok(_) => HttpResponse::Ok().finish(),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "User not found"}))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{user_id}/restore",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "Staff user id")),
+    responses(
+        (status = 200, description = "The restored staff user", body = StaffUser),
+        (status = 404, description = "Deleted user not found", body = ErrorResponse),
+    )
+)]
+pub async fn restore_user(state: web::Data<AppState>, user_id: web::Path<Uuid>) -> impl Responder {
+    let repo = UserRepository::new();
+    match repo.restore(state.into_inner(), user_id.into_inner()).await {
+        Ok(user) => HttpResponse::Ok().json(user),
+        Err(CustomErrors::NotFound) => {
+            HttpResponse::NotFound().json(json!({"error": "Deleted user not found"}))
+        }
         Err(e) => HttpResponse::InternalServerError().json(e.to_string()),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/login",
+    tag = "users",
+    request_body(content = StaffUser, description = "Only `username` and `passwd` are checked"),
+    responses(
+        (status = 200, description = "Logged in; sets the `access_token` and `refresh_token` cookies", body = LoginResponse),
+        (status = 400, description = "Password missing", body = ErrorResponse),
+        (status = 401, description = "Wrong username or password", body = ErrorResponse),
+    )
+)]
 pub async fn login_user(state: web::Data<AppState>, user: web::Json<StaffUser>) -> impl Responder {
     let repo = UserRepository::new();
 
@@ -81,9 +188,8 @@ pub async fn login_user(state: web::Data<AppState>, user: web::Json<StaffUser>)
         return HttpResponse::BadRequest().json(json!({"error": "Password is required"}));
     }
 
-    // Pass the `Arc<AppState>` by calling `.into_inner()`
-    match repo
-        .login(state.clone().into_inner(), user.into_inner())
+    // Nobody is signed in yet, so look the user up across every branch.
+    match branch_scope::unrestricted(repo.login(state.clone().into_inner(), user.into_inner()))
         .await
     {
         Ok(user) => {
@@ -125,9 +231,20 @@ pub async fn login_user(state: web::Data<AppState>, user: web::Json<StaffUser>)
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/refresh",
+    tag = "users",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "New `access_token` cookie set", body = MessageResponse),
+        (status = 400, description = "`refreshToken` missing", body = ErrorResponse),
+        (status = 401, description = "Refresh token invalid or expired", body = ErrorResponse),
+    )
+)]
 pub async fn refresh_token(
     state: web::Data<AppState>,
-    req: HttpRequest,
+    _req: HttpRequest,
     body: web::Json<serde_json::Value>,
 ) -> impl Responder {
     let refresh_token_from_body = if let Some(token) =
@@ -148,9 +265,10 @@ pub async fn refresh_token(
             }
 
             let repo = UserRepository::new();
-            match repo
-                .get_by_id(state.clone().into_inner(), user_id.parse().unwrap())
-                .await
+            match branch_scope::unrestricted(
+                repo.get_by_id(state.clone().into_inner(), user_id.parse().unwrap()),
+            )
+            .await
             {
                 Ok(user) => match jwt_repo::create_token(&user, "user", "access", &state).await {
                     Ok(new_access_token) => {
@@ -174,7 +292,13 @@ pub async fn refresh_token(
     }
 }
 
-pub async fn logout_user(state: web::Data<AppState>) -> impl Responder {
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/logout",
+    tag = "users",
+    responses((status = 200, description = "Token cookies cleared", body = MessageResponse))
+)]
+pub async fn logout_user(_state: web::Data<AppState>) -> impl Responder {
     // Create expired cookies to clear tokens
     let expired_access_cookie = Cookie::build("access_token", "")
         .path("/")
@@ -3,3 +3,4 @@ pub mod user_address;
 pub mod user_audit_trail;
 pub mod user_changes_made;
 pub mod user_note;
+pub mod user_permission;
@@ -1,27 +1,36 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{self, FromRow};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct StaffUser {
     pub user_id: Option<Uuid>,
     pub name: Option<String>,
     pub username: String,
     pub mob_phone: Option<String>,
+    /// Where email notifications go; staff without one get none.
+    pub email: Option<String>,
     pub passwd: String,
     pub acc_level: Option<UserLevel>,
     pub status: Option<UserStatus>,
     pub a_created: Option<NaiveDateTime>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Branch the user belongs to. New users go to the creator's branch; use
+    /// `POST /api/v1/users/{id}/transfer` to move one.
+    #[sqlx(default)]
+    #[schema(read_only)]
+    pub branch_id: Option<Uuid>,
 }
 
-#[derive(Clone, Serialize, Deserialize, FromRow, Debug)]
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
 pub struct StaffUserFullNames {
     pub user_id: Uuid,
     pub name: String,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "user_level", rename_all = "lowercase")]
 pub enum UserLevel {
     Admin,
@@ -30,7 +39,7 @@ pub enum UserLevel {
     Trainee,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type)]
+#[derive(Clone, Serialize, Deserialize, Debug, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "user_status", rename_all = "lowercase")]
 pub enum UserStatus {
     Active,
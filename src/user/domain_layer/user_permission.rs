@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Clone, Serialize, Deserialize, FromRow, Debug, ToSchema)]
+pub struct StaffPermission {
+    pub user_id: Uuid,
+    pub permission: String,
+    pub granted_by: Option<Uuid>,
+    pub granted_at: DateTime<Utc>,
+}
@@ -0,0 +1,44 @@
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::user::domain_layer::user_audit_trail::AuditTrail;
+
+pub struct AuditTrailRepository {}
+
+impl AuditTrailRepository {
+    pub fn new() -> Self {
+        AuditTrailRepository {}
+    }
+
+    pub async fn record(
+        &self,
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        action_type: &str,
+        description: String,
+        ip_address: Option<String>,
+    ) -> Result<AuditTrail, sqlx::Error> {
+        let entry = AuditTrail {
+            entry_id: Uuid::new_v4(),
+            user_id,
+            timestamp: Utc::now(),
+            description,
+            action_type: action_type.to_string(),
+            ip_address,
+        };
+        sqlx::query(
+            "INSERT INTO audit_trail (entry_id, user_id, timestamp, description, action_type, ip_address)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(entry.entry_id)
+        .bind(entry.user_id)
+        .bind(entry.timestamp)
+        .bind(&entry.description)
+        .bind(&entry.action_type)
+        .bind(&entry.ip_address)
+        .execute(pool)
+        .await?;
+        Ok(entry)
+    }
+}
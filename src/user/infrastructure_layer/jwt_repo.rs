@@ -1,4 +1,4 @@
-use actix_web::web;
+use actix_web::{web, HttpRequest};
 use chrono::{Duration, Utc};
 use jsonwebtoken::{
     decode, encode, Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation,
@@ -6,6 +6,7 @@ use jsonwebtoken::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::env;
+use uuid::Uuid;
 
 use crate::{user::domain_layer::user::StaffUser, AppState};
 
@@ -53,7 +54,7 @@ impl Issuer {
 }
 
 impl AuthClaims {
-    pub fn new(issuer: &str, role: Issuer, auth_type: &str, subject: String) -> Self {
+    pub fn new(_issuer: &str, role: Issuer, auth_type: &str, subject: String) -> Self {
         let iat = Utc::now().timestamp();
         let exp = match auth_type {
             "refresh" => (Utc::now() + Duration::days(30)).timestamp(),
@@ -123,3 +124,23 @@ pub async fn verify_token(
 
     Ok(decoded_token)
 }
+
+/// The staff user behind a request, taken from the `Authorization: Bearer`
+/// header or the `access_token` cookie set at login.
+pub async fn authenticated_user_id(
+    req: &HttpRequest,
+    app_state: &web::Data<AppState>,
+) -> Option<Uuid> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or_else(|| {
+            req.cookie("access_token")
+                .map(|cookie| cookie.value().to_string())
+        })?;
+    let claims = verify_token(&token, "access", app_state).await.ok()?;
+    claims.claims.sub.parse().ok()
+}
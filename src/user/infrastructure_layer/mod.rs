@@ -1,4 +1,6 @@
+pub mod audit_trail_repository;
 pub mod auth_repo;
 pub mod custom_error_repo_users;
 pub mod jwt_repo;
+pub mod permission_repository;
 pub mod user_repository;
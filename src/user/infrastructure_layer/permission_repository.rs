@@ -0,0 +1,99 @@
+use actix_web::error::ResponseError;
+use derive_more::Display;
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::user::domain_layer::user_permission::StaffPermission;
+
+#[derive(Debug, Display, Serialize)]
+pub enum CustomErrors {
+    #[display(fmt = "Database error: {}", _0)]
+    DatabaseError(String),
+    #[display(fmt = "Permission not found")]
+    NotFound,
+}
+
+impl ResponseError for CustomErrors {}
+
+pub struct PermissionRepository {}
+
+impl PermissionRepository {
+    pub fn new() -> Self {
+        PermissionRepository {}
+    }
+
+    pub async fn has_permission(
+        &self,
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        permission: &str,
+    ) -> Result<bool, CustomErrors> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (
+                 SELECT 1 FROM staff_permissions p
+                 JOIN staff_users u ON u.user_id = p.user_id
+                 WHERE p.user_id = $1 AND p.permission = $2 AND u.deleted_at IS NULL
+             )",
+        )
+        .bind(user_id)
+        .bind(permission)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))
+    }
+
+    pub async fn list(
+        &self,
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+    ) -> Result<Vec<StaffPermission>, CustomErrors> {
+        sqlx::query_as::<_, StaffPermission>(
+            "SELECT * FROM staff_permissions WHERE user_id = $1 ORDER BY permission",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))
+    }
+
+    pub async fn grant(
+        &self,
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        permission: &str,
+        granted_by: Uuid,
+    ) -> Result<StaffPermission, CustomErrors> {
+        sqlx::query_as::<_, StaffPermission>(
+            "INSERT INTO staff_permissions (user_id, permission, granted_by)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, permission) DO UPDATE SET granted_by = $3, granted_at = CURRENT_TIMESTAMP
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(permission)
+        .bind(granted_by)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))
+    }
+
+    pub async fn revoke(
+        &self,
+        pool: &Pool<Postgres>,
+        user_id: Uuid,
+        permission: &str,
+    ) -> Result<(), CustomErrors> {
+        let result =
+            sqlx::query("DELETE FROM staff_permissions WHERE user_id = $1 AND permission = $2")
+                .bind(user_id)
+                .bind(permission)
+                .execute(pool)
+                .await
+                .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(CustomErrors::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use argon2::{
     password_hash::{rand_core::OsRng, SaltString},
     Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
 };
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Utc};
 use derive_more::Display;
 use serde::Serialize;
 use serde_json::{json, Value};
@@ -14,6 +14,10 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
+    shared::{
+        domain_layer::outbox_event::{OutboxEvent, UserSuspended, USER_EVENTS_TOPIC},
+        infrastructure_layer::outbox_repository::OutboxRepository,
+    },
     user::domain_layer::user::{StaffUser, StaffUserFullNames, UserLevel, UserStatus},
     AppState,
 };
@@ -24,6 +28,10 @@ pub enum CustomErrors {
     DatabaseError(String),
     #[display(fmt = "Not authorized")]
     NotAuthorized,
+    #[display(fmt = "User not found")]
+    NotFound,
+    #[display(fmt = "User was modified by someone else")]
+    PreconditionFailed,
 }
 
 impl ResponseError for CustomErrors {}
@@ -36,9 +44,10 @@ impl UserRepository {
     }
 
     pub async fn get_all(&self, state: Arc<AppState>) -> Result<Vec<StaffUser>, CustomErrors> {
-        let records = sqlx::query_as::<_, StaffUser>("SELECT * FROM staff_users")
-            .fetch_all(&state.db)
-            .await;
+        let records =
+            sqlx::query_as::<_, StaffUser>("SELECT * FROM staff_users WHERE deleted_at IS NULL")
+                .fetch_all(&state.db)
+                .await;
 
         match records {
             Ok(users) => Ok(users),
@@ -50,10 +59,11 @@ impl UserRepository {
         &self,
         state: Arc<AppState>,
     ) -> Result<Vec<StaffUserFullNames>, CustomErrors> {
-        let records =
-            sqlx::query_as::<_, StaffUserFullNames>("SELECT user_id, name FROM staff_users")
-                .fetch_all(&state.db)
-                .await;
+        let records = sqlx::query_as::<_, StaffUserFullNames>(
+            "SELECT user_id, name FROM staff_users WHERE deleted_at IS NULL",
+        )
+        .fetch_all(&state.db)
+        .await;
 
         match records {
             Ok(users) => Ok(users),
@@ -66,10 +76,12 @@ impl UserRepository {
         state: Arc<AppState>,
         user_id: Uuid,
     ) -> Result<StaffUser, CustomErrors> {
-        let record = sqlx::query_as::<_, StaffUser>("SELECT * FROM staff_users WHERE user_id = $1")
-            .bind(user_id)
-            .fetch_one(&state.db)
-            .await;
+        let record = sqlx::query_as::<_, StaffUser>(
+            "SELECT * FROM staff_users WHERE user_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&state.db)
+        .await;
         match record {
             Ok(user) => Ok(user),
             Err(e) => Err(CustomErrors::DatabaseError(e.to_string())),
@@ -86,7 +98,7 @@ impl UserRepository {
         let status = user.status.unwrap_or(UserStatus::Active);
         let a_created = user
             .a_created
-            .unwrap_or_else(|| NaiveDateTime::from_timestamp(0, 0));
+            .unwrap_or_else(|| DateTime::UNIX_EPOCH.naive_utc());
 
         // Hash the password
         let argon2 = Argon2::default();
@@ -97,14 +109,15 @@ impl UserRepository {
             .unwrap();
 
         let record = sqlx::query_as::<_, StaffUser>(
-            "INSERT INTO staff_users (user_id, name, username, mob_phone, passwd, acc_level, status, a_created) 
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) 
+            "INSERT INTO staff_users (user_id, name, username, mob_phone, email, passwd, acc_level, status, a_created)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
              RETURNING *",
         )
         .bind(user_id)
         .bind(user.name)
         .bind(user.username)
         .bind(user.mob_phone)
+        .bind(user.email)
         .bind(password_hash.to_string())
         .bind(acc_level)
         .bind(status)
@@ -138,13 +151,33 @@ impl UserRepository {
         }
     }
 
+    /// Updates a user only if it still has the `updated_at` the caller last saw.
+    /// `expected_updated_at = None` skips the check (`If-Match: *`).
     pub async fn update(
         &self,
         state: Arc<AppState>,
         user: StaffUser,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<StaffUser, CustomErrors> {
+        let mut tx = state
+            .db
+            .begin()
+            .await
+            .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
+        // Locked so that a concurrent update cannot hide a suspension.
+        let previous_status = sqlx::query_scalar::<_, UserStatus>(
+            "SELECT status FROM staff_users WHERE user_id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(user.user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+
         let record = sqlx::query_as::<_, StaffUser>(
-            "UPDATE staff_users SET name = $1, username = $2, mob_phone = $3, passwd = $4, acc_level = $5, status = $6, a_created = $7 WHERE user_id = $8 RETURNING *",
+            "UPDATE staff_users SET name = $1, username = $2, mob_phone = $3, passwd = $4, acc_level = $5, status = $6, a_created = $7, email = $10, updated_at = CURRENT_TIMESTAMP
+             WHERE user_id = $8 AND deleted_at IS NULL AND ($9::timestamptz IS NULL OR updated_at = $9)
+             RETURNING *",
         )
         .bind(user.name)
         .bind(user.username)
@@ -154,35 +187,94 @@ impl UserRepository {
         .bind(user.status)
         .bind(user.a_created)
         .bind(user.user_id)
-        .fetch_one(&state.db)
+        .bind(expected_updated_at)
+        .bind(user.email)
+        .fetch_optional(&mut *tx)
         .await;
         match record {
-            Ok(user) => Ok(user),
+            Ok(Some(updated)) => {
+                let suspended = matches!(updated.status, Some(UserStatus::Suspended))
+                    && !matches!(previous_status, Some(UserStatus::Suspended));
+                if let (true, Some(user_id)) = (suspended, updated.user_id) {
+                    let event = UserSuspended {
+                        user_id: user_id.to_string(),
+                    };
+                    OutboxRepository::enqueue(
+                        &mut tx,
+                        &OutboxEvent::new(
+                            USER_EVENTS_TOPIC,
+                            "staff_user",
+                            user_id,
+                            "UserSuspended",
+                            &event,
+                        ),
+                    )
+                    .await
+                    .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+                }
+                tx.commit()
+                    .await
+                    .map_err(|e| CustomErrors::DatabaseError(e.to_string()))?;
+                Ok(updated)
+            }
+            Ok(None) => match user.user_id {
+                Some(user_id) => match self.get_by_id(state, user_id).await {
+                    Ok(_) => Err(CustomErrors::PreconditionFailed),
+                    Err(_) => Err(CustomErrors::NotFound),
+                },
+                None => Err(CustomErrors::NotFound),
+            },
             Err(e) => Err(CustomErrors::DatabaseError(e.to_string())),
         }
     }
 
+    /// Soft-deletes a user; the row stays until the retention purge removes it.
     pub async fn delete(&self, state: Arc<AppState>, user_id: Uuid) -> Result<(), CustomErrors> {
-        let record = sqlx::query("DELETE FROM staff_users WHERE user_id = $1")
-            .bind(user_id)
-            .execute(&state.db)
-            .await;
+        let record = sqlx::query(
+            "UPDATE staff_users SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+             WHERE user_id = $1 AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&state.db)
+        .await;
         match record {
+            Ok(result) if result.rows_affected() == 0 => Err(CustomErrors::NotFound),
             Ok(_) => Ok(()),
             Err(e) => Err(CustomErrors::DatabaseError(e.to_string())),
         }
     }
 
+    pub async fn restore(
+        &self,
+        state: Arc<AppState>,
+        user_id: Uuid,
+    ) -> Result<StaffUser, CustomErrors> {
+        let record = sqlx::query_as::<_, StaffUser>(
+            "UPDATE staff_users SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE user_id = $1 AND deleted_at IS NOT NULL
+             RETURNING *",
+        )
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await;
+        match record {
+            Ok(Some(user)) => Ok(user),
+            Ok(None) => Err(CustomErrors::NotFound),
+            Err(e) => Err(CustomErrors::DatabaseError(e.to_string())),
+        }
+    }
+
     pub async fn login(
         &self,
         state: Arc<AppState>,
         user: StaffUser,
     ) -> Result<StaffUser, CustomErrors> {
-        let record =
-            sqlx::query_as::<_, StaffUser>("SELECT * FROM staff_users WHERE username = $1")
-                .bind(&user.username)
-                .fetch_one(&state.db)
-                .await;
+        let record = sqlx::query_as::<_, StaffUser>(
+            "SELECT * FROM staff_users WHERE username = $1 AND deleted_at IS NULL",
+        )
+        .bind(&user.username)
+        .fetch_one(&state.db)
+        .await;
         match record {
             Ok(user_db) => {
                 let parsed_hash = PasswordHash::new(&user_db.passwd).unwrap();
@@ -194,7 +286,7 @@ impl UserRepository {
                     _ => Err(CustomErrors::NotAuthorized),
                 }
             }
-            Err(e) => Err(CustomErrors::NotAuthorized),
+            Err(_) => Err(CustomErrors::NotAuthorized),
         }
     }
 }
@@ -1,6 +1,10 @@
-use actix_web::web;
+use actix_web::{guard, web};
 
-use crate::user::application_layer::user_service;
+use crate::properties::application_layer::properties_service;
+use crate::shared::{
+    application_layer::branch_service, infrastructure_layer::idempotency_repo::Idempotency,
+};
+use crate::user::application_layer::{permission_service, user_service};
 
 pub fn user_configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -10,9 +14,50 @@ pub fn user_configure_routes(cfg: &mut web::ServiceConfig) {
                 "/byuserid/{user_id}",
                 web::get().to(user_service::get_user_by_id),
             )
-            .route("", web::post().to(user_service::register_user))
+            .service(
+                web::resource("")
+                    .guard(guard::Post())
+                    .wrap(Idempotency)
+                    .route(web::post().to(user_service::register_user)),
+            )
             .route("", web::put().to(user_service::update_user))
             .route("/{user_id}", web::delete().to(user_service::delete_user))
+            .route(
+                "/{user_id}/restore",
+                web::post().to(user_service::restore_user),
+            )
+            .route(
+                "/{user_id}/permissions",
+                web::get().to(permission_service::get_user_permissions),
+            )
+            .route(
+                "/{user_id}/permissions/{permission}",
+                web::put().to(permission_service::grant_permission),
+            )
+            .route(
+                "/{user_id}/permissions/{permission}",
+                web::delete().to(permission_service::revoke_permission),
+            )
+            .route(
+                "/{user_id}/branches",
+                web::get().to(branch_service::get_staff_branches),
+            )
+            .route(
+                "/{user_id}/branches/{branch_id}",
+                web::put().to(branch_service::grant_staff_branch),
+            )
+            .route(
+                "/{user_id}/branches/{branch_id}",
+                web::delete().to(branch_service::revoke_staff_branch),
+            )
+            .route(
+                "/{user_id}/properties",
+                web::get().to(properties_service::get_by_staff),
+            )
+            .route(
+                "/{user_id}/transfer",
+                web::post().to(branch_service::transfer_user),
+            )
             .route("/login", web::post().to(user_service::login_user))
             .route("/refresh", web::post().to(user_service::refresh_token))
             .route("/logout", web::post().to(user_service::logout_user))